@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Write};
+use std::path::Path;
+use uuid::Uuid;
+
+/// Windows error codes returned when antivirus software blocks a file
+const ERROR_VIRUS_INFECTED: i32 = 225;
+const ERROR_VIRUS_DELETED: i32 = 226;
+
+/// Why a folder can't be written to
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteBlockReason {
+    NotFound,
+    NotADirectory,
+    /// Folder or volume is read-only
+    ReadOnly,
+    /// Windows Controlled Folder Access (ransomware protection) denied the app
+    ControlledFolderAccess,
+    /// Antivirus software blocked or removed the file
+    Antivirus,
+    /// The user lacks write permission on the folder
+    PermissionDenied,
+    Other,
+}
+
+/// Result of a write probe
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WriteAccessCheck {
+    pub writable: bool,
+    pub reason: Option<WriteBlockReason>,
+    /// OS error message when the probe failed
+    pub details: Option<String>,
+}
+
+/// Check that the Mods folder accepts new files before installing anything
+/// Creates and deletes a tiny probe file, classifying the failure if that is refused
+#[tauri::command]
+pub fn check_mods_writable(path: String) -> WriteAccessCheck {
+    let dir = Path::new(&path);
+
+    let blocked = |reason, details: Option<String>| WriteAccessCheck {
+        writable: false,
+        reason: Some(reason),
+        details,
+    };
+
+    match fs::metadata(dir) {
+        Ok(metadata) if !metadata.is_dir() => {
+            return blocked(WriteBlockReason::NotADirectory, None)
+        }
+        Ok(_) => {}
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return blocked(WriteBlockReason::NotFound, None)
+        }
+        Err(e) => return blocked(classify_write_error(&e, dir), Some(e.to_string())),
+    }
+
+    match probe_write(dir) {
+        Ok(()) => WriteAccessCheck {
+            writable: true,
+            reason: None,
+            details: None,
+        },
+        Err(e) => blocked(classify_write_error(&e, dir), Some(e.to_string())),
+    }
+}
+
+fn probe_write(dir: &Path) -> io::Result<()> {
+    let probe = dir.join(format!(".simsforge_write_test_{}", Uuid::new_v4()));
+    let written = File::create(&probe).and_then(|mut file| {
+        file.write_all(b"simsforge")?;
+        file.sync_all()
+    });
+    // Remove the probe even when writing to it failed halfway
+    let removed = fs::remove_file(&probe);
+
+    written?;
+    match removed {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Turn the probe's I/O error into a reason the UI can explain
+fn classify_write_error(error: &io::Error, dir: &Path) -> WriteBlockReason {
+    if matches!(
+        error.raw_os_error(),
+        Some(ERROR_VIRUS_INFECTED | ERROR_VIRUS_DELETED)
+    ) && cfg!(windows)
+    {
+        return WriteBlockReason::Antivirus;
+    }
+
+    match error.kind() {
+        ErrorKind::ReadOnlyFilesystem => WriteBlockReason::ReadOnly,
+        ErrorKind::NotFound => WriteBlockReason::NotFound,
+        ErrorKind::PermissionDenied => {
+            let read_only = fs::metadata(dir)
+                .map(|m| m.permissions().readonly())
+                .unwrap_or(false);
+            if read_only {
+                WriteBlockReason::ReadOnly
+            } else if cfg!(windows) && is_protected_folder(dir) {
+                // CFA reports a plain access denied, but only ever inside protected folders
+                WriteBlockReason::ControlledFolderAccess
+            } else {
+                WriteBlockReason::PermissionDenied
+            }
+        }
+        _ => WriteBlockReason::Other,
+    }
+}
+
+/// Whether `dir` is inside a folder Controlled Folder Access protects by default
+/// The game's Mods folder lives under Documents, which is always protected
+fn is_protected_folder(dir: &Path) -> bool {
+    dir.components().any(|component| {
+        let name = component.as_os_str().to_string_lossy();
+        ["Documents", "Pictures", "Desktop", "OneDrive"]
+            .iter()
+            .any(|protected| protected.eq_ignore_ascii_case(&name))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn check(path: &Path) -> WriteAccessCheck {
+        check_mods_writable(path.to_string_lossy().to_string())
+    }
+
+    #[test]
+    fn writable_folder_passes_without_leftovers() {
+        let dir = tempdir().unwrap();
+
+        let result = check(dir.path());
+
+        assert!(result.writable);
+        assert_eq!(result.reason, None);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn read_only_folder_is_reported() {
+        let dir = tempdir().unwrap();
+        let mods = dir.path().join("Mods");
+        fs::create_dir(&mods).unwrap();
+        let mut permissions = fs::metadata(&mods).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&mods, permissions).unwrap();
+
+        // Privileged users (root, CI containers) ignore permission bits
+        let privileged = File::create(mods.join("probe")).is_ok();
+        let result = check(&mods);
+
+        let mut permissions = fs::metadata(&mods).unwrap().permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        fs::set_permissions(&mods, permissions).unwrap();
+
+        if privileged {
+            assert!(result.writable);
+        } else {
+            assert!(!result.writable);
+            assert_eq!(result.reason, Some(WriteBlockReason::ReadOnly));
+            assert!(result.details.is_some());
+        }
+    }
+
+    #[test]
+    fn classifies_missing_and_denied_folders() {
+        let dir = tempdir().unwrap();
+
+        let missing = check(&dir.path().join("missing"));
+        assert_eq!(missing.reason, Some(WriteBlockReason::NotFound));
+
+        let file = dir.path().join("file.txt");
+        fs::write(&file, b"x").unwrap();
+        assert_eq!(check(&file).reason, Some(WriteBlockReason::NotADirectory));
+
+        let denied = io::Error::from(ErrorKind::PermissionDenied);
+        assert_eq!(
+            classify_write_error(&denied, dir.path()),
+            WriteBlockReason::PermissionDenied
+        );
+        assert_eq!(
+            classify_write_error(&io::Error::from(ErrorKind::ReadOnlyFilesystem), dir.path()),
+            WriteBlockReason::ReadOnly
+        );
+        assert!(is_protected_folder(Path::new(
+            "C:/Users/me/Documents/Electronic Arts/The Sims 4/Mods"
+        )));
+        assert!(!is_protected_folder(Path::new("D:/Games/Mods")));
+    }
+}
@@ -0,0 +1,458 @@
+use crate::manifest::{self, SeenArchive};
+use crate::replace::same_file;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{copy, BufWriter};
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Size and file count of an archive, read from its central directory
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ArchiveInspection {
+    /// Total size of all entries once extracted
+    pub uncompressed_bytes: u64,
+    /// Number of file entries (directories excluded)
+    pub file_count: usize,
+    /// Relative paths of all file entries
+    pub files: Vec<String>,
+}
+
+/// Everything the pre-install confirmation dialog needs, computed in one call
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExtractionPlan {
+    pub uncompressed_bytes: u64,
+    pub file_count: usize,
+    /// Free space on the volume holding the destination
+    pub available_bytes: u64,
+    /// Whether the extracted content fits in the available space
+    pub will_fit: bool,
+    /// Archive entries that would overwrite files already present in the destination
+    pub conflicts_with_existing: Vec<String>,
+}
+
+/// Compression used when repacking an archive
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RepackMethod {
+    /// Readable by every tool, levels 0-9
+    #[default]
+    Deflate,
+    /// Smaller and faster to extract, levels 1-22
+    Zstd,
+}
+
+/// Sizes before and after repacking
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RepackReport {
+    pub input_bytes: u64,
+    pub output_bytes: u64,
+    /// Number of entries (files and directories) written
+    pub entry_count: usize,
+}
+
+/// Read entry sizes and names from the central directory without decompressing anything
+pub fn inspect_archive(zip_path: &Path) -> Result<ArchiveInspection, String> {
+    let file = File::open(zip_path).map_err(|e| format!("Failed to open ZIP: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Invalid ZIP file: {}", e))?;
+
+    let mut uncompressed_bytes: u64 = 0;
+    let mut files = Vec::new();
+
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index_raw(i)
+            .map_err(|e| format!("Failed to read ZIP entry: {}", e))?;
+
+        if entry.is_dir() {
+            continue;
+        }
+
+        uncompressed_bytes = uncompressed_bytes.saturating_add(entry.size());
+        files.push(entry.name().to_string());
+    }
+
+    Ok(ArchiveInspection {
+        uncompressed_bytes,
+        file_count: files.len(),
+        files,
+    })
+}
+
+/// Free space available to the current user on the volume holding `path`
+/// The destination may not exist yet, so the closest existing ancestor is queried
+pub fn available_space(path: &Path) -> Result<u64, String> {
+    let existing = path
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .ok_or_else(|| format!("No existing parent directory for {}", path.display()))?;
+
+    fs4::available_space(existing)
+        .map_err(|e| format!("Failed to get free space for {}: {}", path.display(), e))
+}
+
+/// Archive entries whose output path already exists in `dest_dir`
+/// Entry names that would escape the destination are ignored here (extraction rejects them)
+pub fn preflight_conflicts(files: &[String], dest_dir: &Path) -> Vec<String> {
+    files
+        .iter()
+        .filter(|name| {
+            safe_relative_path(name)
+                .map(|relative| dest_dir.join(relative).exists())
+                .unwrap_or(false)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Convert an archive entry name into a relative path that stays inside the destination
+pub fn safe_relative_path(name: &str) -> Option<PathBuf> {
+    // UNC paths (\\server\share)
+    if name.starts_with("\\\\") || name.starts_with("//") {
+        return None;
+    }
+    let mut relative = PathBuf::new();
+
+    for component in name.split(['/', '\\']) {
+        match component {
+            "" | "." => continue,
+            ".." => return None,
+            // Drive letters (C:, C:foo), pushing one replaces the whole path on Windows
+            // Other colons are left to the extraction's name sanitizer
+            c if is_drive_prefix(c) => return None,
+            c => relative.push(c),
+        }
+    }
+
+    (!relative.as_os_str().is_empty()).then_some(relative)
+}
+
+fn is_drive_prefix(component: &str) -> bool {
+    let bytes = component.as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+/// Compute the footprint of an archive and check it against the destination before extracting
+#[tauri::command]
+pub fn plan_extraction(zip_path: String, dest_dir: String) -> Result<ExtractionPlan, String> {
+    let dest_dir = Path::new(&dest_dir);
+    let available_bytes = available_space(dest_dir)?;
+
+    build_plan(Path::new(&zip_path), dest_dir, available_bytes)
+}
+
+/// Rewrite an archive with the chosen compression, entries sorted by name
+/// Timestamps and permissions are carried over, so the same input always gives the same bytes
+#[tauri::command(async)]
+pub fn repack_archive(
+    input: String,
+    output: String,
+    compression_level: Option<i32>,
+    method: Option<RepackMethod>,
+) -> Result<RepackReport, String> {
+    repack(
+        Path::new(&input),
+        Path::new(&output),
+        method.unwrap_or_default(),
+        compression_level,
+    )
+}
+
+pub fn repack(
+    input: &Path,
+    output: &Path,
+    method: RepackMethod,
+    compression_level: Option<i32>,
+) -> Result<RepackReport, String> {
+    if same_file(input, output) {
+        return Err("Output must be a different file than the input".to_string());
+    }
+
+    let file = File::open(input).map_err(|e| format!("Failed to open ZIP: {}", e))?;
+    let input_bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Invalid ZIP file: {}", e))?;
+
+    // Central directory order depends on the tool that built the archive, names don't
+    let mut order: Vec<(String, usize)> = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index_raw(i)
+            .map_err(|e| format!("Failed to read ZIP entry: {}", e))?;
+        order.push((entry.name().to_string(), i));
+    }
+    order.sort();
+
+    let output_file = File::create(output)
+        .map_err(|e| format!("Failed to create {}: {}", output.display(), e))?;
+    let mut writer = ZipWriter::new(BufWriter::new(output_file));
+    let compression = match method {
+        RepackMethod::Deflate => CompressionMethod::Deflated,
+        RepackMethod::Zstd => CompressionMethod::Zstd,
+    };
+
+    for &(_, index) in &order {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| format!("Failed to read ZIP entry: {}", e))?;
+        let name = entry.name().to_string();
+
+        let mut options = FileOptions::default()
+            .compression_method(compression)
+            .compression_level(compression_level)
+            .last_modified_time(entry.last_modified())
+            .large_file(entry.size() >= u32::MAX as u64);
+        if let Some(mode) = entry.unix_mode() {
+            options = options.unix_permissions(mode);
+        }
+
+        let written = if entry.is_dir() {
+            writer.add_directory(name.as_str(), options)
+        } else {
+            writer.start_file(name.as_str(), options).and_then(|_| {
+                copy(&mut entry, &mut writer)
+                    .map(|_| ())
+                    .map_err(Into::into)
+            })
+        };
+        written.map_err(|e| format!("Failed to write {}: {}", name, e))?;
+    }
+
+    writer
+        .finish()
+        .map_err(|e| format!("Failed to finish {}: {}", output.display(), e))?;
+
+    let output_bytes = std::fs::metadata(output).map(|m| m.len()).unwrap_or(0);
+    Ok(RepackReport {
+        input_bytes,
+        output_bytes,
+        entry_count: order.len(),
+    })
+}
+
+/// Identify an archive by its content listing instead of its bytes
+/// Hashes the sorted (name, CRC-32, size) of every file from the central directory, so it is
+/// near-instant on huge archives and unaffected by recompression or timestamp changes
+#[tauri::command]
+pub fn archive_fingerprint(zip_path: String) -> Result<String, String> {
+    fingerprint(Path::new(&zip_path))
+}
+
+/// Fingerprint an archive and remember it, returning where it was first seen when it is a
+/// download the user already has
+#[tauri::command]
+pub fn check_seen_archive(
+    app_handle: tauri::AppHandle,
+    zip_path: String,
+) -> Result<Option<SeenArchive>, String> {
+    let fingerprint = fingerprint(Path::new(&zip_path))?;
+    manifest::open_app_manifest(&app_handle)?.record_archive(&fingerprint, &zip_path)
+}
+
+pub fn fingerprint(zip_path: &Path) -> Result<String, String> {
+    let file = File::open(zip_path).map_err(|e| format!("Failed to open ZIP: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Invalid ZIP file: {}", e))?;
+
+    let mut entries = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index_raw(i)
+            .map_err(|e| format!("Failed to read ZIP entry: {}", e))?;
+        if !entry.is_dir() {
+            entries.push((entry.name().to_string(), entry.crc32(), entry.size()));
+        }
+    }
+    entries.sort();
+
+    let mut hasher = Sha256::new();
+    for (name, crc32, size) in entries {
+        // The separator keeps ("ab", "c") and ("a", "bc") apart
+        hasher.update(name.as_bytes());
+        hasher.update([0]);
+        hasher.update(crc32.to_le_bytes());
+        hasher.update(size.to_le_bytes());
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn build_plan(
+    zip_path: &Path,
+    dest_dir: &Path,
+    available_bytes: u64,
+) -> Result<ExtractionPlan, String> {
+    let inspection = inspect_archive(zip_path)?;
+
+    Ok(ExtractionPlan {
+        uncompressed_bytes: inspection.uncompressed_bytes,
+        file_count: inspection.file_count,
+        available_bytes,
+        will_fit: inspection.uncompressed_bytes <= available_bytes,
+        conflicts_with_existing: preflight_conflicts(&inspection.files, dest_dir),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::write_zip;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn will_fit_follows_available_space() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("mod.zip"),
+            &[
+                ("Mod/", b""),
+                ("Mod/a.package", &[1u8; 600]),
+                ("Mod/b.package", &[2u8; 400]),
+            ],
+        );
+        let dest = dir.path().join("Mods");
+
+        let plan = build_plan(Path::new(&zip_path), &dest, 1000).unwrap();
+        assert_eq!(plan.uncompressed_bytes, 1000);
+        assert_eq!(plan.file_count, 2);
+        assert!(plan.will_fit);
+
+        let plan = build_plan(Path::new(&zip_path), &dest, 999).unwrap();
+        assert!(!plan.will_fit);
+    }
+
+    #[test]
+    fn reports_files_already_in_destination() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("mod.zip"),
+            &[("Mod/a.package", b"new"), ("Mod/b.package", b"new")],
+        );
+        let dest = dir.path().join("Mods");
+        fs::create_dir_all(dest.join("Mod")).unwrap();
+        fs::write(dest.join("Mod/b.package"), b"old").unwrap();
+
+        let plan = plan_extraction(zip_path, dest.to_string_lossy().to_string()).unwrap();
+
+        assert_eq!(plan.conflicts_with_existing, vec!["Mod/b.package"]);
+        assert!(plan.available_bytes > 0);
+    }
+
+    #[test]
+    fn repacking_is_byte_identical_and_sorted() {
+        let dir = tempdir().unwrap();
+        let padding = vec![b'x'; 64 * 1024];
+        let zip_path = write_zip(
+            &dir.path().join("bloated.zip"),
+            &[
+                ("Mod/z.package", &padding),
+                ("Mod/", b""),
+                ("Mod/a.package", b"DBPF"),
+                ("readme.txt", b"hello"),
+            ],
+        );
+
+        let first = dir.path().join("first.zip");
+        let second = dir.path().join("second.zip");
+        for output in [&first, &second] {
+            let report =
+                repack(Path::new(&zip_path), output, RepackMethod::Zstd, Some(19)).unwrap();
+            assert_eq!(report.entry_count, 4);
+            assert!(report.output_bytes > 0);
+        }
+        assert_eq!(fs::read(&first).unwrap(), fs::read(&second).unwrap());
+
+        let mut repacked = ZipArchive::new(File::open(&first).unwrap()).unwrap();
+        let names: Vec<String> = (0..repacked.len())
+            .map(|i| repacked.by_index(i).unwrap().name().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["Mod/", "Mod/a.package", "Mod/z.package", "readme.txt"]
+        );
+        let mut content = Vec::new();
+        copy(
+            &mut repacked.by_name("Mod/z.package").unwrap(),
+            &mut content,
+        )
+        .unwrap();
+        assert_eq!(content, padding);
+
+        let deflated = dir.path().join("deflated.zip");
+        let report = repack(
+            Path::new(&zip_path),
+            &deflated,
+            RepackMethod::Deflate,
+            Some(9),
+        )
+        .unwrap();
+        assert_eq!(report.input_bytes, fs::metadata(&zip_path).unwrap().len());
+        assert_eq!(report.output_bytes, fs::metadata(&deflated).unwrap().len());
+        assert!(repack(
+            Path::new(&zip_path),
+            Path::new(&zip_path),
+            RepackMethod::Deflate,
+            None
+        )
+        .is_err());
+        // The same archive reached through another path is refused too, and left intact
+        let before = fs::read(&zip_path).unwrap();
+        let other_path = dir
+            .path()
+            .join(".")
+            .join(Path::new(&zip_path).file_name().unwrap());
+        assert!(repack(
+            Path::new(&zip_path),
+            &other_path,
+            RepackMethod::Deflate,
+            None
+        )
+        .is_err());
+        assert_eq!(fs::read(&zip_path).unwrap(), before);
+    }
+
+    #[test]
+    fn fingerprint_follows_content_not_packaging() {
+        let dir = tempdir().unwrap();
+        let entries: [(&str, &[u8]); 3] = [
+            ("Mod/", b""),
+            ("Mod/a.package", b"DBPF first"),
+            ("Mod/b.package", b"DBPF second"),
+        ];
+        let original = write_zip(&dir.path().join("original.zip"), &entries);
+        let copy = dir.path().join("copy.zip");
+        fs::copy(&original, &copy).unwrap();
+        let repacked = dir.path().join("repacked.zip");
+        repack(Path::new(&original), &repacked, RepackMethod::Zstd, Some(3)).unwrap();
+
+        let expected = fingerprint(Path::new(&original)).unwrap();
+        assert_eq!(expected.len(), 64);
+        assert_eq!(fingerprint(&copy).unwrap(), expected);
+        assert_eq!(fingerprint(&repacked).unwrap(), expected);
+
+        let changed = write_zip(
+            &dir.path().join("changed.zip"),
+            &[
+                ("Mod/a.package", b"DBPF first"),
+                ("Mod/b.package", b"DBPF other!"),
+            ],
+        );
+        assert_ne!(archive_fingerprint(changed).unwrap(), expected);
+    }
+
+    #[test]
+    fn rejects_entry_names_escaping_destination() {
+        assert_eq!(
+            safe_relative_path("Mod\\sub/./a.package"),
+            Some(PathBuf::from("Mod").join("sub").join("a.package"))
+        );
+        assert_eq!(safe_relative_path("../evil.package"), None);
+        assert_eq!(safe_relative_path("C:/Windows/evil.dll"), None);
+        assert_eq!(safe_relative_path("Mod/c:evil.dll"), None);
+        assert_eq!(safe_relative_path("\\\\server\\share\\evil.dll"), None);
+        assert_eq!(safe_relative_path("/"), None);
+        assert_eq!(
+            safe_relative_path("Mods/Note: read me.txt"),
+            Some(PathBuf::from("Mods").join("Note: read me.txt"))
+        );
+    }
+}
@@ -0,0 +1,420 @@
+use crate::replace::{same_file, temp_path_for};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+/// First bytes of the first volume of a WinZip / Info-ZIP split or spanned archive
+const SPLIT_MARKERS: [&[u8]; 2] = [b"PK\x07\x08", b"PK00"];
+
+const EOCD_SIGNATURE: &[u8] = b"PK\x05\x06";
+const CENTRAL_HEADER_SIGNATURE: &[u8] = b"PK\x01\x02";
+const EOCD_SIZE: usize = 22;
+const CENTRAL_HEADER_SIZE: usize = 46;
+
+/// The end of central directory record is followed by at most a 64 KiB comment
+const MAX_EOCD_SEARCH: u64 = EOCD_SIZE as u64 + u16::MAX as u64;
+
+/// Single archive assembled from its parts
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JoinedArchive {
+    pub path: String,
+    pub part_count: usize,
+    pub bytes: u64,
+    pub entry_count: usize,
+}
+
+/// Find every part of the multi-part set `path` belongs to, in order
+/// Recognizes `name.zip.001`, `name.z01` ... `name.zip` and `name.part1.zip`
+/// Returns None when `path` is not part of a set of at least two files
+#[tauri::command]
+pub fn detect_archive_parts(path: String) -> Option<Vec<String>> {
+    let parts = find_parts(Path::new(&path))?;
+    Some(
+        parts
+            .into_iter()
+            .map(|part| part.to_string_lossy().to_string())
+            .collect(),
+    )
+}
+
+/// Assemble the parts (in the order `detect_archive_parts` returns them) into one archive
+/// that `extract_zip` can open; the output is only written once it opens and every entry reads back
+#[tauri::command(async)]
+pub fn join_archive_parts(parts: Vec<String>, output: String) -> Result<JoinedArchive, String> {
+    let parts: Vec<PathBuf> = parts.iter().map(PathBuf::from).collect();
+    join_parts(&parts, Path::new(&output))
+}
+
+pub fn join_parts(parts: &[PathBuf], output: &Path) -> Result<JoinedArchive, String> {
+    if parts.len() < 2 {
+        return Err("At least two parts are needed".to_string());
+    }
+    if parts.iter().any(|part| same_file(part, output)) {
+        return Err("Output must be a different file than the parts".to_string());
+    }
+
+    let temp = temp_path_for(output)?;
+    let entry_count = match assemble(parts, &temp).and_then(|_| verify(&temp)) {
+        Ok(count) => count,
+        Err(e) => {
+            let _ = fs::remove_file(&temp);
+            return Err(e);
+        }
+    };
+    fs::rename(&temp, output)
+        .map_err(|e| format!("Failed to move joined archive into place: {}", e))?;
+
+    Ok(JoinedArchive {
+        path: output.to_string_lossy().to_string(),
+        part_count: parts.len(),
+        bytes: fs::metadata(output).map(|m| m.len()).unwrap_or(0),
+        entry_count,
+    })
+}
+
+fn find_parts(path: &Path) -> Option<Vec<PathBuf>> {
+    let dir = path.parent()?;
+    let name = path.file_name()?.to_str()?;
+    let (stem, extension) = name.rsplit_once('.')?;
+
+    let parts = if is_number(extension) && extension.len() >= 3 {
+        // name.zip.001, name.zip.002, ...
+        let width = extension.len();
+        numbered_parts(dir, |n| format!("{}.{:0width$}", stem, n))
+    } else if let Some((base, prefix, width)) = part_suffix(stem) {
+        // name.part1.zip, name.part2.zip, ... (possibly zero padded: part01)
+        numbered_parts(dir, |n| {
+            format!("{}.{}{:0width$}.{}", base, prefix, n, extension)
+        })
+    } else if is_spanned_extension(extension) {
+        // name.z01, name.z02, ..., then name.zip holding the central directory
+        let (z, zip) = if extension.starts_with('Z') {
+            ("Z", "ZIP")
+        } else {
+            ("z", "zip")
+        };
+        let mut parts = numbered_parts(dir, |n| format!("{}.{}{:02}", stem, z, n));
+        let last = dir.join(format!("{}.{}", stem, zip));
+        if parts.is_empty() || !last.is_file() {
+            return None;
+        }
+        parts.push(last);
+        parts
+    } else {
+        return None;
+    };
+
+    (parts.len() >= 2).then_some(parts)
+}
+
+fn is_number(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Split `name.part01` into the base name, the `part` prefix as written and the number width
+fn part_suffix(stem: &str) -> Option<(&str, &str, usize)> {
+    let (base, part) = stem.rsplit_once('.')?;
+    let prefix = part.get(..4).filter(|p| p.eq_ignore_ascii_case("part"))?;
+    let number = &part[4..];
+    is_number(number).then_some((base, prefix, number.len()))
+}
+
+/// `z01`..`z99` and the final `.zip` of a spanned set
+fn is_spanned_extension(extension: &str) -> bool {
+    extension.eq_ignore_ascii_case("zip")
+        || (extension.len() == 3 && extension.starts_with(['z', 'Z']) && is_number(&extension[1..]))
+}
+
+/// Parts numbered from 1 that exist on disk, stopping at the first gap
+fn numbered_parts(dir: &Path, name_for: impl Fn(usize) -> String) -> Vec<PathBuf> {
+    (1..)
+        .map(|n| dir.join(name_for(n)))
+        .take_while(|part| part.is_file())
+        .collect()
+}
+
+/// Concatenate the parts; split archives then get their offsets rebased onto a single disk
+fn assemble(parts: &[PathBuf], dest: &Path) -> Result<(), String> {
+    // Read access too, split archives have their central directory rewritten in place
+    let mut output = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(dest)
+        .map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut written: i64 = 0;
+    let mut disk_starts = Vec::with_capacity(parts.len());
+    let mut split = false;
+
+    for (index, part) in parts.iter().enumerate() {
+        let mut input =
+            File::open(part).map_err(|e| format!("Failed to open {}: {}", part.display(), e))?;
+
+        if index == 0 {
+            let mut marker = Vec::with_capacity(4);
+            (&mut input)
+                .take(4)
+                .read_to_end(&mut marker)
+                .map_err(|e| format!("Failed to read {}: {}", part.display(), e))?;
+            split = SPLIT_MARKERS.contains(&marker.as_slice());
+            if split {
+                // Offsets on the first disk count the marker, which is dropped
+                disk_starts.push(-(marker.len() as i64));
+            } else {
+                output
+                    .write_all(&marker)
+                    .map_err(|e| format!("Failed to write joined archive: {}", e))?;
+                disk_starts.push(0);
+                written += marker.len() as i64;
+            }
+        } else {
+            disk_starts.push(written);
+        }
+
+        written += io::copy(&mut input, &mut output)
+            .map_err(|e| format!("Failed to append {}: {}", part.display(), e))?
+            as i64;
+    }
+
+    if split {
+        rebase_central_directory(&mut output, &disk_starts)?;
+    }
+    output
+        .sync_all()
+        .map_err(|e| format!("Failed to flush joined archive: {}", e))
+}
+
+/// Rewrite the per-disk offsets of a split archive as offsets into the joined file
+fn rebase_central_directory(file: &mut File, disk_starts: &[i64]) -> Result<(), String> {
+    let io_error = |e: io::Error| format!("Failed to rewrite central directory: {}", e);
+    let len = file.metadata().map_err(io_error)?.len();
+    let tail_len = len.min(MAX_EOCD_SEARCH);
+    let mut tail = vec![0u8; tail_len as usize];
+    file.seek(SeekFrom::Start(len - tail_len))
+        .and_then(|_| file.read_exact(&mut tail))
+        .map_err(io_error)?;
+
+    let eocd_index = tail
+        .windows(EOCD_SIGNATURE.len())
+        .rposition(|window| window == EOCD_SIGNATURE)
+        .filter(|&index| index + EOCD_SIZE <= tail.len())
+        .ok_or("Joined archive has no end of central directory")?;
+    let eocd_offset = len - tail_len + eocd_index as u64;
+    let mut eocd = tail[eocd_index..eocd_index + EOCD_SIZE].to_vec();
+
+    let total_entries = read_u16(&eocd, 10);
+    let cd_size = read_u32(&eocd, 12);
+    let cd_offset = read_u32(&eocd, 16);
+    if total_entries == u16::MAX || cd_offset == u32::MAX {
+        return Err("Split ZIP64 archives are not supported".to_string());
+    }
+
+    let cd_start = rebase(disk_starts, read_u16(&eocd, 6), cd_offset)?;
+    let mut central_directory = vec![0u8; cd_size as usize];
+    file.seek(SeekFrom::Start(cd_start as u64))
+        .and_then(|_| file.read_exact(&mut central_directory))
+        .map_err(io_error)?;
+
+    let mut position = 0;
+    for _ in 0..total_entries {
+        let header = central_directory
+            .get(position..position + CENTRAL_HEADER_SIZE)
+            .filter(|header| header.starts_with(CENTRAL_HEADER_SIGNATURE))
+            .ok_or("Corrupt central directory in split archive")?;
+        let local_offset = read_u32(header, 42);
+        if local_offset == u32::MAX {
+            return Err("Split ZIP64 archives are not supported".to_string());
+        }
+        let rebased = rebase(disk_starts, read_u16(header, 34), local_offset)?;
+        let variable_len = read_u16(header, 28) as usize
+            + read_u16(header, 30) as usize
+            + read_u16(header, 32) as usize;
+
+        central_directory[position + 34..position + 36].copy_from_slice(&0u16.to_le_bytes());
+        central_directory[position + 42..position + 46].copy_from_slice(&rebased.to_le_bytes());
+        position += CENTRAL_HEADER_SIZE + variable_len;
+    }
+
+    // Everything now lives on disk 0
+    eocd[4..8].fill(0);
+    eocd[8..10].copy_from_slice(&total_entries.to_le_bytes());
+    eocd[16..20].copy_from_slice(&cd_start.to_le_bytes());
+
+    file.seek(SeekFrom::Start(cd_start as u64))
+        .and_then(|_| file.write_all(&central_directory))
+        .and_then(|_| file.seek(SeekFrom::Start(eocd_offset)))
+        .and_then(|_| file.write_all(&eocd))
+        .map_err(io_error)
+}
+
+/// Offset on `disk` to offset in the joined file
+fn rebase(disk_starts: &[i64], disk: u16, offset: u32) -> Result<u32, String> {
+    let start = disk_starts
+        .get(disk as usize)
+        .ok_or_else(|| format!("Archive needs part {}, which is missing", disk as usize + 1))?;
+    u32::try_from(start + offset as i64)
+        .map_err(|_| "Corrupt offset in split archive central directory".to_string())
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+/// Open the joined archive and read every entry back (checking CRCs), returns the entry count
+fn verify(path: &Path) -> Result<usize, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open joined archive: {}", e))?;
+    let mut archive =
+        ZipArchive::new(file).map_err(|e| format!("Joined archive is not valid: {}", e))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Joined archive is not valid: {}", e))?;
+        io::copy(&mut entry, &mut io::sink())
+            .map_err(|e| format!("Joined archive is not valid: {}", e))?;
+    }
+
+    Ok(archive.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::{extract_archive, ExtractOptions};
+    use crate::test_support::write_zip;
+    use tempfile::tempdir;
+
+    const ENTRIES: [(&str, &[u8]); 3] = [
+        ("Mod/a.package", b"first package"),
+        ("Mod/b.package", b"second package"),
+        ("readme.txt", b"hello"),
+    ];
+
+    fn paths(parts: Vec<String>) -> Vec<PathBuf> {
+        parts.into_iter().map(PathBuf::from).collect()
+    }
+
+    /// Turn a regular archive into a two-disk split archive, cut before the second entry
+    fn split_in_two(zip: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let mut data = zip.to_vec();
+        let eocd = data.len() - EOCD_SIZE;
+        let total = read_u16(&data, eocd + 10) as usize;
+        let cd_offset = read_u32(&data, eocd + 16) as usize;
+
+        let mut position = cd_offset;
+        let mut split = 0;
+        for index in 0..total {
+            let offset = read_u32(&data, position + 42) as usize;
+            if index == 1 {
+                split = offset;
+            }
+            let (disk, rebased) = if index == 0 {
+                (0u16, offset + 4)
+            } else {
+                (1u16, offset - split)
+            };
+            data[position + 34..position + 36].copy_from_slice(&disk.to_le_bytes());
+            data[position + 42..position + 46].copy_from_slice(&(rebased as u32).to_le_bytes());
+            position += CENTRAL_HEADER_SIZE
+                + read_u16(&data, position + 28) as usize
+                + read_u16(&data, position + 30) as usize
+                + read_u16(&data, position + 32) as usize;
+        }
+        data[eocd + 4..eocd + 8].copy_from_slice(&[1, 0, 1, 0]);
+        data[eocd + 16..eocd + 20].copy_from_slice(&((cd_offset - split) as u32).to_le_bytes());
+
+        let mut first = SPLIT_MARKERS[0].to_vec();
+        first.extend_from_slice(&data[..split]);
+        (first, data[split..].to_vec())
+    }
+
+    fn assert_extracts(archive: &Path, dest: &Path) {
+        let report = extract_archive(archive, dest, &ExtractOptions::default(), |_, _| {}).unwrap();
+        assert_eq!(report.files_written, 3);
+        for (name, content) in ENTRIES {
+            assert_eq!(fs::read(dest.join(name)).unwrap(), content);
+        }
+    }
+
+    #[test]
+    fn joins_spanned_two_part_archive() {
+        let dir = tempdir().unwrap();
+        let zip = write_zip(&dir.path().join("source.zip"), &ENTRIES);
+        let (first, second) = split_in_two(&fs::read(zip).unwrap());
+        fs::write(dir.path().join("Mod.z01"), first).unwrap();
+        fs::write(dir.path().join("Mod.zip"), second).unwrap();
+
+        let parts =
+            detect_archive_parts(dir.path().join("Mod.zip").to_string_lossy().to_string()).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert!(parts[0].ends_with("Mod.z01"));
+        assert!(parts[1].ends_with("Mod.zip"));
+
+        let output = dir.path().join("joined.zip");
+        let joined = join_parts(&paths(parts), &output).unwrap();
+        assert_eq!(joined.part_count, 2);
+        assert_eq!(joined.entry_count, 3);
+        assert_eq!(joined.bytes, fs::metadata(&output).unwrap().len());
+
+        assert_extracts(&output, &dir.path().join("out"));
+    }
+
+    #[test]
+    fn joins_numbered_byte_split_archive() {
+        let dir = tempdir().unwrap();
+        let zip = write_zip(&dir.path().join("source.zip"), &ENTRIES);
+        let bytes = fs::read(zip).unwrap();
+        let (first, second) = bytes.split_at(bytes.len() / 2);
+        fs::write(dir.path().join("Mod.zip.001"), first).unwrap();
+        fs::write(dir.path().join("Mod.zip.002"), second).unwrap();
+
+        let parts =
+            detect_archive_parts(dir.path().join("Mod.zip.002").to_string_lossy().to_string())
+                .unwrap();
+        assert!(parts[0].ends_with("Mod.zip.001"));
+
+        let output = dir.path().join("joined.zip");
+        join_parts(&paths(parts), &output).unwrap();
+        assert_eq!(fs::read(&output).unwrap(), bytes);
+        assert_extracts(&output, &dir.path().join("out"));
+    }
+
+    #[test]
+    fn detects_part_numbered_sets_and_rejects_bad_joins() {
+        let dir = tempdir().unwrap();
+        for name in [
+            "Big.part01.zip",
+            "Big.part02.zip",
+            "Big.part03.zip",
+            "Single.zip",
+        ] {
+            fs::write(dir.path().join(name), b"garbage").unwrap();
+        }
+
+        let parts = detect_archive_parts(
+            dir.path()
+                .join("Big.part02.zip")
+                .to_string_lossy()
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(parts.len(), 3);
+        assert!(parts[2].ends_with("Big.part03.zip"));
+        assert!(
+            detect_archive_parts(dir.path().join("Single.zip").to_string_lossy().to_string())
+                .is_none()
+        );
+
+        let output = dir.path().join("joined.zip");
+        assert!(join_parts(&paths(parts), &output).is_err());
+        assert!(!output.exists());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 4);
+    }
+}
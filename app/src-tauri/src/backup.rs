@@ -0,0 +1,956 @@
+use crate::archive::{safe_relative_path, RepackMethod};
+use crate::calculate_file_hash;
+use crate::manifest::{remove_path, unix_now};
+use crate::replace::temp_path_for;
+use crate::tree_hash::{tree_hash, tree_hash_of_listing, TreeHashCache};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{copy, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tauri::Emitter;
+use uuid::Uuid;
+use walkdir::WalkDir;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Packages are usually compressed internally already, a fast level loses little
+const DEFAULT_COMPRESSION_LEVEL: i32 = 1;
+
+/// Entry of an incremental backup zip describing the backed up folder
+const BACKUP_MANIFEST_NAME: &str = ".simsforge-backup.json";
+
+/// Shape of the timestamp ending the folder backups the frontend writes before a mod update
+/// (`<mod>_v<version>_2024-05-01T10-20-30-123Z`), `0` stands for any digit
+const FOLDER_BACKUP_TIMESTAMP: &str = "0000-00-00T00-00-00-000Z";
+
+/// Progress payload emitted on `backup://progress`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BackupProgress {
+    pub backup_path: String,
+    pub done: usize,
+    pub total: usize,
+    /// File being added, relative to the backed up folder
+    pub current_file: String,
+}
+
+/// Summary of a backup
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BackupReport {
+    pub file_count: usize,
+    pub input_bytes: u64,
+    pub output_bytes: u64,
+    /// Output size divided by input size (1.0 = no gain)
+    pub ratio: f64,
+}
+
+/// Backup found under a backup root
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BackupEntry {
+    pub path: String,
+    /// Profile folder the backup is stored in, empty for backups directly in the root
+    pub profile: String,
+    /// Unix timestamp (seconds) of the last write to the backup
+    pub created: i64,
+    /// Size in bytes, summed over the content for folder backups
+    pub size: u64,
+}
+
+/// Backups removed by `prune_backups`
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct PruneReport {
+    pub pruned: Vec<BackupEntry>,
+    pub reclaimed_bytes: u64,
+    /// Backups that matched the policy but could not be removed
+    pub errors: Vec<String>,
+}
+
+/// State of the backed up folder, stored in every incremental backup
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupManifest {
+    pub id: String,
+    /// Backup this one only holds the changes of, None for a full backup
+    pub base_id: Option<String>,
+    /// Unix timestamp (seconds)
+    pub created: i64,
+    /// Every file of the folder, relative path -> SHA-256
+    pub files: BTreeMap<String, String>,
+    /// Every sub-folder, so empty ones are restored too
+    pub dirs: Vec<String>,
+    /// Files and folders removed since the base backup
+    pub deleted: Vec<String>,
+}
+
+/// Summary of an incremental backup
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IncrementalBackupReport {
+    pub path: String,
+    /// New or modified files stored in this backup
+    pub changed: usize,
+    pub deleted: usize,
+    /// Files left to the base backups
+    pub unchanged: usize,
+    pub output_bytes: u64,
+}
+
+/// Summary of a restored backup chain
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RestoreReport {
+    pub target: String,
+    pub file_count: usize,
+    /// Tree hash of the restored folder, equal to the one of the backed up folder
+    pub tree_hash: String,
+}
+
+/// List the backups under `backup_root`, newest first
+/// Each sub-folder is a profile holding its backups (zips or folders)
+#[tauri::command(async)]
+pub fn list_backups(backup_root: String) -> Result<Vec<BackupEntry>, String> {
+    scan_backups(Path::new(&backup_root))
+}
+
+/// Move backups beyond `keep_count` per profile, or older than `keep_days`, to the trash
+/// The most recent backup of each profile is always kept
+#[tauri::command(async)]
+pub fn prune_backups(
+    backup_root: String,
+    keep_count: Option<usize>,
+    keep_days: Option<u64>,
+    use_trash: Option<bool>,
+) -> Result<PruneReport, String> {
+    let backups = scan_backups(Path::new(&backup_root))?;
+    let mut report = PruneReport::default();
+
+    for backup in select_prunable(backups, keep_count, keep_days, unix_now()) {
+        match remove_path(Path::new(&backup.path), use_trash.unwrap_or(true)) {
+            Ok(()) => {
+                report.reclaimed_bytes += backup.size;
+                report.pruned.push(backup);
+            }
+            Err(e) => report.errors.push(e),
+        }
+    }
+
+    Ok(report)
+}
+
+fn scan_backups(root: &Path) -> Result<Vec<BackupEntry>, String> {
+    let read = |dir: &Path| {
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))
+    };
+
+    // Anything not recognized as a backup (notes, desktop.ini, stray saves) is left out
+    let mut backups = Vec::new();
+    for entry in read(root)?.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if is_backup(&path) {
+            backups.push(backup_entry(&path, ""));
+            continue;
+        }
+        if !path.is_dir() {
+            continue;
+        }
+
+        let profile = entry.file_name().to_string_lossy().to_string();
+        for backup in read(&path)?.filter_map(|entry| entry.ok()) {
+            if is_backup(&backup.path()) {
+                backups.push(backup_entry(&backup.path(), &profile));
+            }
+        }
+    }
+
+    backups.sort_by(|a, b| b.created.cmp(&a.created).then_with(|| a.path.cmp(&b.path)));
+    Ok(backups)
+}
+
+/// Zips written by `create_backup` and `create_incremental_backup`, or folder backups named
+/// with a trailing timestamp; a folder holding other backups is a profile, never a backup
+fn is_backup(path: &Path) -> bool {
+    if path.is_file() {
+        return path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    }
+    path.is_dir() && is_folder_backup_name(path) && !contains_backups(path)
+}
+
+fn is_folder_backup_name(dir: &Path) -> bool {
+    let Some(name) = dir.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let Some((_, timestamp)) = name.rsplit_once('_') else {
+        return false;
+    };
+    timestamp.len() == FOLDER_BACKUP_TIMESTAMP.len()
+        && timestamp.chars().zip(FOLDER_BACKUP_TIMESTAMP.chars()).all(
+            |(c, expected)| match expected {
+                '0' => c.is_ascii_digit(),
+                _ => c == expected,
+            },
+        )
+}
+
+fn contains_backups(dir: &Path) -> bool {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .any(|entry| is_backup(&entry.path()))
+        })
+        .unwrap_or(false)
+}
+
+/// Folder backups are dated by their newest file, a folder's own time moves whenever entries change
+fn backup_entry(path: &Path, profile: &str) -> BackupEntry {
+    let files: Vec<fs::Metadata> = WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|m| m.is_file())
+        .collect();
+    let created = files
+        .iter()
+        .filter_map(|m| m.modified().ok())
+        .max()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let size = files.iter().map(|m| m.len()).sum();
+
+    BackupEntry {
+        path: path.to_string_lossy().to_string(),
+        profile: profile.to_string(),
+        created,
+        size,
+    }
+}
+
+/// Backups outside the retention policy, never the newest of a profile
+fn select_prunable(
+    backups: Vec<BackupEntry>,
+    keep_count: Option<usize>,
+    keep_days: Option<u64>,
+    now: i64,
+) -> Vec<BackupEntry> {
+    let mut by_profile: BTreeMap<String, Vec<BackupEntry>> = BTreeMap::new();
+    for backup in backups {
+        by_profile
+            .entry(backup.profile.clone())
+            .or_default()
+            .push(backup);
+    }
+
+    let max_age = keep_days.map(|days| days as i64 * 24 * 60 * 60);
+    let mut prunable = Vec::new();
+    for (_, mut backups) in by_profile {
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.created));
+        for (rank, backup) in backups.into_iter().enumerate().skip(1) {
+            let over_count = keep_count.is_some_and(|count| rank >= count);
+            let too_old = max_age.is_some_and(|age| now - backup.created > age);
+            if over_count || too_old {
+                prunable.push(backup);
+            }
+        }
+    }
+    prunable
+}
+
+/// Zip the content of `source_dir` into `backup_path`
+/// `compression_level` 0 stores files as-is, otherwise 1-9 for Deflate or 1-22 for Zstd
+/// (default 1), linked mods are backed up by content
+/// Emits `backup://progress` after each file
+#[tauri::command(async)]
+pub fn create_backup(
+    app_handle: tauri::AppHandle,
+    source_dir: String,
+    backup_path: String,
+    compression_level: Option<i32>,
+    method: Option<RepackMethod>,
+) -> Result<BackupReport, String> {
+    backup_directory(
+        Path::new(&source_dir),
+        Path::new(&backup_path),
+        method.unwrap_or_default(),
+        compression_level.unwrap_or(DEFAULT_COMPRESSION_LEVEL),
+        |done, total, current_file| {
+            let _ = app_handle.emit(
+                "backup://progress",
+                BackupProgress {
+                    backup_path: backup_path.clone(),
+                    done,
+                    total,
+                    current_file: current_file.to_string(),
+                },
+            );
+        },
+    )
+}
+
+pub fn backup_directory(
+    source: &Path,
+    output: &Path,
+    method: RepackMethod,
+    compression_level: i32,
+    on_progress: impl Fn(usize, usize, &str),
+) -> Result<BackupReport, String> {
+    if !source.is_dir() {
+        return Err(format!("Directory not found: {}", source.display()));
+    }
+    if output.starts_with(source) {
+        return Err("The backup can't be written inside the folder being backed up".to_string());
+    }
+
+    let mut files = Vec::new();
+    for entry in WalkDir::new(source)
+        .min_depth(1)
+        .follow_links(true)
+        .sort_by_file_name()
+    {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+        if entry.file_type().is_file() {
+            files.push(entry.into_path());
+        }
+    }
+
+    let (compression, level) = match (method, compression_level) {
+        (_, level) if level <= 0 => (CompressionMethod::Stored, None),
+        (RepackMethod::Deflate, level) => (CompressionMethod::Deflated, Some(level.min(9))),
+        (RepackMethod::Zstd, level) => (CompressionMethod::Zstd, Some(level.min(22))),
+    };
+
+    // Written next to the destination and renamed at the end, so a failed run leaves no broken zip
+    let temp_path = temp_path_for(output)?;
+    let outcome = write_zip(
+        source,
+        &files,
+        None,
+        &temp_path,
+        compression,
+        level,
+        &on_progress,
+    )
+    .and_then(|input_bytes| {
+        fs::rename(&temp_path, output)
+            .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+        Ok(input_bytes)
+    });
+    let input_bytes = match outcome {
+        Ok(input_bytes) => input_bytes,
+        Err(e) => {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
+        }
+    };
+
+    let output_bytes = fs::metadata(output).map(|m| m.len()).unwrap_or(0);
+    Ok(BackupReport {
+        file_count: files.len(),
+        input_bytes,
+        output_bytes,
+        ratio: if input_bytes > 0 {
+            output_bytes as f64 / input_bytes as f64
+        } else {
+            1.0
+        },
+    })
+}
+
+/// Write `files` into a new zip at `output`, returns the total size of the files added
+/// `manifest` is stored last as the backup manifest entry
+fn write_zip(
+    source: &Path,
+    files: &[PathBuf],
+    manifest: Option<&BackupManifest>,
+    output: &Path,
+    compression: CompressionMethod,
+    level: Option<i32>,
+    on_progress: &impl Fn(usize, usize, &str),
+) -> Result<u64, String> {
+    let file = File::create(output)
+        .map_err(|e| format!("Failed to create {}: {}", output.display(), e))?;
+    let mut writer = ZipWriter::new(BufWriter::new(file));
+    let mut input_bytes = 0;
+
+    for (index, path) in files.iter().enumerate() {
+        let name = path
+            .strip_prefix(source)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let mut input =
+            File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let size = input.metadata().map(|m| m.len()).unwrap_or(0);
+
+        let options = FileOptions::default()
+            .compression_method(compression)
+            .compression_level(level)
+            .large_file(size >= u32::MAX as u64);
+        writer
+            .start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {}: {}", name, e))?;
+        copy(&mut input, &mut writer).map_err(|e| format!("Failed to add {}: {}", name, e))?;
+
+        input_bytes += size;
+        on_progress(index + 1, files.len(), &name);
+    }
+
+    if let Some(manifest) = manifest {
+        let json = serde_json::to_vec_pretty(manifest)
+            .map_err(|e| format!("Failed to serialize backup manifest: {}", e))?;
+        writer
+            .start_file(BACKUP_MANIFEST_NAME, FileOptions::default())
+            .and_then(|_| writer.write_all(&json).map_err(Into::into))
+            .map_err(|e| format!("Failed to add backup manifest: {}", e))?;
+    }
+
+    writer
+        .finish()
+        .map_err(|e| format!("Failed to finish {}: {}", output.display(), e))?
+        .into_inner()
+        .map_err(|e| format!("Failed to write {}: {}", output.display(), e.error()))?
+        .sync_all()
+        .map_err(|e| format!("Failed to sync {}: {}", output.display(), e))?;
+    Ok(input_bytes)
+}
+
+/// Back up only what changed in `source` since the backup at `base_manifest`, whose stored
+/// manifest is compared against by hash
+/// Stores new and modified files plus the list of deletions, or every file when there is no
+/// base; the backup is written to `backup_root` and its path returned
+#[tauri::command(async)]
+pub fn create_incremental_backup(
+    source: String,
+    backup_root: String,
+    base_manifest: Option<String>,
+) -> Result<IncrementalBackupReport, String> {
+    let base = base_manifest
+        .map(|path| read_backup_manifest(Path::new(&path)))
+        .transpose()?;
+    incremental_backup(Path::new(&source), Path::new(&backup_root), base.as_ref())
+}
+
+/// Restore a chain of backups, a full backup followed by its incrementals in order,
+/// into the new folder `target`
+/// The restored folder is checked against the tree hash of the last backup before it is moved
+/// into place
+#[tauri::command(async)]
+pub fn restore_incremental(chain: Vec<String>, target: String) -> Result<RestoreReport, String> {
+    let chain: Vec<PathBuf> = chain.iter().map(PathBuf::from).collect();
+    restore_chain(&chain, Path::new(&target))
+}
+
+pub fn incremental_backup(
+    source: &Path,
+    backup_root: &Path,
+    base: Option<&BackupManifest>,
+) -> Result<IncrementalBackupReport, String> {
+    if !source.is_dir() {
+        return Err(format!("Directory not found: {}", source.display()));
+    }
+    if backup_root.starts_with(source) {
+        return Err("The backup can't be written inside the folder being backed up".to_string());
+    }
+    fs::create_dir_all(backup_root)
+        .map_err(|e| format!("Failed to create {}: {}", backup_root.display(), e))?;
+
+    let mut files = BTreeMap::new();
+    let mut dirs = Vec::new();
+    let mut changed = Vec::new();
+    for entry in WalkDir::new(source)
+        .min_depth(1)
+        .follow_links(true)
+        .sort_by_file_name()
+    {
+        let entry = entry.map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+        let relative = entry
+            .path()
+            .strip_prefix(source)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+        if entry.file_type().is_dir() {
+            dirs.push(relative);
+            continue;
+        }
+
+        let hash = calculate_file_hash(entry.path().to_string_lossy().to_string())?;
+        let unchanged = base.and_then(|base| base.files.get(&relative)) == Some(&hash);
+        if !unchanged {
+            changed.push(entry.into_path());
+        }
+        files.insert(relative, hash);
+    }
+
+    let deleted: Vec<String> = match base {
+        Some(base) => base
+            .files
+            .keys()
+            .filter(|path| !files.contains_key(*path))
+            .chain(base.dirs.iter().filter(|dir| !dirs.contains(dir)))
+            .cloned()
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let manifest = BackupManifest {
+        id: Uuid::new_v4().to_string(),
+        base_id: base.map(|base| base.id.clone()),
+        created: unix_now(),
+        files,
+        dirs,
+        deleted,
+    };
+
+    let kind = if base.is_some() {
+        "incremental"
+    } else {
+        "full"
+    };
+    let output = (0..)
+        .map(|n| match n {
+            0 => backup_root.join(format!("{}-{}.zip", kind, manifest.created)),
+            n => backup_root.join(format!("{}-{}-{}.zip", kind, manifest.created, n)),
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap();
+
+    let temp_path = temp_path_for(&output)?;
+    let outcome = write_zip(
+        source,
+        &changed,
+        Some(&manifest),
+        &temp_path,
+        CompressionMethod::Deflated,
+        Some(DEFAULT_COMPRESSION_LEVEL),
+        &|_, _, _| {},
+    )
+    .and_then(|_| {
+        fs::rename(&temp_path, &output)
+            .map_err(|e| format!("Failed to write {}: {}", output.display(), e))
+    });
+    if let Err(e) = outcome {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+
+    Ok(IncrementalBackupReport {
+        path: output.to_string_lossy().to_string(),
+        changed: changed.len(),
+        deleted: manifest.deleted.len(),
+        unchanged: manifest.files.len() - changed.len(),
+        output_bytes: fs::metadata(&output).map(|m| m.len()).unwrap_or(0),
+    })
+}
+
+/// Manifest stored in an incremental backup
+pub fn read_backup_manifest(backup: &Path) -> Result<BackupManifest, String> {
+    let file = File::open(backup)
+        .map_err(|e| format!("Failed to open backup {}: {}", backup.display(), e))?;
+    let mut archive = ZipArchive::new(BufReader::new(file))
+        .map_err(|e| format!("Invalid backup {}: {}", backup.display(), e))?;
+    let mut json = String::new();
+    archive
+        .by_name(BACKUP_MANIFEST_NAME)
+        .map_err(|_| format!("{} is not an incremental backup", backup.display()))?
+        .read_to_string(&mut json)
+        .map_err(|e| format!("Failed to read manifest of {}: {}", backup.display(), e))?;
+    serde_json::from_str(&json)
+        .map_err(|e| format!("Invalid manifest in {}: {}", backup.display(), e))
+}
+
+pub fn restore_chain(chain: &[PathBuf], target: &Path) -> Result<RestoreReport, String> {
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+
+    let manifests = chain
+        .iter()
+        .map(|backup| read_backup_manifest(backup))
+        .collect::<Result<Vec<_>, String>>()?;
+    let Some(last) = manifests.last() else {
+        return Err("No backup to restore".to_string());
+    };
+    for (index, manifest) in manifests.iter().enumerate() {
+        let expected_base = index.checked_sub(1).map(|i| &manifests[i].id);
+        if manifest.base_id.as_ref() != expected_base {
+            return Err(format!(
+                "{} does not follow the previous backup of the chain",
+                chain[index].display()
+            ));
+        }
+    }
+
+    // Restored next to the target and moved into place once verified
+    let staging = temp_path_for(target)?;
+    let outcome = replay(chain, &manifests, &staging).and_then(|_| {
+        let restored = tree_hash(&staging, &TreeHashCache::default())?;
+        let expected = tree_hash_of_listing(&last.files, &last.dirs);
+        if restored != expected {
+            return Err(format!(
+                "Restored folder does not match the backup: expected tree hash {}, got {}",
+                expected, restored
+            ));
+        }
+        fs::rename(&staging, target)
+            .map_err(|e| format!("Failed to move restore into {}: {}", target.display(), e))?;
+        Ok(restored)
+    });
+
+    match outcome {
+        Ok(tree_hash) => Ok(RestoreReport {
+            target: target.to_string_lossy().to_string(),
+            file_count: last.files.len(),
+            tree_hash,
+        }),
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            Err(e)
+        }
+    }
+}
+
+/// Apply each backup of the chain onto `dest` in order: deletions first, then its files
+fn replay(chain: &[PathBuf], manifests: &[BackupManifest], dest: &Path) -> Result<(), String> {
+    fs::create_dir_all(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+
+    for (backup, manifest) in chain.iter().zip(manifests) {
+        for removed in &manifest.deleted {
+            let path = safe_relative_path(removed)
+                .map(|relative| dest.join(relative))
+                .ok_or_else(|| format!("Unsafe path in backup: {}", removed))?;
+            let _ = if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            };
+        }
+
+        let file = File::open(backup)
+            .map_err(|e| format!("Failed to open backup {}: {}", backup.display(), e))?;
+        let mut archive = ZipArchive::new(BufReader::new(file))
+            .map_err(|e| format!("Invalid backup {}: {}", backup.display(), e))?;
+        for i in 0..archive.len() {
+            let mut entry = archive
+                .by_index(i)
+                .map_err(|e| format!("Failed to read {}: {}", backup.display(), e))?;
+            if entry.is_dir() || entry.name() == BACKUP_MANIFEST_NAME {
+                continue;
+            }
+            let path = safe_relative_path(entry.name())
+                .map(|relative| dest.join(relative))
+                .ok_or_else(|| format!("Unsafe path in backup: {}", entry.name()))?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            let mut output = File::create(&path)
+                .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+            copy(&mut entry, &mut output)
+                .map_err(|e| format!("Failed to restore {}: {}", path.display(), e))?;
+        }
+    }
+
+    let last = manifests.last().map(|m| m.dirs.as_slice()).unwrap_or(&[]);
+    for dir in last {
+        if let Some(relative) = safe_relative_path(dir) {
+            fs::create_dir_all(dest.join(relative))
+                .map_err(|e| format!("Failed to create {}: {}", dir, e))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tempfile::tempdir;
+    use zip::ZipArchive;
+
+    fn mods_fixture(root: &Path) {
+        fs::create_dir_all(root.join("CAS")).unwrap();
+        // Repetitive but not trivially so, to separate fast and thorough levels
+        let tuning: String = (0..4000)
+            .map(|i| format!("<T n=\"value_{}\">{}</T>\n", i % 97, i * 31 % 1013))
+            .collect();
+        fs::write(root.join("CAS/hair.package"), tuning.as_bytes()).unwrap();
+        fs::write(root.join("tuning.package"), tuning.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn higher_levels_give_smaller_backups() {
+        let dir = tempdir().unwrap();
+        let mods = dir.path().join("Mods");
+        mods_fixture(&mods);
+
+        let backup = |name: &str, method, level| {
+            backup_directory(&mods, &dir.path().join(name), method, level, |_, _, _| {}).unwrap()
+        };
+        let stored = backup("store.zip", RepackMethod::Deflate, 0);
+        let fast = backup("fast.zip", RepackMethod::Deflate, 1);
+        let best = backup("best.zip", RepackMethod::Deflate, 9);
+        let zstd = backup("zstd.zip", RepackMethod::Zstd, 19);
+
+        assert!(stored.output_bytes > stored.input_bytes);
+        assert!(stored.ratio > 1.0);
+        assert!(fast.output_bytes < stored.output_bytes);
+        assert!(best.output_bytes < fast.output_bytes);
+        assert!(zstd.output_bytes < stored.output_bytes);
+        assert_eq!(best.input_bytes, stored.input_bytes);
+        assert!(best.ratio < fast.ratio);
+
+        let mut archive =
+            ZipArchive::new(File::open(dir.path().join("zstd.zip")).unwrap()).unwrap();
+        assert_eq!(archive.len(), 2);
+        let mut content = Vec::new();
+        copy(
+            &mut archive.by_name("CAS/hair.package").unwrap(),
+            &mut content,
+        )
+        .unwrap();
+        assert_eq!(content, fs::read(mods.join("CAS/hair.package")).unwrap());
+    }
+
+    fn backup_at(root: &Path, relative: &str, days_old: u64) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, vec![0u8; 100]).unwrap();
+        let modified =
+            std::time::SystemTime::now() - std::time::Duration::from_secs(days_old * 24 * 60 * 60);
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    fn pruned_names(report: &PruneReport) -> Vec<String> {
+        let mut names: Vec<String> = report
+            .pruned
+            .iter()
+            .map(|backup| {
+                let path = Path::new(&backup.path);
+                format!(
+                    "{}/{}",
+                    backup.profile,
+                    path.file_name().unwrap().to_string_lossy()
+                )
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn root_string(root: &Path) -> String {
+        root.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn lists_backups_per_profile_newest_first() {
+        let dir = tempdir().unwrap();
+        backup_at(dir.path(), "Main/old.zip", 10);
+        backup_at(dir.path(), "Main/new.zip", 1);
+        backup_at(dir.path(), "Legacy/only.zip", 5);
+        backup_at(
+            dir.path(),
+            "Hair_v2_2024-05-01T10-20-30-123Z/hair.package",
+            3,
+        );
+
+        let backups = list_backups(root_string(dir.path())).unwrap();
+
+        let listed: Vec<(&str, &str)> = backups
+            .iter()
+            .map(|b| {
+                (
+                    b.profile.as_str(),
+                    Path::new(&b.path).file_name().unwrap().to_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                ("Main", "new.zip"),
+                ("", "Hair_v2_2024-05-01T10-20-30-123Z"),
+                ("Legacy", "only.zip"),
+                ("Main", "old.zip")
+            ]
+        );
+        assert!(backups.iter().all(|b| b.size == 100));
+    }
+
+    #[test]
+    fn only_recognized_backups_are_listed() {
+        let dir = tempdir().unwrap();
+        backup_at(dir.path(), "Main/backup.zip", 10);
+        backup_at(dir.path(), "Main/Slot_00000001.save", 1);
+        backup_at(dir.path(), "Main/notes.txt", 1);
+        backup_at(dir.path(), "desktop.ini", 1);
+        backup_at(dir.path(), "Slot_00000002.save", 1);
+        // Named like a backup but holding backups, so still a profile
+        backup_at(dir.path(), "Old_v1_2024-05-01T10-20-30-123Z/kept.zip", 20);
+
+        let backups = list_backups(root_string(dir.path())).unwrap();
+        let listed: Vec<(&str, &str)> = backups
+            .iter()
+            .map(|b| {
+                (
+                    b.profile.as_str(),
+                    Path::new(&b.path).file_name().unwrap().to_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            listed,
+            vec![
+                ("Main", "backup.zip"),
+                ("Old_v1_2024-05-01T10-20-30-123Z", "kept.zip")
+            ]
+        );
+
+        // The profile folder and the stray save survive pruning
+        let report = prune_backups(root_string(dir.path()), Some(1), Some(1), Some(false)).unwrap();
+        assert!(report.pruned.is_empty());
+        assert!(dir.path().join("Main/Slot_00000001.save").exists());
+    }
+
+    #[test]
+    fn prunes_beyond_keep_count() {
+        let dir = tempdir().unwrap();
+        for days in 1..=4 {
+            backup_at(dir.path(), &format!("Main/backup_{}.zip", days), days);
+        }
+        backup_at(dir.path(), "Legacy/only.zip", 30);
+
+        let report = prune_backups(root_string(dir.path()), Some(2), None, Some(false)).unwrap();
+
+        assert_eq!(
+            pruned_names(&report),
+            vec!["Main/backup_3.zip", "Main/backup_4.zip"]
+        );
+        assert_eq!(report.reclaimed_bytes, 200);
+        assert!(dir.path().join("Main/backup_2.zip").exists());
+        assert!(!dir.path().join("Main/backup_3.zip").exists());
+        assert!(dir.path().join("Legacy/only.zip").exists());
+    }
+
+    #[test]
+    fn prunes_by_age_but_keeps_newest_per_profile() {
+        let dir = tempdir().unwrap();
+        backup_at(dir.path(), "Main/recent.zip", 2);
+        backup_at(dir.path(), "Main/stale.zip", 40);
+        backup_at(dir.path(), "Legacy/ancient.zip", 365);
+        backup_at(dir.path(), "Legacy/older.zip", 400);
+
+        let report = prune_backups(root_string(dir.path()), None, Some(30), Some(false)).unwrap();
+
+        assert_eq!(
+            pruned_names(&report),
+            vec!["Legacy/older.zip", "Main/stale.zip"]
+        );
+        assert!(report.errors.is_empty());
+        assert!(dir.path().join("Legacy/ancient.zip").exists());
+        assert!(dir.path().join("Main/recent.zip").exists());
+
+        // What is left is the newest of each profile
+        let report =
+            prune_backups(root_string(dir.path()), Some(5), Some(30), Some(false)).unwrap();
+        assert!(report.pruned.is_empty());
+    }
+
+    #[test]
+    fn reports_progress_per_file() {
+        let dir = tempdir().unwrap();
+        let mods = dir.path().join("Mods");
+        mods_fixture(&mods);
+
+        let progress = Mutex::new(Vec::new());
+        let report = backup_directory(
+            &mods,
+            &dir.path().join("backup.zip"),
+            RepackMethod::Deflate,
+            DEFAULT_COMPRESSION_LEVEL,
+            |done, total, name| {
+                progress
+                    .lock()
+                    .unwrap()
+                    .push((done, total, name.to_string()))
+            },
+        )
+        .unwrap();
+
+        assert_eq!(report.file_count, 2);
+        assert_eq!(
+            progress.into_inner().unwrap(),
+            vec![
+                (1, 2, "CAS/hair.package".to_string()),
+                (2, 2, "tuning.package".to_string())
+            ]
+        );
+        assert!(backup_directory(
+            &mods,
+            &mods.join("inside.zip"),
+            RepackMethod::Deflate,
+            1,
+            |_, _, _| {}
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn incremental_backup_round_trip() {
+        let dir = tempdir().unwrap();
+        let profile = dir.path().join("profile");
+        let backups = dir.path().join("backups");
+        fs::create_dir_all(profile.join("Mods/CAS")).unwrap();
+        fs::create_dir_all(profile.join("Mods/Old")).unwrap();
+        fs::write(profile.join("Mods/CAS/hair.package"), b"hair v1").unwrap();
+        fs::write(profile.join("Mods/Old/retired.package"), b"retired").unwrap();
+        fs::write(profile.join("Options.ini"), b"[options]").unwrap();
+
+        let full = incremental_backup(&profile, &backups, None).unwrap();
+        assert_eq!(full.changed, 3);
+
+        fs::write(profile.join("Mods/CAS/hair.package"), b"hair v2").unwrap();
+        fs::remove_dir_all(profile.join("Mods/Old")).unwrap();
+        fs::create_dir_all(profile.join("Mods/Empty")).unwrap();
+        fs::write(profile.join("Mods/top.package"), b"top").unwrap();
+
+        let base = read_backup_manifest(Path::new(&full.path)).unwrap();
+        let incremental = incremental_backup(&profile, &backups, Some(&base)).unwrap();
+        assert_eq!(incremental.changed, 2);
+        assert_eq!(incremental.unchanged, 1);
+        // The retired file and its folder
+        assert_eq!(incremental.deleted, 2);
+        assert_ne!(incremental.path, full.path);
+
+        let target = dir.path().join("restored");
+        let report = restore_chain(
+            &[PathBuf::from(&full.path), PathBuf::from(&incremental.path)],
+            &target,
+        )
+        .unwrap();
+
+        let cache = TreeHashCache::default();
+        assert_eq!(report.tree_hash, tree_hash(&profile, &cache).unwrap());
+        assert_eq!(report.file_count, 3);
+        assert_eq!(
+            fs::read(target.join("Mods/CAS/hair.package")).unwrap(),
+            b"hair v2"
+        );
+        assert!(!target.join("Mods/Old").exists());
+        assert!(target.join("Mods/Empty").is_dir());
+
+        // Out of order chains are refused
+        let error = restore_chain(
+            &[PathBuf::from(&incremental.path)],
+            &dir.path().join("broken"),
+        )
+        .unwrap_err();
+        assert!(error.contains("does not follow"));
+        assert!(!dir.path().join("broken").exists());
+    }
+}
@@ -0,0 +1,578 @@
+use crate::archive;
+use crate::operations::{CancellationToken, OperationRegistry, CANCELLED_ERROR};
+use serde::{Deserialize, Serialize};
+use std::fs::{create_dir_all, remove_dir_all, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Write speed assumed when no benchmark has been run yet (typical SATA SSD with small files)
+const ASSUMED_WRITE_MBPS: u64 = 150;
+
+/// Speeds outside this range are measurement noise, not real disks
+const MIN_WRITE_MBPS: u64 = 5;
+const MAX_WRITE_MBPS: u64 = 5000;
+
+/// Fixed cost of creating one file (open, metadata, close), dominant for archives of tiny files
+const PER_FILE_OVERHEAD_MS: u64 = 2;
+
+/// Estimates are shown as "~Ns", so keep them between one second and an hour
+const MIN_ESTIMATE_SECS: u64 = 1;
+const MAX_ESTIMATE_SECS: u64 = 3600;
+
+/// Last disk benchmark of this session, reused by estimates
+#[derive(Default)]
+pub struct BenchmarkCache {
+    write_mbps: Mutex<Option<u64>>,
+}
+
+/// Where the write speed of an estimate came from
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EstimateSource {
+    /// Speed passed by the caller
+    Provided,
+    /// Disk benchmark run during this session
+    Benchmark,
+    /// No measurement available, default speed assumed
+    Assumed,
+}
+
+/// Expected duration of an extraction
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExtractionTimeEstimate {
+    pub seconds: u64,
+    pub based_on: EstimateSource,
+    /// Write speed used for the estimate, after clamping
+    pub write_mbps: u64,
+}
+
+/// Result of disk benchmark
+#[derive(Serialize, Deserialize)]
+pub struct DiskBenchmarkResult {
+    /// Measured disk speed in MB/s
+    pub speed_mbps: u64,
+    /// Total bytes written during benchmark
+    pub bytes_written: u64,
+    /// Time taken in milliseconds
+    pub elapsed_ms: u64,
+}
+
+/// Benchmark disk write speed by writing test files directly in Rust
+/// This avoids IPC overhead and gives accurate disk performance measurement
+#[tauri::command]
+pub fn benchmark_disk_speed(
+    app_handle: tauri::AppHandle,
+    cache: tauri::State<'_, BenchmarkCache>,
+) -> Result<DiskBenchmarkResult, String> {
+    use tauri::Manager;
+
+    // Get app data directory for temp files
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    let benchmark_dir = app_data_dir.join("benchmark_temp");
+
+    // Create benchmark directory
+    create_dir_all(&benchmark_dir)
+        .map_err(|e| format!("Failed to create benchmark directory: {}", e))?;
+
+    // Configuration: 5 files of 50MB each = 250MB total
+    // Larger files reduce overhead impact and give more accurate measurements
+    const FILE_COUNT: usize = 5;
+    const FILE_SIZE: usize = 50 * 1024 * 1024; // 50MB per file
+    const TOTAL_BYTES: u64 = (FILE_COUNT * FILE_SIZE) as u64;
+
+    // Generate test data (pseudo-random pattern)
+    let test_data: Vec<u8> = (0..FILE_SIZE)
+        .map(|i| ((i * 17 + 31) % 256) as u8)
+        .collect();
+
+    // Measure write time
+    let start = Instant::now();
+
+    for i in 0..FILE_COUNT {
+        let file_path = benchmark_dir.join(format!("bench_{}.bin", i));
+        let mut file = File::create(&file_path)
+            .map_err(|e| format!("Failed to create benchmark file: {}", e))?;
+
+        file.write_all(&test_data)
+            .map_err(|e| format!("Failed to write benchmark file: {}", e))?;
+
+        // Ensure data is flushed to disk
+        file.sync_all()
+            .map_err(|e| format!("Failed to sync benchmark file: {}", e))?;
+    }
+
+    let elapsed = start.elapsed();
+    let elapsed_ms = elapsed.as_millis() as u64;
+
+    // Calculate speed in MB/s
+    let speed_mbps = if elapsed_ms > 0 {
+        (TOTAL_BYTES / (1024 * 1024)) * 1000 / elapsed_ms
+    } else {
+        1000 // If too fast to measure, assume very fast
+    };
+
+    // Cleanup benchmark files
+    if let Err(e) = remove_dir_all(&benchmark_dir) {
+        eprintln!("Warning: Failed to cleanup benchmark directory: {}", e);
+    }
+
+    *cache.write_mbps.lock().unwrap() = Some(speed_mbps);
+
+    Ok(DiskBenchmarkResult {
+        speed_mbps,
+        bytes_written: TOTAL_BYTES,
+        elapsed_ms,
+    })
+}
+
+/// Estimate how long extracting an archive will take, for the install dialog
+/// Uses `write_mbps` when given, else the last benchmark of this session, else a default speed
+#[tauri::command]
+pub fn estimate_extraction_time(
+    cache: tauri::State<'_, BenchmarkCache>,
+    zip_path: String,
+    write_mbps: Option<u64>,
+) -> Result<ExtractionTimeEstimate, String> {
+    let inspection = archive::inspect_archive(Path::new(&zip_path))?;
+
+    let (speed, based_on) = match (write_mbps, *cache.write_mbps.lock().unwrap()) {
+        (Some(speed), _) => (speed, EstimateSource::Provided),
+        (None, Some(speed)) => (speed, EstimateSource::Benchmark),
+        (None, None) => (ASSUMED_WRITE_MBPS, EstimateSource::Assumed),
+    };
+    let speed = speed.clamp(MIN_WRITE_MBPS, MAX_WRITE_MBPS);
+
+    Ok(ExtractionTimeEstimate {
+        seconds: estimate_seconds(inspection.uncompressed_bytes, inspection.file_count, speed),
+        based_on,
+        write_mbps: speed,
+    })
+}
+
+/// Transfer time at `write_mbps` plus a fixed cost per file, rounded up and clamped
+fn estimate_seconds(bytes: u64, file_count: usize, write_mbps: u64) -> u64 {
+    let transfer_ms = bytes.saturating_mul(1000) / (write_mbps.max(1) * 1024 * 1024);
+    let overhead_ms = file_count as u64 * PER_FILE_OVERHEAD_MS;
+
+    (transfer_ms + overhead_ms)
+        .div_ceil(1000)
+        .clamp(MIN_ESTIMATE_SECS, MAX_ESTIMATE_SECS)
+}
+
+/// Per-drive benchmark: 4 files of 32MB each = 128MB per target
+/// Smaller than the app benchmark since several drives are measured back to back
+const DRIVE_FILE_COUNT: usize = 4;
+const DRIVE_FILE_SIZE: usize = 32 * 1024 * 1024;
+
+/// Benchmark result for a single target directory
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DriveBenchmarkResult {
+    /// Target directory as provided by the caller
+    pub target: String,
+    /// Measured sequential read speed in MB/s (0 if the target was skipped)
+    pub read_mbps: u64,
+    /// Measured sequential write speed in MB/s (0 if the target was skipped)
+    pub write_mbps: u64,
+    /// Why the target could not be benchmarked
+    pub error: Option<String>,
+}
+
+/// Benchmark read and write speed of several directories, typically one per drive
+/// Targets are measured sequentially so they don't compete for I/O bandwidth
+/// Missing or unwritable targets are skipped and reported with an error entry
+#[tauri::command(async)]
+pub fn benchmark_drives(targets: Vec<String>) -> Vec<DriveBenchmarkResult> {
+    targets
+        .into_iter()
+        .map(|target| benchmark_target(target, DRIVE_FILE_COUNT, DRIVE_FILE_SIZE))
+        .collect()
+}
+
+/// Benchmark one target, always returning an entry (with `error` set on failure)
+fn benchmark_target(target: String, file_count: usize, file_size: usize) -> DriveBenchmarkResult {
+    let target_path = Path::new(&target);
+    let outcome = if target_path.is_dir() {
+        // Unique folder so concurrent app instances or leftovers never collide
+        let bench_dir = target_path.join(format!(".simsforge_benchmark_{}", Uuid::new_v4()));
+        let outcome = measure_read_write(&bench_dir, file_count, file_size);
+
+        if bench_dir.exists() {
+            if let Err(e) = remove_dir_all(&bench_dir) {
+                eprintln!("Warning: Failed to cleanup benchmark directory: {}", e);
+            }
+        }
+        outcome
+    } else {
+        Err("Target directory does not exist".to_string())
+    };
+
+    match outcome {
+        Ok((read_mbps, write_mbps)) => DriveBenchmarkResult {
+            target,
+            read_mbps,
+            write_mbps,
+            error: None,
+        },
+        Err(error) => DriveBenchmarkResult {
+            target,
+            read_mbps: 0,
+            write_mbps: 0,
+            error: Some(error),
+        },
+    }
+}
+
+/// Write then read back test files in `bench_dir`, returning (read MB/s, write MB/s)
+fn measure_read_write(
+    bench_dir: &Path,
+    file_count: usize,
+    file_size: usize,
+) -> Result<(u64, u64), String> {
+    create_dir_all(bench_dir).map_err(|e| format!("Target is not writable: {}", e))?;
+
+    let test_data: Vec<u8> = (0..file_size)
+        .map(|i| ((i * 17 + 31) % 256) as u8)
+        .collect();
+    let total_bytes = (file_count * file_size) as u64;
+
+    let start = Instant::now();
+    for i in 0..file_count {
+        let mut file = File::create(bench_dir.join(format!("bench_{}.bin", i)))
+            .map_err(|e| format!("Target is not writable: {}", e))?;
+        file.write_all(&test_data)
+            .map_err(|e| format!("Failed to write benchmark file: {}", e))?;
+        file.sync_all()
+            .map_err(|e| format!("Failed to sync benchmark file: {}", e))?;
+    }
+    let write_mbps = throughput_mbps(total_bytes, start.elapsed());
+
+    // Reads may be partly served from the OS cache, so this is an upper bound
+    let mut buffer = vec![0u8; 1024 * 1024];
+    let start = Instant::now();
+    for i in 0..file_count {
+        let mut file = File::open(bench_dir.join(format!("bench_{}.bin", i)))
+            .map_err(|e| format!("Failed to open benchmark file: {}", e))?;
+        while file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read benchmark file: {}", e))?
+            > 0
+        {}
+    }
+    let read_mbps = throughput_mbps(total_bytes, start.elapsed());
+
+    Ok((read_mbps, write_mbps))
+}
+
+/// Convert bytes moved in `elapsed` to MB/s
+fn throughput_mbps(bytes: u64, elapsed: Duration) -> u64 {
+    let seconds = elapsed.as_secs_f64().max(0.001);
+    (bytes as f64 / (1024.0 * 1024.0) / seconds) as u64
+}
+
+/// Drive classification test: a sequential file that random writes then land in
+const CLASSIFY_FILE_SIZE: usize = 64 * 1024 * 1024;
+/// Random writes are 4KiB, synced in rounds so the drive can't absorb them all in its cache
+const RANDOM_WRITE_SIZE: usize = 4096;
+const RANDOM_WRITES_PER_ROUND: usize = 32;
+const MAX_RANDOM_ROUNDS: usize = 256;
+/// Upper bound on the random-write phase, slow drives stop early with fewer rounds
+const RANDOM_TEST_BUDGET: Duration = Duration::from_secs(3);
+
+/// Thresholds for `classify_profile`
+const FAST_SSD_MIN_SEQUENTIAL_MBPS: u64 = 1500;
+const FAST_SSD_MIN_IOPS: u64 = 5000;
+const SSD_MIN_IOPS: u64 = 1000;
+/// Below this even a busy CMR hard drive would be unusually slow
+const SMR_MAX_IOPS: u64 = 30;
+/// SMR drives collapse once their CMR cache zone fills up, CMR drives stay steady
+const SMR_MAX_SUSTAINED_RATIO: f64 = 0.3;
+
+/// Coarse drive category shown next to the install location
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum DriveClass {
+    #[serde(rename = "fast-ssd")]
+    FastSsd,
+    #[serde(rename = "ssd")]
+    Ssd,
+    #[serde(rename = "hdd")]
+    Hdd,
+    /// Shingled (SMR) or otherwise very slow drive, large installs will take a long time
+    #[serde(rename = "slow/smr")]
+    SlowSmr,
+}
+
+/// Measurements a drive class is derived from
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct DriveSpeedProfile {
+    pub sequential_mbps: u64,
+    /// Synced 4KiB random writes per second
+    pub random_iops: u64,
+    /// Random write speed at the end of the test relative to the start (1.0 = steady)
+    pub sustained_ratio: f64,
+}
+
+/// Result of `classify_drive`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DriveClassification {
+    pub drive_class: DriveClass,
+    pub profile: DriveSpeedProfile,
+}
+
+/// Classify the drive holding `target` with a short sequential + random write test
+/// Takes a few seconds at most and can be stopped with `cancel_operation(operation_id)`
+#[tauri::command(async)]
+pub fn classify_drive(
+    registry: tauri::State<'_, OperationRegistry>,
+    target: String,
+    operation_id: String,
+) -> Result<DriveClassification, String> {
+    let operation = registry.start(&operation_id);
+    let target_path = Path::new(&target);
+    if !target_path.is_dir() {
+        return Err("Target directory does not exist".to_string());
+    }
+
+    let bench_dir = target_path.join(format!(".simsforge_benchmark_{}", Uuid::new_v4()));
+    let profile = measure_profile(
+        &bench_dir,
+        CLASSIFY_FILE_SIZE,
+        RANDOM_TEST_BUDGET,
+        operation.token(),
+    );
+    if bench_dir.exists() {
+        if let Err(e) = remove_dir_all(&bench_dir) {
+            eprintln!("Warning: Failed to cleanup benchmark directory: {}", e);
+        }
+    }
+
+    let profile = profile?;
+    Ok(DriveClassification {
+        drive_class: classify_profile(&profile),
+        profile,
+    })
+}
+
+/// Map measured speeds to a drive class
+pub fn classify_profile(profile: &DriveSpeedProfile) -> DriveClass {
+    if profile.random_iops < SMR_MAX_IOPS || profile.sustained_ratio < SMR_MAX_SUSTAINED_RATIO {
+        DriveClass::SlowSmr
+    } else if profile.random_iops < SSD_MIN_IOPS {
+        DriveClass::Hdd
+    } else if profile.sequential_mbps >= FAST_SSD_MIN_SEQUENTIAL_MBPS
+        && profile.random_iops >= FAST_SSD_MIN_IOPS
+    {
+        DriveClass::FastSsd
+    } else {
+        DriveClass::Ssd
+    }
+}
+
+/// Write a file of `file_size` sequentially, then overwrite random 4KiB blocks of it
+/// in synced rounds until `budget` runs out, returning the resulting speed profile
+fn measure_profile(
+    bench_dir: &Path,
+    file_size: usize,
+    budget: Duration,
+    token: &CancellationToken,
+) -> Result<DriveSpeedProfile, String> {
+    create_dir_all(bench_dir).map_err(|e| format!("Target is not writable: {}", e))?;
+    let path = bench_dir.join("bench_random.bin");
+
+    let chunk: Vec<u8> = (0..1024 * 1024)
+        .map(|i| ((i * 17 + 31) % 256) as u8)
+        .collect();
+    let mut file = File::create(&path).map_err(|e| format!("Target is not writable: {}", e))?;
+    let start = Instant::now();
+    let mut written = 0;
+    while written < file_size {
+        if token.is_cancelled() {
+            return Err(CANCELLED_ERROR.to_string());
+        }
+        let len = chunk.len().min(file_size - written);
+        file.write_all(&chunk[..len])
+            .map_err(|e| format!("Failed to write benchmark file: {}", e))?;
+        written += len;
+    }
+    file.sync_all()
+        .map_err(|e| format!("Failed to sync benchmark file: {}", e))?;
+    let sequential_mbps = throughput_mbps(file_size as u64, start.elapsed());
+
+    let blocks = (file_size / RANDOM_WRITE_SIZE).max(1) as u64;
+    let block = &chunk[..RANDOM_WRITE_SIZE.min(chunk.len())];
+    // xorshift, a fixed seed keeps runs comparable
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut rounds = Vec::new();
+    let start = Instant::now();
+
+    while rounds.len() < MAX_RANDOM_ROUNDS && start.elapsed() < budget {
+        if token.is_cancelled() {
+            return Err(CANCELLED_ERROR.to_string());
+        }
+        let round_start = Instant::now();
+        for _ in 0..RANDOM_WRITES_PER_ROUND {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let offset = (state % blocks) * RANDOM_WRITE_SIZE as u64;
+            file.seek(SeekFrom::Start(offset))
+                .and_then(|_| file.write_all(block))
+                .map_err(|e| format!("Failed to write benchmark file: {}", e))?;
+        }
+        file.sync_data()
+            .map_err(|e| format!("Failed to sync benchmark file: {}", e))?;
+        rounds.push(round_start.elapsed());
+    }
+
+    let total = start.elapsed().as_secs_f64().max(0.001);
+    let random_iops = ((rounds.len() * RANDOM_WRITES_PER_ROUND) as f64 / total) as u64;
+
+    Ok(DriveSpeedProfile {
+        sequential_mbps,
+        random_iops,
+        sustained_ratio: sustained_ratio(&rounds),
+    })
+}
+
+/// Speed of the last quarter of rounds relative to the first quarter
+/// Too few rounds to compare count as steady
+fn sustained_ratio(rounds: &[Duration]) -> f64 {
+    let quarter = rounds.len() / 4;
+    if quarter < 2 {
+        return 1.0;
+    }
+
+    let first: Duration = rounds[..quarter].iter().sum();
+    let last: Duration = rounds[rounds.len() - quarter..].iter().sum();
+    (first.as_secs_f64() / last.as_secs_f64().max(f64::EPSILON)).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn benchmarks_each_target_and_reports_unusable_ones() {
+        let fast = tempdir().unwrap();
+        let slow = tempdir().unwrap();
+        let not_a_dir = fast.path().join("file.txt");
+        std::fs::write(&not_a_dir, b"x").unwrap();
+
+        let targets = vec![
+            fast.path().to_string_lossy().to_string(),
+            not_a_dir.to_string_lossy().to_string(),
+            slow.path().to_string_lossy().to_string(),
+        ];
+        let results: Vec<_> = targets
+            .into_iter()
+            .map(|target| benchmark_target(target, 2, 256 * 1024))
+            .collect();
+
+        assert_eq!(results.len(), 3);
+        for index in [0, 2] {
+            assert!(results[index].error.is_none());
+            assert!(results[index].write_mbps > 0);
+            assert!(results[index].read_mbps > 0);
+        }
+        assert!(results[1].error.is_some());
+        assert_eq!(results[1].write_mbps, 0);
+
+        // Benchmark files are cleaned up afterwards
+        assert_eq!(std::fs::read_dir(slow.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn throughput_is_computed_in_megabytes_per_second() {
+        assert_eq!(
+            throughput_mbps(100 * 1024 * 1024, Duration::from_secs(2)),
+            50
+        );
+        assert_eq!(throughput_mbps(1024 * 1024, Duration::ZERO), 1000);
+    }
+
+    fn profile(sequential_mbps: u64, random_iops: u64, sustained_ratio: f64) -> DriveSpeedProfile {
+        DriveSpeedProfile {
+            sequential_mbps,
+            random_iops,
+            sustained_ratio,
+        }
+    }
+
+    #[test]
+    fn classifies_synthetic_speed_profiles() {
+        // NVMe, SATA SSD, a slow DRAM-less SSD
+        assert_eq!(
+            classify_profile(&profile(3200, 40000, 0.95)),
+            DriveClass::FastSsd
+        );
+        assert_eq!(classify_profile(&profile(520, 12000, 0.9)), DriveClass::Ssd);
+        assert_eq!(classify_profile(&profile(1800, 2500, 0.8)), DriveClass::Ssd);
+        // CMR hard drive: fine sequentially, seek-bound on random writes, but steady
+        assert_eq!(classify_profile(&profile(180, 150, 0.9)), DriveClass::Hdd);
+        // SMR drive: starts like an HDD, collapses once the cache zone is full
+        assert_eq!(
+            classify_profile(&profile(170, 120, 0.1)),
+            DriveClass::SlowSmr
+        );
+        assert_eq!(classify_profile(&profile(90, 12, 1.0)), DriveClass::SlowSmr);
+    }
+
+    #[test]
+    fn sustained_ratio_compares_last_rounds_to_first() {
+        let ms = Duration::from_millis;
+        assert_eq!(sustained_ratio(&[ms(10); 3]), 1.0);
+
+        let mut rounds = vec![ms(10); 8];
+        rounds.extend(vec![ms(50); 8]);
+        assert!((sustained_ratio(&rounds) - 0.2).abs() < 1e-9);
+
+        // Getting faster is still "steady"
+        rounds.reverse();
+        assert_eq!(sustained_ratio(&rounds), 1.0);
+    }
+
+    #[test]
+    fn measures_profile_and_stops_when_cancelled() {
+        let dir = tempdir().unwrap();
+        let bench_dir = dir.path().join("bench");
+
+        let profile = measure_profile(
+            &bench_dir,
+            256 * 1024,
+            Duration::from_millis(200),
+            &CancellationToken::default(),
+        )
+        .unwrap();
+        assert!(profile.sequential_mbps > 0);
+        assert!(profile.random_iops > 0);
+        assert!(profile.sustained_ratio > 0.0 && profile.sustained_ratio <= 1.0);
+
+        let token = CancellationToken::default();
+        token.cancel();
+        assert_eq!(
+            measure_profile(&bench_dir, 256 * 1024, Duration::from_secs(1), &token).unwrap_err(),
+            CANCELLED_ERROR
+        );
+    }
+
+    #[test]
+    fn estimates_transfer_time_plus_per_file_cost() {
+        const MB: u64 = 1024 * 1024;
+
+        // 1500 MB at 100 MB/s = 15s, plus 500 files * 2ms = 1s
+        assert_eq!(estimate_seconds(1500 * MB, 500, 100), 16);
+        // Partial seconds round up
+        assert_eq!(estimate_seconds(150 * MB, 0, 100), 2);
+        // Tiny archives never show "~0s", huge ones are capped
+        assert_eq!(estimate_seconds(1024, 1, 5000), MIN_ESTIMATE_SECS);
+        assert_eq!(estimate_seconds(u64::MAX, 0, 5), MAX_ESTIMATE_SECS);
+        assert_eq!(estimate_seconds(10 * MB, 0, 0), 10);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::dbpf;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// CAS part (clothing, hair, accessories...)
+const CAS_PART_TYPE: u32 = 0x034AEECB;
+
+/// Object definition and catalog object, present in every Build/Buy item
+const BUILD_BUY_TYPES: [u32; 2] = [0xC0DB5AE7, 0x319E4F1D];
+
+/// What kind of content a mod file provides
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModCategory {
+    #[serde(rename = "CAS")]
+    Cas,
+    BuildBuy,
+    Scripts,
+    Tuning,
+    /// Not a mod file, or a package with nothing recognisable
+    Other,
+}
+
+impl ModCategory {
+    /// Folder name used when organizing the library
+    pub fn folder_name(self) -> &'static str {
+        match self {
+            ModCategory::Cas => "CAS",
+            ModCategory::BuildBuy => "BuildBuy",
+            ModCategory::Scripts => "Scripts",
+            ModCategory::Tuning => "Tuning",
+            ModCategory::Other => "Other",
+        }
+    }
+}
+
+/// Classify a mod file from its extension and, for packages, its resource types
+/// CAS and Build/Buy content win over tuning, since most of those packages also carry tuning
+pub fn classify_file(path: &Path) -> ModCategory {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "ts4script" => ModCategory::Scripts,
+        "package" => dbpf::read_index(path)
+            .map(|entries| classify_types(entries.iter().map(|entry| entry.key.type_id)))
+            .unwrap_or(ModCategory::Other),
+        _ => ModCategory::Other,
+    }
+}
+
+fn classify_types(types: impl Iterator<Item = u32>) -> ModCategory {
+    let mut category = ModCategory::Other;
+
+    for type_id in types {
+        if type_id == CAS_PART_TYPE {
+            return ModCategory::Cas;
+        }
+        if BUILD_BUY_TYPES.contains(&type_id) {
+            category = ModCategory::BuildBuy;
+        } else if dbpf::is_tuning_type(type_id) && category == ModCategory::Other {
+            category = ModCategory::Tuning;
+        }
+    }
+
+    category
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cas_and_build_buy_take_precedence_over_tuning() {
+        assert_eq!(
+            classify_types([0x0333406C, CAS_PART_TYPE].into_iter()),
+            ModCategory::Cas
+        );
+        assert_eq!(
+            classify_types([0x545AC67A, 0xC0DB5AE7, 0x0333406C].into_iter()),
+            ModCategory::BuildBuy
+        );
+        assert_eq!(
+            classify_types([0x0333406C].into_iter()),
+            ModCategory::Tuning
+        );
+        assert_eq!(classify_types([0x00B2D882].into_iter()), ModCategory::Other);
+    }
+}
@@ -0,0 +1,270 @@
+use crate::dbpf;
+use crate::descriptor;
+use crate::overrides;
+use crate::script;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tauri::Emitter;
+
+/// First patch shipping Python 3.7, earlier builds run Python 3.3
+const PYTHON_37_PATCH: [u32; 2] = [1, 61];
+
+/// How likely a mod is to work on a given game version, most serious last
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum CompatibilityStatus {
+    Ok,
+    /// Looks like it replaces base-game tuning, which patches often change (a guess from
+    /// instance ids, see `overrides::find_likely_overrides`)
+    OverrideRisk,
+    /// Its descriptor names an older game version
+    Outdated,
+    /// Its scripts were compiled for another Python than the game runs
+    ScriptVersionMismatch,
+}
+
+/// Compatibility verdict for one mod file
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ModCompatibility {
+    #[serde(rename = "mod")]
+    pub mod_path: String,
+    /// Most serious of the problems found
+    pub status: CompatibilityStatus,
+    /// Every problem found, empty when `Ok`
+    pub reasons: Vec<String>,
+}
+
+/// Progress payload emitted on `compatibility://progress`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CompatibilityProgress {
+    pub operation_id: String,
+    pub checked: usize,
+    pub total: usize,
+}
+
+/// Check every package and script under `mods_dir` against `game_version` (e.g. "1.107.151")
+/// Combines likely base-game override detection, the Python version scripts were compiled for and the
+/// game version named in descriptors; emits `compatibility://progress` as mods are checked
+#[tauri::command(async)]
+pub fn compatibility_report(
+    app_handle: tauri::AppHandle,
+    mods_dir: String,
+    game_version: String,
+    operation_id: String,
+) -> Result<Vec<ModCompatibility>, String> {
+    check_library(Path::new(&mods_dir), &game_version, |checked, total| {
+        let _ = app_handle.emit(
+            "compatibility://progress",
+            CompatibilityProgress {
+                operation_id: operation_id.clone(),
+                checked,
+                total,
+            },
+        );
+    })
+}
+
+pub fn check_library(
+    mods_dir: &Path,
+    game_version: &str,
+    on_progress: impl Fn(usize, usize) + Sync,
+) -> Result<Vec<ModCompatibility>, String> {
+    if !mods_dir.is_dir() {
+        return Err(format!("Directory not found: {}", mods_dir.display()));
+    }
+    let game_version = parse_version(game_version)
+        .ok_or_else(|| format!("Invalid game version: {}", game_version))?;
+
+    let mut mods: Vec<PathBuf> = dbpf::find_packages(mods_dir);
+    mods.extend(script::find_scripts(mods_dir));
+    mods.sort();
+
+    let total = mods.len();
+    let checked = AtomicUsize::new(0);
+    on_progress(0, total);
+
+    Ok(mods
+        .par_iter()
+        .map(|path| {
+            let report = check_mod(path, &game_version);
+            on_progress(checked.fetch_add(1, Ordering::SeqCst) + 1, total);
+            report
+        })
+        .collect())
+}
+
+fn check_mod(path: &Path, game_version: &[u32]) -> ModCompatibility {
+    let mut problems: Vec<(CompatibilityStatus, String)> = Vec::new();
+    let is_script = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("ts4script"))
+        .unwrap_or(false);
+
+    if is_script {
+        let expected = game_python_version(game_version);
+        match script::compiled_python_versions(path) {
+            Ok(versions) => {
+                if let Some(other) = versions.iter().find(|version| *version != expected) {
+                    problems.push((
+                        CompatibilityStatus::ScriptVersionMismatch,
+                        format!(
+                            "Compiled for Python {}, the game runs Python {}",
+                            other, expected
+                        ),
+                    ));
+                }
+            }
+            Err(e) => problems.push((CompatibilityStatus::ScriptVersionMismatch, e)),
+        }
+    } else if let Ok(overrides) = overrides::package_likely_overrides(path) {
+        if !overrides.is_empty() {
+            problems.push((
+                CompatibilityStatus::OverrideRisk,
+                format!(
+                    "Likely overrides {} base-game resource(s), check for an update after patches",
+                    overrides.len()
+                ),
+            ));
+        }
+    }
+
+    let made_for = descriptor::read_mod_descriptor(path.to_string_lossy().to_string())
+        .and_then(|descriptor| descriptor.game_version);
+    if let Some(made_for) = made_for {
+        if let Some(version) = parse_version(&made_for) {
+            if older_patch(&version, game_version) {
+                problems.push((
+                    CompatibilityStatus::Outdated,
+                    format!("Made for game version {}", made_for),
+                ));
+            }
+        }
+    }
+
+    ModCompatibility {
+        mod_path: path.to_string_lossy().to_string(),
+        status: problems
+            .iter()
+            .map(|(status, _)| *status)
+            .max()
+            .unwrap_or(CompatibilityStatus::Ok),
+        reasons: problems.into_iter().map(|(_, reason)| reason).collect(),
+    }
+}
+
+/// "1.107.151.1020" -> [1, 107, 151, 1020], a leading "v" is accepted
+fn parse_version(version: &str) -> Option<Vec<u32>> {
+    let version = version.trim().trim_start_matches(['v', 'V']);
+    let parts: Option<Vec<u32>> = version.split('.').map(|p| p.trim().parse().ok()).collect();
+    parts.filter(|parts| !parts.is_empty())
+}
+
+/// Whether `version` is an older patch than `game`, builds of the same patch are compatible
+fn older_patch(version: &[u32], game: &[u32]) -> bool {
+    let patch = |v: &[u32]| [v[0], v.get(1).copied().unwrap_or(0)];
+    patch(version) < patch(game)
+}
+
+fn game_python_version(game_version: &[u32]) -> &'static str {
+    if older_patch(game_version, &PYTHON_37_PATCH) {
+        "3.3"
+    } else {
+        "3.7"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbpf::test_support::{build_package, key};
+    use crate::test_support::write_zip;
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// Header of a .pyc compiled by Python 3.7 and 3.3
+    const PYC_37: &[u8] = b"\x42\x0d\x0d\x0a\0\0\0\0";
+    const PYC_33: &[u8] = b"\x9e\x0c\x0d\x0a\0\0\0\0";
+
+    fn status_of<'a>(report: &'a [ModCompatibility], name: &str) -> &'a ModCompatibility {
+        report
+            .iter()
+            .find(|entry| entry.mod_path.ends_with(name))
+            .unwrap()
+    }
+
+    #[test]
+    fn reports_each_status() {
+        let dir = tempdir().unwrap();
+        let mods = dir.path();
+        fs::write(
+            mods.join("own_content.package"),
+            build_package(&[(key(0x6017E896, 0, 0x9A3C_5E11_02B4_77D0), b"<I/>".to_vec())]),
+        )
+        .unwrap();
+        fs::write(
+            mods.join("buff_override.package"),
+            build_package(&[(key(0x6017E896, 0, 12345), b"<I/>".to_vec())]),
+        )
+        .unwrap();
+        fs::write(
+            mods.join("old_hair.package"),
+            build_package(&[(key(0x034AEECB, 0, 1), b"cas".to_vec())]),
+        )
+        .unwrap();
+        fs::write(
+            mods.join("old_hair.txt"),
+            "Name: Old Hair\nTested on: 1.98.127\n",
+        )
+        .unwrap();
+        write_zip(&mods.join("current.ts4script"), &[("mod/main.pyc", PYC_37)]);
+        write_zip(&mods.join("legacy.ts4script"), &[("mod/main.pyc", PYC_33)]);
+
+        let progress = AtomicUsize::new(0);
+        let report = check_library(mods, "1.107.151.1020", |checked, _| {
+            progress.fetch_max(checked, Ordering::SeqCst);
+        })
+        .unwrap();
+
+        assert_eq!(report.len(), 5);
+        assert_eq!(progress.load(Ordering::SeqCst), 5);
+        for name in ["own_content.package", "current.ts4script"] {
+            let entry = status_of(&report, name);
+            assert_eq!(entry.status, CompatibilityStatus::Ok);
+            assert!(entry.reasons.is_empty());
+        }
+        assert_eq!(
+            status_of(&report, "buff_override.package").status,
+            CompatibilityStatus::OverrideRisk
+        );
+        let old_hair = status_of(&report, "old_hair.package");
+        assert_eq!(old_hair.status, CompatibilityStatus::Outdated);
+        assert!(old_hair.reasons[0].contains("1.98.127"));
+        let legacy = status_of(&report, "legacy.ts4script");
+        assert_eq!(legacy.status, CompatibilityStatus::ScriptVersionMismatch);
+        assert!(legacy.reasons[0].contains("Python 3.3"));
+    }
+
+    #[test]
+    fn old_games_expect_python_33_scripts() {
+        let dir = tempdir().unwrap();
+        write_zip(&dir.path().join("legacy.ts4script"), &[("mod.pyc", PYC_33)]);
+        write_zip(
+            &dir.path().join("current.ts4script"),
+            &[("mod.pyc", PYC_37)],
+        );
+
+        let report = check_library(dir.path(), "1.58", |_, _| {}).unwrap();
+
+        assert_eq!(
+            status_of(&report, "legacy.ts4script").status,
+            CompatibilityStatus::Ok
+        );
+        assert_eq!(
+            status_of(&report, "current.ts4script").status,
+            CompatibilityStatus::ScriptVersionMismatch
+        );
+        assert!(check_library(dir.path(), "latest", |_, _| {}).is_err());
+    }
+}
@@ -0,0 +1,690 @@
+use crate::cpu;
+use crate::dbpf::{self, ResourceKey, NAME_MAP_TYPE, SIMDATA_TYPE, XML_TUNING_TYPES};
+use crate::operations::{CancellationToken, OperationRegistry, CANCELLED_ERROR};
+use crate::script;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+
+/// Number of packages parsed per batch, progress and cancellation are checked between batches
+const SCAN_BATCH_SIZE: usize = 64;
+
+/// Resource key -> indexes of the packages containing it
+type KeyOwners = HashMap<ResourceKey, Vec<usize>>;
+
+/// Human-readable category of the resource types mods most often collide on
+const RESOURCE_CATEGORIES: [(u32, &str); 12] = [
+    (0x034AEECB, "CAS part"),
+    (0x3C1AF1F2, "Thumbnail"),
+    (0x00B2D882, "Image"),
+    (0xC0DB5AE7, "Object definition"),
+    (0x319E4F1D, "Catalog object"),
+    (0x015A1849, "Geometry"),
+    (0x01661233, "Model"),
+    (0x220557DA, "String table"),
+    (0x0333406C, "XML tuning"),
+    (0x545AC67A, "SimData"),
+    (0x6017E896, "Buff"),
+    (0xE882D22F, "Interaction"),
+];
+
+/// Resource key shared by several packages
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PackageConflict {
+    /// Key formatted as TYPE:GROUP:INSTANCE
+    pub resource_key: String,
+    pub type_id: u32,
+    pub group: u32,
+    pub instance: u64,
+    /// Packages containing this key, sorted by path
+    pub packages: Vec<String>,
+    /// Number of packages sharing the key
+    pub severity: usize,
+}
+
+/// Python module shipped by several script mods, only one of them wins at load time
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ScriptModuleConflict {
+    /// Fully-qualified module name
+    pub module: String,
+    /// .ts4script files providing the module, sorted by path
+    pub scripts: Vec<String>,
+}
+
+/// Result of a conflict scan over a mods directory
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConflictScanResult {
+    /// Conflicts sorted by severity, most shared keys first
+    pub conflicts: Vec<PackageConflict>,
+    /// Number of packages that were indexed
+    pub scanned_packages: usize,
+    /// Packages and scripts that could not be parsed, with the reason
+    pub unreadable_packages: Vec<String>,
+    /// Modules defined by more than one .ts4script, sorted by module name
+    pub script_conflicts: Vec<ScriptModuleConflict>,
+    /// Number of .ts4script files that were inspected
+    pub scanned_scripts: usize,
+}
+
+/// Resource key owned by more than one package anywhere in the library
+#[derive(Serialize, Deserialize, Debug)]
+pub struct KeyCollision {
+    /// Key formatted as TYPE:GROUP:INSTANCE
+    pub resource_key: String,
+    pub type_id: u32,
+    /// Human-readable resource category, e.g. "CAS part"
+    pub category: String,
+    /// Packages containing this key, sorted by path
+    pub packages: Vec<String>,
+}
+
+/// Tuning name defined by more than one package under different instance ids
+/// Instance conflicts miss these, but the game resolves tuning by name too and only one wins
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TuningNameCollision {
+    /// Tuning name as written by the first package, e.g. "creator:Buff_Happy"
+    pub name: String,
+    /// Distinct instance ids the name is defined under, as 16-digit hex
+    pub instances: Vec<String>,
+    /// Packages defining the name, sorted by path
+    pub packages: Vec<String>,
+}
+
+/// Progress payload emitted on `conflict-scan://progress`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ConflictScanProgress {
+    pub operation_id: String,
+    pub scanned: usize,
+    pub total: usize,
+}
+
+/// Find resource keys shared by several packages in a mods directory
+/// Packages are indexed in parallel batches, emitting `conflict-scan://progress` after each batch
+/// The scan can be stopped with `cancel_operation(operation_id)`
+#[tauri::command(async)]
+pub fn find_package_conflicts(
+    app_handle: tauri::AppHandle,
+    registry: tauri::State<'_, OperationRegistry>,
+    mods_dir: String,
+    operation_id: String,
+) -> Result<ConflictScanResult, String> {
+    let operation = registry.start(&operation_id);
+
+    scan_conflicts(Path::new(&mods_dir), operation.token(), |scanned, total| {
+        let _ = app_handle.emit(
+            "conflict-scan://progress",
+            ConflictScanProgress {
+                operation_id: operation_id.clone(),
+                scanned,
+                total,
+            },
+        );
+    })
+}
+
+/// Map every resource key in the library to the packages that contain it
+/// Reports every key owned by more than one package, the most shared first, with its category
+/// Emits `key-collisions://progress` and can be stopped with `cancel_operation(operation_id)`
+#[tauri::command(async)]
+pub fn find_global_key_collisions(
+    app_handle: tauri::AppHandle,
+    registry: tauri::State<'_, OperationRegistry>,
+    mods_dir: String,
+    operation_id: String,
+) -> Result<Vec<KeyCollision>, String> {
+    let operation = registry.start(&operation_id);
+
+    global_key_collisions(Path::new(&mods_dir), operation.token(), |scanned, total| {
+        let _ = app_handle.emit(
+            "key-collisions://progress",
+            ConflictScanProgress {
+                operation_id: operation_id.clone(),
+                scanned,
+                total,
+            },
+        );
+    })
+}
+
+fn global_key_collisions(
+    mods_dir: &Path,
+    token: &CancellationToken,
+    on_progress: impl Fn(usize, usize),
+) -> Result<Vec<KeyCollision>, String> {
+    if !mods_dir.is_dir() {
+        return Err(format!("Directory not found: {}", mods_dir.display()));
+    }
+
+    let packages = dbpf::find_packages(mods_dir);
+    let (owners, _) = index_owners(&packages, token, on_progress)?;
+
+    Ok(collect_conflicts(owners, &packages)
+        .into_iter()
+        .map(|conflict| KeyCollision {
+            category: category_label(conflict.type_id).to_string(),
+            resource_key: conflict.resource_key,
+            type_id: conflict.type_id,
+            packages: conflict.packages,
+        })
+        .collect())
+}
+
+/// Find tuning names defined by several packages under different instances
+/// XML tuning is named by its root `n` attribute, binary tuning by the package's name map
+/// Emits `tuning-names://progress` and can be stopped with `cancel_operation(operation_id)`
+#[tauri::command(async)]
+pub fn find_tuning_name_collisions(
+    app_handle: tauri::AppHandle,
+    registry: tauri::State<'_, OperationRegistry>,
+    mods_dir: String,
+    operation_id: String,
+) -> Result<Vec<TuningNameCollision>, String> {
+    let operation = registry.start(&operation_id);
+
+    tuning_name_collisions(Path::new(&mods_dir), operation.token(), |scanned, total| {
+        let _ = app_handle.emit(
+            "tuning-names://progress",
+            ConflictScanProgress {
+                operation_id: operation_id.clone(),
+                scanned,
+                total,
+            },
+        );
+    })
+}
+
+fn tuning_name_collisions(
+    mods_dir: &Path,
+    token: &CancellationToken,
+    on_progress: impl Fn(usize, usize),
+) -> Result<Vec<TuningNameCollision>, String> {
+    if !mods_dir.is_dir() {
+        return Err(format!("Directory not found: {}", mods_dir.display()));
+    }
+
+    let packages = dbpf::find_packages(mods_dir);
+    let results = scan_packages(&packages, token, on_progress, tuning_names)?;
+
+    // Lowercased name -> (name as first seen, instances, owning packages)
+    let mut names: BTreeMap<String, (String, BTreeSet<u64>, BTreeSet<usize>)> = BTreeMap::new();
+    for (package_index, result) in results.into_iter().enumerate() {
+        // Packages whose index can't be read are reported by the conflict scan
+        let Ok(tuning) = result else {
+            continue;
+        };
+        for (name, instance) in tuning {
+            let entry = names
+                .entry(name.to_lowercase())
+                .or_insert_with(|| (name, BTreeSet::new(), BTreeSet::new()));
+            entry.1.insert(instance);
+            entry.2.insert(package_index);
+        }
+    }
+
+    Ok(names
+        .into_values()
+        .filter(|(_, instances, owners)| instances.len() > 1 && owners.len() > 1)
+        .map(|(name, instances, owners)| TuningNameCollision {
+            name,
+            instances: instances
+                .iter()
+                .map(|instance| format!("{:016X}", instance))
+                .collect(),
+            packages: owners
+                .iter()
+                .map(|&i| packages[i].to_string_lossy().to_string())
+                .collect(),
+        })
+        .collect())
+}
+
+/// (tuning name, instance) of every named tuning resource in a package
+/// A resource that fails to read is skipped, the rest of the package is still named
+fn tuning_names(path: &Path) -> Result<Vec<(String, u64)>, String> {
+    let entries = dbpf::read_index(path)?;
+    let mut reader = BufReader::new(
+        File::open(path)
+            .map_err(|e| format!("Failed to open package {}: {}", path.display(), e))?,
+    );
+
+    let mut name_map = HashMap::new();
+    for entry in entries.iter().filter(|e| e.key.type_id == NAME_MAP_TYPE) {
+        if let Ok(data) = dbpf::read_resource(&mut reader, entry) {
+            name_map.extend(dbpf::parse_name_map(&data));
+        }
+    }
+
+    let mut names = Vec::new();
+    for entry in &entries {
+        let instance = entry.key.instance;
+        let name = if XML_TUNING_TYPES.contains(&entry.key.type_id) {
+            dbpf::read_resource(&mut reader, entry)
+                .ok()
+                .and_then(|data| xml_tuning_name(&data))
+                .or_else(|| name_map.get(&instance).cloned())
+        } else if entry.key.type_id == SIMDATA_TYPE {
+            // SimData is named only through the package's name map
+            name_map.get(&instance).cloned()
+        } else {
+            None
+        };
+        if let Some(name) = name.filter(|name| !name.is_empty()) {
+            names.push((name, instance));
+        }
+    }
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+/// `n` attribute of the root `<I>` / `<M>` element of XML tuning
+pub(crate) fn xml_tuning_name(data: &[u8]) -> Option<String> {
+    let text = std::str::from_utf8(data).ok()?;
+    let root = text
+        .match_indices('<')
+        .map(|(start, _)| &text[start + 1..])
+        .find(|tag| {
+            (tag.starts_with('I') || tag.starts_with('M'))
+                && tag[1..].starts_with(char::is_whitespace)
+        })?;
+    let root = &root[..root.find('>')?];
+
+    root.split_whitespace().find_map(|attribute| {
+        attribute
+            .strip_prefix("n=")
+            .map(|value| value.trim_end_matches('/').trim_matches('"').to_string())
+    })
+}
+
+/// Category label of a resource type, "Other" for types we have no name for
+fn category_label(type_id: u32) -> &'static str {
+    RESOURCE_CATEGORIES
+        .iter()
+        .find(|(known, _)| *known == type_id)
+        .map(|(_, label)| *label)
+        .unwrap_or("Other")
+}
+
+/// Index every package under `mods_dir` and report keys owned by more than one package
+pub(crate) fn scan_conflicts(
+    mods_dir: &Path,
+    token: &CancellationToken,
+    on_progress: impl Fn(usize, usize),
+) -> Result<ConflictScanResult, String> {
+    let packages = dbpf::find_packages(mods_dir);
+    let total = packages.len();
+    let (owners, mut unreadable_packages) = index_owners(&packages, token, on_progress)?;
+    let scanned_packages = total - unreadable_packages.len();
+
+    if token.is_cancelled() {
+        return Err(CANCELLED_ERROR.to_string());
+    }
+
+    let scripts = script::find_scripts(mods_dir);
+    let mut scanned_scripts = 0;
+    let mut module_owners: HashMap<String, Vec<usize>> = HashMap::new();
+    for (script_index, path) in scripts.iter().enumerate() {
+        match script::inspect_script(path) {
+            Ok(inspection) => {
+                scanned_scripts += 1;
+                for module in inspection.modules {
+                    module_owners.entry(module).or_default().push(script_index);
+                }
+            }
+            Err(e) => unreadable_packages.push(e),
+        }
+    }
+
+    Ok(ConflictScanResult {
+        conflicts: collect_conflicts(owners, &packages),
+        scanned_packages,
+        unreadable_packages,
+        script_conflicts: collect_script_conflicts(module_owners, &scripts),
+        scanned_scripts,
+    })
+}
+
+/// Index packages in parallel batches into a key -> owning package indexes map
+/// Returns the map and the errors of the packages that could not be read
+fn index_owners(
+    packages: &[PathBuf],
+    token: &CancellationToken,
+    on_progress: impl Fn(usize, usize),
+) -> Result<(KeyOwners, Vec<String>), String> {
+    let results = scan_packages(packages, token, on_progress, |path| {
+        dbpf::read_index(path).map(|entries| unique_keys(&entries))
+    })?;
+
+    let mut owners = KeyOwners::new();
+    let mut unreadable_packages = Vec::new();
+    for (package_index, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(keys) => {
+                for key in keys {
+                    owners.entry(key).or_default().push(package_index);
+                }
+            }
+            Err(e) => unreadable_packages.push(e),
+        }
+    }
+
+    Ok((owners, unreadable_packages))
+}
+
+/// Run `read` over every package in parallel batches, returning the results in package order
+/// Progress and cancellation are checked between batches
+fn scan_packages<T: Send>(
+    packages: &[PathBuf],
+    token: &CancellationToken,
+    on_progress: impl Fn(usize, usize),
+    read: impl Fn(&Path) -> Result<T, String> + Sync,
+) -> Result<Vec<Result<T, String>>, String> {
+    let total = packages.len();
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(cpu::recommended_workers().scanning)
+        .build()
+        .map_err(|e| format!("Failed to create scan thread pool: {}", e))?;
+
+    let mut results = Vec::with_capacity(total);
+    on_progress(0, total);
+
+    for batch in packages.chunks(SCAN_BATCH_SIZE) {
+        if token.is_cancelled() {
+            return Err(CANCELLED_ERROR.to_string());
+        }
+
+        let batch_results: Vec<Result<T, String>> =
+            pool.install(|| batch.par_iter().map(|path| read(path)).collect());
+        results.extend(batch_results);
+        on_progress(results.len(), total);
+    }
+
+    Ok(results)
+}
+
+/// Turn the module → scripts map into conflicts, sorted by module name
+fn collect_script_conflicts(
+    owners: HashMap<String, Vec<usize>>,
+    scripts: &[PathBuf],
+) -> Vec<ScriptModuleConflict> {
+    let mut conflicts: Vec<ScriptModuleConflict> = owners
+        .into_iter()
+        .filter(|(_, owners)| owners.len() > 1)
+        .map(|(module, owners)| ScriptModuleConflict {
+            module,
+            scripts: owners
+                .iter()
+                .map(|&i| scripts[i].to_string_lossy().to_string())
+                .collect(),
+        })
+        .collect();
+
+    conflicts.sort_by(|a, b| a.module.cmp(&b.module));
+    conflicts
+}
+
+/// Deduplicate keys within one package so a package never conflicts with itself
+fn unique_keys(entries: &[dbpf::IndexEntry]) -> Vec<ResourceKey> {
+    let mut keys: Vec<ResourceKey> = entries.iter().map(|entry| entry.key).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    keys
+}
+
+/// Turn the key → owners map into conflicts, most shared keys first
+fn collect_conflicts(owners: KeyOwners, packages: &[PathBuf]) -> Vec<PackageConflict> {
+    let mut conflicts: Vec<PackageConflict> = owners
+        .into_iter()
+        .filter(|(_, owners)| owners.len() > 1)
+        .map(|(key, owners)| PackageConflict {
+            resource_key: key.to_string(),
+            type_id: key.type_id,
+            group: key.group,
+            instance: key.instance,
+            severity: owners.len(),
+            packages: owners
+                .iter()
+                .map(|&i| packages[i].to_string_lossy().to_string())
+                .collect(),
+        })
+        .collect();
+
+    conflicts.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| a.resource_key.cmp(&b.resource_key))
+    });
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbpf::test_support::{build_package, build_zlib_package, key, name_map};
+    use crate::test_support::write_zip;
+    use std::fs;
+    use std::sync::Mutex;
+    use tempfile::tempdir;
+
+    fn write_package(dir: &Path, name: &str, keys: &[ResourceKey]) {
+        let resources: Vec<_> = keys.iter().map(|k| (*k, b"data".to_vec())).collect();
+        fs::write(dir.join(name), build_package(&resources)).unwrap();
+    }
+
+    #[test]
+    fn reports_overlapping_keys_sorted_by_severity() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("sub")).unwrap();
+        let shared_by_three = key(0x545AC67A, 0, 1);
+        let shared_by_two = key(0x0333406C, 0, 2);
+
+        write_package(dir.path(), "a.package", &[shared_by_three, shared_by_two]);
+        write_package(dir.path(), "b.package", &[shared_by_three, key(1, 0, 10)]);
+        write_package(
+            &dir.path().join("sub"),
+            "c.package",
+            &[shared_by_three, shared_by_two],
+        );
+        write_package(dir.path(), "d.package", &[key(1, 0, 11), key(1, 0, 11)]);
+        fs::write(dir.path().join("broken.package"), b"not a package").unwrap();
+
+        let progress = Mutex::new(Vec::new());
+        let result = scan_conflicts(dir.path(), &CancellationToken::default(), |done, total| {
+            progress.lock().unwrap().push((done, total))
+        })
+        .unwrap();
+
+        assert_eq!(result.scanned_packages, 4);
+        assert_eq!(result.unreadable_packages.len(), 1);
+        assert_eq!(result.conflicts.len(), 2);
+        assert_eq!(
+            result.conflicts[0].resource_key,
+            shared_by_three.to_string()
+        );
+        assert_eq!(result.conflicts[0].severity, 3);
+        assert_eq!(result.conflicts[1].severity, 2);
+        assert!(result.conflicts[1].packages[1].ends_with("c.package"));
+        assert_eq!(progress.into_inner().unwrap().last(), Some(&(5, 5)));
+    }
+
+    #[test]
+    fn reports_modules_shared_by_script_mods() {
+        let dir = tempdir().unwrap();
+        write_zip(
+            &dir.path().join("a.ts4script"),
+            &[
+                ("shared/__init__.pyc", b""),
+                ("shared/utils.pyc", b""),
+                ("a_only.pyc", b""),
+            ],
+        );
+        write_zip(
+            &dir.path().join("b.ts4script"),
+            &[("shared/utils.pyc", b""), ("shared/utils.py", b"")],
+        );
+        write_zip(&dir.path().join("c.ts4script"), &[("c_only.pyc", b"")]);
+
+        let result = scan_conflicts(dir.path(), &CancellationToken::default(), |_, _| {}).unwrap();
+
+        assert_eq!(result.scanned_scripts, 3);
+        assert_eq!(result.script_conflicts.len(), 1);
+        assert_eq!(result.script_conflicts[0].module, "shared.utils");
+        assert!(result.script_conflicts[0].scripts[0].ends_with("a.ts4script"));
+        assert!(result.script_conflicts[0].scripts[1].ends_with("b.ts4script"));
+        assert!(result.conflicts.is_empty());
+    }
+
+    #[test]
+    fn maps_every_collision_across_the_library() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("CAS")).unwrap();
+        let hair = key(0x034AEECB, 0, 0x80000000_00000001);
+        let hair_thumbnail = key(0x3C1AF1F2, 0, 0x80000000_00000001);
+        let tuning = key(0x0333406C, 0, 42);
+        let unknown = key(0x12345678, 0, 7);
+
+        write_package(dir.path(), "a.package", &[hair, hair_thumbnail, tuning]);
+        write_package(
+            &dir.path().join("CAS"),
+            "b.package",
+            &[hair, hair_thumbnail],
+        );
+        write_package(dir.path(), "c.package", &[hair, tuning, unknown]);
+        write_package(dir.path(), "d.package", &[unknown, key(1, 0, 1)]);
+
+        let progress = Mutex::new(Vec::new());
+        let collisions =
+            global_key_collisions(dir.path(), &CancellationToken::default(), |done, total| {
+                progress.lock().unwrap().push((done, total))
+            })
+            .unwrap();
+
+        assert_eq!(collisions.len(), 4);
+        assert_eq!(collisions[0].resource_key, hair.to_string());
+        assert_eq!(collisions[0].category, "CAS part");
+        assert_eq!(collisions[0].packages.len(), 3);
+        assert!(collisions[0].packages[0].ends_with("CAS/b.package"));
+        assert!(collisions[1..].iter().all(|c| c.packages.len() == 2));
+
+        let category_of = |k: ResourceKey| {
+            collisions
+                .iter()
+                .find(|c| c.resource_key == k.to_string())
+                .map(|c| c.category.as_str())
+        };
+        assert_eq!(category_of(hair_thumbnail), Some("Thumbnail"));
+        assert_eq!(category_of(tuning), Some("XML tuning"));
+        assert_eq!(category_of(unknown), Some("Other"));
+        assert_eq!(progress.into_inner().unwrap().last(), Some(&(4, 4)));
+    }
+
+    #[test]
+    fn stops_when_cancelled() {
+        let dir = tempdir().unwrap();
+        write_package(dir.path(), "a.package", &[key(1, 0, 1)]);
+
+        let token = CancellationToken::default();
+        token.cancel();
+
+        let result = scan_conflicts(dir.path(), &token, |_, _| {});
+        assert_eq!(result.unwrap_err(), CANCELLED_ERROR);
+        let result = global_key_collisions(dir.path(), &token, |_, _| {});
+        assert_eq!(result.unwrap_err(), CANCELLED_ERROR);
+        let result = tuning_name_collisions(dir.path(), &token, |_, _| {});
+        assert_eq!(result.unwrap_err(), CANCELLED_ERROR);
+    }
+
+    #[test]
+    fn reports_tuning_names_shared_under_different_instances() {
+        let dir = tempdir().unwrap();
+        let buff = |instance: u64| {
+            format!(
+                "<?xml version=\"1.0\"?>\n<I c=\"Buff\" i=\"buff\" m=\"buffs.buff\" n=\"creator:Buff_Happy\" s=\"{}\">\n</I>",
+                instance
+            )
+            .into_bytes()
+        };
+        fs::write(
+            dir.path().join("a.package"),
+            build_package(&[(key(0x6017E896, 0, 10), buff(10))]),
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("b.package"),
+            build_package(&[(key(0x6017E896, 0, 20), buff(20))]),
+        )
+        .unwrap();
+        // Binary tuning named through the name map, under another instance and case
+        fs::write(
+            dir.path().join("c.package"),
+            build_package(&[
+                (key(0x545AC67A, 0, 30), b"DATA".to_vec()),
+                (
+                    key(NAME_MAP_TYPE, 0, 1),
+                    name_map(&[(30, "Creator:buff_happy")]),
+                ),
+            ]),
+        )
+        .unwrap();
+        // Same name and instance in two packages is an ordinary key conflict, not reported here
+        let trait_tuning =
+            b"<I c=\"Trait\" i=\"trait\" m=\"traits.traits\" n=\"creator:Trait_Kind\" s=\"40\"/>";
+        for name in ["d.package", "e.package"] {
+            fs::write(
+                dir.path().join(name),
+                build_package(&[(key(0x0333406C, 0, 40), trait_tuning.to_vec())]),
+            )
+            .unwrap();
+        }
+
+        let collisions =
+            tuning_name_collisions(dir.path(), &CancellationToken::default(), |_, _| {}).unwrap();
+
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].name, "creator:Buff_Happy");
+        assert_eq!(
+            collisions[0].instances,
+            vec!["000000000000000A", "0000000000000014", "000000000000001E"]
+        );
+        let names: Vec<&str> = collisions[0]
+            .packages
+            .iter()
+            .map(|p| p.rsplit(['/', '\\']).next().unwrap())
+            .collect();
+        assert_eq!(names, vec!["a.package", "b.package", "c.package"]);
+    }
+
+    #[test]
+    fn skips_unreadable_tuning_but_keeps_the_package() {
+        let dir = tempdir().unwrap();
+        let buff = |instance: u64| {
+            format!("<I c=\"Buff\" n=\"creator:Buff_Sad\" s=\"{}\"/>", instance).into_bytes()
+        };
+        fs::write(
+            dir.path().join("a.package"),
+            build_package(&[(key(0x6017E896, 0, 10), buff(10))]),
+        )
+        .unwrap();
+        let mut package = build_zlib_package(&[
+            (key(0x6017E896, 0, 99), buff(99)),
+            (key(0x6017E896, 0, 20), buff(20)),
+        ]);
+        // Break the zlib header of the first resource, stored right after the 96-byte header
+        package[96..98].copy_from_slice(&[0xFF, 0xFF]);
+        fs::write(dir.path().join("b.package"), package).unwrap();
+
+        let collisions =
+            tuning_name_collisions(dir.path(), &CancellationToken::default(), |_, _| {}).unwrap();
+
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(
+            collisions[0].instances,
+            vec!["000000000000000A", "0000000000000014"]
+        );
+        assert_eq!(collisions[0].packages.len(), 2);
+    }
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+/// Past this many threads, index parsing and hashing are limited by the disk, not the CPU
+const MAX_IO_WORKERS: usize = 8;
+
+/// Parallel downloads beyond this rarely speed anything up and get throttled by mod hosts
+const MAX_DOWNLOADS: usize = 4;
+
+/// Default worker counts for each kind of job
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RecommendedWorkers {
+    /// Decompression and file writes (CPU bound), one core is left for the UI
+    pub extraction: usize,
+    /// Package indexing, hashing and other disk-bound scans
+    pub scanning: usize,
+    /// Concurrent downloads (network bound)
+    pub downloads: usize,
+}
+
+/// Core counts of this machine and the parallelism derived from them
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CpuInfo {
+    pub logical_cores: usize,
+    pub physical_cores: usize,
+    pub recommended_workers: RecommendedWorkers,
+}
+
+/// Report core counts and the default worker counts the app uses
+#[tauri::command]
+pub fn get_cpu_info() -> CpuInfo {
+    let logical_cores = num_cpus::get().max(1);
+    let physical_cores = num_cpus::get_physical().clamp(1, logical_cores);
+
+    CpuInfo {
+        logical_cores,
+        physical_cores,
+        recommended_workers: recommend_workers(logical_cores, physical_cores),
+    }
+}
+
+/// Worker counts for this machine
+pub fn recommended_workers() -> RecommendedWorkers {
+    get_cpu_info().recommended_workers
+}
+
+fn recommend_workers(logical_cores: usize, physical_cores: usize) -> RecommendedWorkers {
+    RecommendedWorkers {
+        extraction: logical_cores.saturating_sub(1).max(1),
+        scanning: logical_cores.min(MAX_IO_WORKERS),
+        downloads: physical_cores.clamp(2, MAX_DOWNLOADS),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_consistent_core_counts() {
+        let info = get_cpu_info();
+
+        assert!(info.logical_cores > 0);
+        assert!(info.physical_cores > 0);
+        assert!(info.physical_cores <= info.logical_cores);
+
+        let workers = info.recommended_workers;
+        assert!(workers.extraction >= 1 && workers.extraction <= info.logical_cores);
+        assert!(workers.scanning >= 1 && workers.scanning <= MAX_IO_WORKERS);
+        assert!(workers.downloads >= 2 && workers.downloads <= MAX_DOWNLOADS);
+    }
+
+    #[test]
+    fn balances_cpu_and_io_work() {
+        assert_eq!(
+            recommend_workers(1, 1),
+            RecommendedWorkers {
+                extraction: 1,
+                scanning: 1,
+                downloads: 2
+            }
+        );
+        assert_eq!(
+            recommend_workers(32, 16),
+            RecommendedWorkers {
+                extraction: 31,
+                scanning: MAX_IO_WORKERS,
+                downloads: MAX_DOWNLOADS
+            }
+        );
+    }
+}
@@ -0,0 +1,720 @@
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Magic bytes at the start of every Sims 4 package file
+pub const DBPF_MAGIC: &[u8; 4] = b"DBPF";
+
+/// Size of the DBPF 2.x header in bytes
+const HEADER_SIZE: usize = 96;
+
+/// Index flag bits marking fields shared by every entry (stored once after the flags)
+const INDEX_CONSTANT_TYPE: u32 = 0x1;
+const INDEX_CONSTANT_GROUP: u32 = 0x2;
+const INDEX_CONSTANT_INSTANCE_HIGH: u32 = 0x4;
+/// Flags of an index where every entry stores its full key
+const INDEX_FLAGS_NONE: u32 = 0x0;
+
+/// Compression types stored in index entries
+const COMPRESSION_NONE: u16 = 0x0000;
+const COMPRESSION_ZLIB: u16 = 0x5A42;
+const COMPRESSION_REFPACK: u16 = 0xFFFF;
+const COMPRESSION_STREAMABLE: u16 = 0xFFFE;
+/// Compression marker of entries that were deleted but left in the index
+const COMPRESSION_DELETED: u16 = 0xFFE0;
+
+/// Largest resource we decompress in memory, guards against corrupt size fields
+const MAX_RESOURCE_SIZE: u32 = 256 * 1024 * 1024;
+
+/// Tuning types whose resources are XML, the tuning name is the root's `n` attribute
+/// Generic tuning plus the dedicated types mods most often ship (buff, interaction, trait,
+/// loot, snippet, object, career, aspiration)
+pub const XML_TUNING_TYPES: [u32; 10] = [
+    0x0333406C, 0x03B33DDF, 0x6017E896, 0xE882D22F, 0xCB5FDDC7, 0x0C772E27, 0x7DF2169C, 0xB61DE6B4,
+    0x73996BEB, 0x28B64675,
+];
+
+/// Binary tuning, shares the instance of the XML tuning it belongs to
+pub const SIMDATA_TYPE: u32 = 0x545AC67A;
+
+/// Name map, maps instance ids of the package's resources to their names
+pub const NAME_MAP_TYPE: u32 = 0x0166038C;
+
+/// XML tuning or SimData
+pub fn is_tuning_type(type_id: u32) -> bool {
+    type_id == SIMDATA_TYPE || XML_TUNING_TYPES.contains(&type_id)
+}
+
+/// (instance, name) entries of a name map resource: version, count, then (instance, name
+/// length, UTF-8 name) per entry; a truncated map yields the entries read so far
+pub fn parse_name_map(data: &[u8]) -> Vec<(u64, String)> {
+    let mut names = Vec::new();
+    let Some(count) = data.get(4..8) else {
+        return names;
+    };
+    let count = u32::from_le_bytes(count.try_into().unwrap());
+
+    let mut at = 8usize;
+    for _ in 0..count {
+        let Some(header) = data.get(at..at.saturating_add(12)) else {
+            break;
+        };
+        let instance = u64::from_le_bytes(header[..8].try_into().unwrap());
+        let len = u32::from_le_bytes(header[8..].try_into().unwrap()) as usize;
+        let start = at + 12;
+        let Some(name) = data.get(start..start.saturating_add(len)) else {
+            break;
+        };
+        names.push((instance, String::from_utf8_lossy(name).to_string()));
+        at = start + len;
+    }
+    names
+}
+
+/// Type/Group/Instance triple identifying a resource inside a package
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ResourceKey {
+    pub type_id: u32,
+    pub group: u32,
+    pub instance: u64,
+}
+
+impl fmt::Display for ResourceKey {
+    /// Formats the key the way modding tools (S4PE, Sims 4 Studio) display it
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:08X}:{:08X}:{:016X}",
+            self.type_id, self.group, self.instance
+        )
+    }
+}
+
+/// Single resource entry from a package index
+#[derive(Clone, Debug)]
+pub struct IndexEntry {
+    pub key: ResourceKey,
+    /// Absolute offset of the resource data in the package
+    pub offset: u32,
+    /// Size of the stored (possibly compressed) data
+    pub file_size: u32,
+    /// Size of the data once decompressed
+    pub mem_size: u32,
+    /// Compression type (0x0000 none, 0x5A42 zlib, 0xFFFF/0xFFFE RefPack)
+    pub compression: u16,
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+/// Read the resource index of a package file
+/// Only the header and index are read, resource data is left untouched
+pub fn read_index(path: &Path) -> Result<Vec<IndexEntry>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open package {}: {}", path.display(), e))?;
+
+    read_index_from(&mut BufReader::new(file))
+        .map_err(|e| format!("Invalid package {}: {}", path.display(), e))
+}
+
+/// Read the resource index from any seekable DBPF source
+pub fn read_index_from<R: Read + Seek>(reader: &mut R) -> Result<Vec<IndexEntry>, String> {
+    let mut header = [0u8; HEADER_SIZE];
+    reader
+        .read_exact(&mut header)
+        .map_err(|_| "file is too small to be a package".to_string())?;
+
+    if &header[0..4] != DBPF_MAGIC {
+        return Err("missing DBPF magic".to_string());
+    }
+
+    let major_version = read_u32(&header, 4);
+    if major_version != 2 {
+        return Err(format!("unsupported DBPF version {}", major_version));
+    }
+
+    let entry_count = read_u32(&header, 36) as usize;
+    let index_size = read_u32(&header, 44) as u64;
+    // Position 64 is authoritative in 2.x, older writers only fill the legacy field at 40
+    let index_position = match read_u32(&header, 64) {
+        0 => read_u32(&header, 40),
+        position => position,
+    } as u64;
+
+    if entry_count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let package_len = reader
+        .seek(SeekFrom::End(0))
+        .map_err(|e| format!("failed to read package length: {}", e))?;
+    if index_position + index_size > package_len {
+        return Err("index lies beyond the end of the file".to_string());
+    }
+
+    let mut index = vec![0u8; index_size as usize];
+    reader
+        .seek(SeekFrom::Start(index_position))
+        .and_then(|_| reader.read_exact(&mut index))
+        .map_err(|e| format!("failed to read index: {}", e))?;
+
+    parse_index(&index, entry_count)
+}
+
+/// Decode the raw index block into entries
+fn parse_index(index: &[u8], entry_count: usize) -> Result<Vec<IndexEntry>, String> {
+    let truncated = || "index is truncated".to_string();
+
+    if index.len() < 4 {
+        return Err(truncated());
+    }
+    let flags = read_u32(index, 0);
+    let mut cursor = 4;
+
+    // Constant fields are stored once, right after the flags
+    let mut read_constant = |bit: u32| -> Result<Option<u32>, String> {
+        if flags & bit == 0 {
+            return Ok(None);
+        }
+        if cursor + 4 > index.len() {
+            return Err(truncated());
+        }
+        let value = read_u32(index, cursor);
+        cursor += 4;
+        Ok(Some(value))
+    };
+    let constant_type = read_constant(INDEX_CONSTANT_TYPE)?;
+    let constant_group = read_constant(INDEX_CONSTANT_GROUP)?;
+    let constant_instance_high = read_constant(INDEX_CONSTANT_INSTANCE_HIGH)?;
+
+    let entry_size = 20
+        + 4 * [constant_type, constant_group, constant_instance_high]
+            .iter()
+            .filter(|c| c.is_none())
+            .count();
+    if cursor + entry_count * entry_size > index.len() {
+        return Err(truncated());
+    }
+
+    let mut entries = Vec::with_capacity(entry_count);
+    for _ in 0..entry_count {
+        let mut next = || {
+            let value = read_u32(index, cursor);
+            cursor += 4;
+            value
+        };
+
+        let type_id = constant_type.unwrap_or_else(&mut next);
+        let group = constant_group.unwrap_or_else(&mut next);
+        let instance_high = constant_instance_high.unwrap_or_else(&mut next);
+        let instance_low = next();
+        let offset = next();
+        // High bit flags the extended (compression type) layout, it isn't part of the size
+        let file_size = next() & 0x7FFF_FFFF;
+        let mem_size = next();
+        let compression = read_u16(index, cursor);
+        cursor += 4; // compression type + committed flag
+
+        if compression == COMPRESSION_DELETED {
+            continue;
+        }
+
+        entries.push(IndexEntry {
+            key: ResourceKey {
+                type_id,
+                group,
+                instance: ((instance_high as u64) << 32) | instance_low as u64,
+            },
+            offset,
+            file_size,
+            mem_size,
+            compression,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Read and decompress the data of a single resource
+pub fn read_resource<R: Read + Seek>(
+    reader: &mut R,
+    entry: &IndexEntry,
+) -> Result<Vec<u8>, String> {
+    if entry.mem_size > MAX_RESOURCE_SIZE {
+        return Err(format!(
+            "resource {} is too large ({} bytes)",
+            entry.key, entry.mem_size
+        ));
+    }
+
+    let raw = read_raw_resource(reader, entry)?;
+
+    match entry.compression {
+        COMPRESSION_NONE => Ok(raw),
+        COMPRESSION_ZLIB => {
+            let mut data = Vec::with_capacity(entry.mem_size as usize);
+            // One byte past the declared size is enough to tell the stream lied about it
+            ZlibDecoder::new(raw.as_slice())
+                .take(entry.mem_size as u64 + 1)
+                .read_to_end(&mut data)
+                .map_err(|e| format!("failed to inflate resource {}: {}", entry.key, e))?;
+            if data.len() != entry.mem_size as usize {
+                return Err(format!(
+                    "resource {} inflates to a different size than its index entry ({})",
+                    entry.key, entry.mem_size
+                ));
+            }
+            Ok(data)
+        }
+        COMPRESSION_REFPACK | COMPRESSION_STREAMABLE => decompress_refpack(&raw)
+            .map_err(|e| format!("failed to decompress resource {}: {}", entry.key, e)),
+        other => Err(format!(
+            "resource {} uses unknown compression 0x{:04X}",
+            entry.key, other
+        )),
+    }
+}
+
+/// Read the data of a resource as stored, without decompressing it
+pub fn read_raw_resource<R: Read + Seek>(
+    reader: &mut R,
+    entry: &IndexEntry,
+) -> Result<Vec<u8>, String> {
+    // Check the index against the real file before allocating what it claims
+    let file_len = reader
+        .seek(SeekFrom::End(0))
+        .map_err(|e| format!("failed to read resource {}: {}", entry.key, e))?;
+    if entry.file_size > MAX_RESOURCE_SIZE
+        || entry.offset as u64 + entry.file_size as u64 > file_len
+    {
+        return Err(format!(
+            "resource {} lies outside the package ({} bytes at offset {})",
+            entry.key, entry.file_size, entry.offset
+        ));
+    }
+
+    let mut raw = vec![0u8; entry.file_size as usize];
+    reader
+        .seek(SeekFrom::Start(entry.offset as u64))
+        .and_then(|_| reader.read_exact(&mut raw))
+        .map_err(|e| format!("failed to read resource {}: {}", entry.key, e))?;
+    Ok(raw)
+}
+
+/// Writes a DBPF 2.1 package one resource at a time, the index and header go in on `finish`
+pub struct PackageWriter<W: Write + Seek> {
+    writer: W,
+    index: Vec<u8>,
+    entry_count: u32,
+    position: u64,
+}
+
+impl<W: Write + Seek> PackageWriter<W> {
+    pub fn new(mut writer: W) -> Result<Self, String> {
+        // Placeholder header, filled in once the index position is known
+        writer
+            .write_all(&[0u8; HEADER_SIZE])
+            .map_err(|e| format!("failed to write package header: {}", e))?;
+        Ok(Self {
+            writer,
+            index: INDEX_FLAGS_NONE.to_le_bytes().to_vec(),
+            entry_count: 0,
+            position: HEADER_SIZE as u64,
+        })
+    }
+
+    /// Add a resource from its decompressed data, stored zlib-compressed
+    pub fn add(&mut self, key: ResourceKey, data: &[u8]) -> Result<(), String> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        let compressed = encoder
+            .write_all(data)
+            .and_then(|_| encoder.finish())
+            .map_err(|e| format!("failed to compress resource {}: {}", key, e))?;
+
+        let entry = IndexEntry {
+            key,
+            offset: 0,
+            file_size: compressed.len() as u32,
+            mem_size: data.len() as u32,
+            compression: COMPRESSION_ZLIB,
+        };
+        self.add_raw(&entry, &compressed)
+    }
+
+    /// Copy a resource as it was stored in its source package (no recompression)
+    pub fn add_raw(&mut self, entry: &IndexEntry, raw: &[u8]) -> Result<(), String> {
+        let offset =
+            u32::try_from(self.position).map_err(|_| "package is larger than 4 GiB".to_string())?;
+        self.writer
+            .write_all(raw)
+            .map_err(|e| format!("failed to write resource {}: {}", entry.key, e))?;
+        self.position += raw.len() as u64;
+
+        let key = entry.key;
+        self.index.extend_from_slice(&key.type_id.to_le_bytes());
+        self.index.extend_from_slice(&key.group.to_le_bytes());
+        self.index
+            .extend_from_slice(&((key.instance >> 32) as u32).to_le_bytes());
+        self.index
+            .extend_from_slice(&(key.instance as u32).to_le_bytes());
+        self.index.extend_from_slice(&offset.to_le_bytes());
+        self.index
+            .extend_from_slice(&(raw.len() as u32 | 0x8000_0000).to_le_bytes());
+        self.index.extend_from_slice(&entry.mem_size.to_le_bytes());
+        self.index
+            .extend_from_slice(&entry.compression.to_le_bytes());
+        self.index.extend_from_slice(&1u16.to_le_bytes()); // committed
+        self.entry_count += 1;
+        Ok(())
+    }
+
+    /// Write the index and the real header, returns the underlying writer
+    pub fn finish(mut self) -> Result<W, String> {
+        let index_position =
+            u32::try_from(self.position).map_err(|_| "package is larger than 4 GiB".to_string())?;
+
+        let mut header = [0u8; HEADER_SIZE];
+        header[0..4].copy_from_slice(DBPF_MAGIC);
+        header[4..8].copy_from_slice(&2u32.to_le_bytes());
+        header[8..12].copy_from_slice(&1u32.to_le_bytes());
+        header[36..40].copy_from_slice(&self.entry_count.to_le_bytes());
+        header[44..48].copy_from_slice(&(self.index.len() as u32).to_le_bytes());
+        header[60..64].copy_from_slice(&3u32.to_le_bytes());
+        header[64..68].copy_from_slice(&index_position.to_le_bytes());
+
+        self.writer
+            .write_all(&self.index)
+            .and_then(|_| self.writer.seek(SeekFrom::Start(0)))
+            .and_then(|_| self.writer.write_all(&header))
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| format!("failed to write package index: {}", e))?;
+        Ok(self.writer)
+    }
+}
+
+/// Decompress EA's RefPack (QFS) internal compression
+fn decompress_refpack(data: &[u8]) -> Result<Vec<u8>, String> {
+    let truncated = || "RefPack stream is truncated".to_string();
+
+    if data.len() < 2 || data[1] != 0xFB {
+        return Err("missing RefPack signature".to_string());
+    }
+    let flags = data[0];
+    let size_bytes = if flags & 0x80 != 0 { 4 } else { 3 };
+    // Bit 0 adds a compressed size field before the decompressed size
+    let mut pos = 2 + if flags & 0x01 != 0 { size_bytes } else { 0 };
+
+    if pos + size_bytes > data.len() {
+        return Err(truncated());
+    }
+    let expected_size = data[pos..pos + size_bytes]
+        .iter()
+        .fold(0usize, |size, &b| (size << 8) | b as usize);
+    pos += size_bytes;
+
+    if expected_size > MAX_RESOURCE_SIZE as usize {
+        return Err("RefPack size is too large".to_string());
+    }
+    let mut output = Vec::with_capacity(expected_size);
+
+    while pos < data.len() {
+        let b0 = data[pos] as usize;
+        let (header_len, plain, copy, offset) = if b0 < 0x80 {
+            let b1 = *data.get(pos + 1).ok_or_else(truncated)? as usize;
+            (
+                2,
+                b0 & 0x03,
+                ((b0 & 0x1C) >> 2) + 3,
+                ((b0 & 0x60) << 3) + b1 + 1,
+            )
+        } else if b0 < 0xC0 {
+            let b1 = *data.get(pos + 1).ok_or_else(truncated)? as usize;
+            let b2 = *data.get(pos + 2).ok_or_else(truncated)? as usize;
+            (
+                3,
+                (b1 >> 6) & 0x03,
+                (b0 & 0x3F) + 4,
+                ((b1 & 0x3F) << 8) + b2 + 1,
+            )
+        } else if b0 < 0xE0 {
+            let b1 = *data.get(pos + 1).ok_or_else(truncated)? as usize;
+            let b2 = *data.get(pos + 2).ok_or_else(truncated)? as usize;
+            let b3 = *data.get(pos + 3).ok_or_else(truncated)? as usize;
+            (
+                4,
+                b0 & 0x03,
+                ((b0 & 0x0C) << 6) + b3 + 5,
+                ((b0 & 0x10) << 12) + (b1 << 8) + b2 + 1,
+            )
+        } else if b0 < 0xFC {
+            (1, ((b0 & 0x1F) << 2) + 4, 0, 0)
+        } else {
+            // Stop opcode, may still carry up to 3 literal bytes
+            (1, b0 & 0x03, 0, 0)
+        };
+        pos += header_len;
+
+        let literal = data.get(pos..pos + plain).ok_or_else(truncated)?;
+        output.extend_from_slice(literal);
+        pos += plain;
+
+        if copy > 0 {
+            if offset > output.len() {
+                return Err("RefPack back-reference before start of output".to_string());
+            }
+            // Byte by byte: the source range may overlap what is being written
+            let start = output.len() - offset;
+            for i in 0..copy {
+                output.push(output[start + i]);
+            }
+        }
+
+        if b0 >= 0xFC {
+            break;
+        }
+    }
+
+    if output.len() != expected_size {
+        return Err(format!(
+            "RefPack produced {} bytes, expected {}",
+            output.len(),
+            expected_size
+        ));
+    }
+    Ok(output)
+}
+
+/// Recursively list all .package files under a directory, sorted for stable output
+pub fn find_packages(root: &Path) -> Vec<PathBuf> {
+    let mut packages: Vec<PathBuf> = WalkDir::new(root)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            path.extension()
+                .map(|ext| ext.eq_ignore_ascii_case("package"))
+                .unwrap_or(false)
+        })
+        .collect();
+
+    packages.sort();
+    packages
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+
+    /// Build an uncompressed DBPF 2.1 package holding the given resources
+    pub fn build_package(resources: &[(ResourceKey, Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![0u8; HEADER_SIZE];
+        let mut index = 0u32.to_le_bytes().to_vec();
+
+        for (key, content) in resources {
+            let offset = data.len() as u32;
+            data.extend_from_slice(content);
+
+            index.extend_from_slice(&key.type_id.to_le_bytes());
+            index.extend_from_slice(&key.group.to_le_bytes());
+            index.extend_from_slice(&((key.instance >> 32) as u32).to_le_bytes());
+            index.extend_from_slice(&(key.instance as u32).to_le_bytes());
+            index.extend_from_slice(&offset.to_le_bytes());
+            index.extend_from_slice(&(content.len() as u32 | 0x8000_0000).to_le_bytes());
+            index.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            index.extend_from_slice(&0u16.to_le_bytes());
+            index.extend_from_slice(&1u16.to_le_bytes());
+        }
+
+        let index_position = data.len() as u32;
+        data.extend_from_slice(&index);
+
+        data[0..4].copy_from_slice(DBPF_MAGIC);
+        data[4..8].copy_from_slice(&2u32.to_le_bytes());
+        data[8..12].copy_from_slice(&1u32.to_le_bytes());
+        data[36..40].copy_from_slice(&(resources.len() as u32).to_le_bytes());
+        data[44..48].copy_from_slice(&(index.len() as u32).to_le_bytes());
+        data[60..64].copy_from_slice(&3u32.to_le_bytes());
+        data[64..68].copy_from_slice(&index_position.to_le_bytes());
+        data
+    }
+
+    /// Rewrite every index entry of a package built by `build_package` as zlib-compressed
+    pub fn build_zlib_package(resources: &[(ResourceKey, Vec<u8>)]) -> Vec<u8> {
+        let compressed: Vec<(ResourceKey, Vec<u8>)> = resources
+            .iter()
+            .map(|(key, content)| {
+                let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(content).unwrap();
+                (*key, encoder.finish().unwrap())
+            })
+            .collect();
+        let mut package = build_package(&compressed);
+
+        let index_position = read_u32(&package, 64) as usize;
+        for (i, (_, content)) in resources.iter().enumerate() {
+            let entry = index_position + 4 + i * 32;
+            package[entry + 24..entry + 28].copy_from_slice(&(content.len() as u32).to_le_bytes());
+            package[entry + 28..entry + 30].copy_from_slice(&COMPRESSION_ZLIB.to_le_bytes());
+        }
+        package
+    }
+
+    /// Name map resource listing `names` as (instance, name)
+    pub fn name_map(names: &[(u64, &str)]) -> Vec<u8> {
+        let mut data = 1u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&(names.len() as u32).to_le_bytes());
+        for (instance, name) in names {
+            data.extend_from_slice(&instance.to_le_bytes());
+            data.extend_from_slice(&(name.len() as u32).to_le_bytes());
+            data.extend_from_slice(name.as_bytes());
+        }
+        data
+    }
+
+    /// Shorthand for a resource key
+    pub fn key(type_id: u32, group: u32, instance: u64) -> ResourceKey {
+        ResourceKey {
+            type_id,
+            group,
+            instance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::*;
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn reads_index_entries() {
+        let package = build_package(&[
+            (key(0x0333406C, 0, 0x1122334455667788), b"<I/>".to_vec()),
+            (key(0x220557DA, 0x80000000, 42), vec![0u8; 16]),
+        ]);
+
+        let entries = read_index_from(&mut Cursor::new(package)).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].key, key(0x0333406C, 0, 0x1122334455667788));
+        assert_eq!(entries[0].file_size, 4);
+        assert_eq!(entries[1].key.group, 0x80000000);
+        assert_eq!(entries[1].mem_size, 16);
+    }
+
+    #[test]
+    fn reads_index_with_constant_type() {
+        let mut index = Vec::new();
+        index.extend_from_slice(&INDEX_CONSTANT_TYPE.to_le_bytes());
+        index.extend_from_slice(&0x545AC67Au32.to_le_bytes());
+        for instance in [1u32, 2u32] {
+            for value in [0, 0, instance, 96, 0, 0] {
+                index.extend_from_slice(&value.to_le_bytes());
+            }
+            index.extend_from_slice(&[0, 0, 1, 0]);
+        }
+
+        let entries = parse_index(&index, 2).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|e| e.key.type_id == 0x545AC67A));
+        assert_eq!(entries[1].key.instance, 2);
+    }
+
+    #[test]
+    fn rejects_non_package_data() {
+        let mut zip_like = b"PK\x03\x04".to_vec();
+        zip_like.resize(200, 0);
+
+        assert!(read_index_from(&mut Cursor::new(zip_like)).is_err());
+        assert!(read_index_from(&mut Cursor::new(b"DBPF".to_vec())).is_err());
+    }
+
+    #[test]
+    fn reads_zlib_compressed_resources() {
+        let content = b"<?xml version=\"1.0\"?><I n=\"tuning\"/>".repeat(20);
+        let package = build_zlib_package(&[(key(0x0333406C, 0, 7), content.clone())]);
+        let mut reader = Cursor::new(package);
+
+        let entries = read_index_from(&mut reader).unwrap();
+        assert_eq!(entries[0].compression, COMPRESSION_ZLIB);
+        assert!(entries[0].file_size < entries[0].mem_size);
+        assert_eq!(read_resource(&mut reader, &entries[0]).unwrap(), content);
+    }
+
+    #[test]
+    fn rejects_sizes_the_data_does_not_match() {
+        let content = vec![b'a'; 4096];
+        let package = build_zlib_package(&[(key(0x0333406C, 0, 7), content)]);
+        let mut reader = Cursor::new(package);
+        let entries = read_index_from(&mut reader).unwrap();
+
+        // Stored size past the end of the file
+        let mut beyond = entries[0].clone();
+        beyond.file_size = u32::MAX >> 1;
+        assert!(read_raw_resource(&mut reader, &beyond)
+            .unwrap_err()
+            .contains("outside the package"));
+
+        // Stream inflating to more than the index declares
+        let mut understated = entries[0].clone();
+        understated.mem_size = 16;
+        assert!(read_resource(&mut reader, &understated)
+            .unwrap_err()
+            .contains("different size"));
+    }
+
+    #[test]
+    fn decompresses_refpack() {
+        // Literal "abc", then copy 6 bytes from 3 back, then stop
+        let stream = [0x10, 0xFB, 0, 0, 9, 0x0F, 0x02, b'a', b'b', b'c', 0xFC];
+        assert_eq!(decompress_refpack(&stream).unwrap(), b"abcabcabc");
+
+        let wrong_size = [0x10, 0xFB, 0, 0, 10, 0x0F, 0x02, b'a', b'b', b'c', 0xFC];
+        assert!(decompress_refpack(&wrong_size).is_err());
+        assert!(decompress_refpack(&[0x10, 0xFB, 0, 0, 9, 0x0F]).is_err());
+    }
+
+    #[test]
+    fn parses_name_maps_up_to_truncation() {
+        let data = test_support::name_map(&[(7, "creator:Buff"), (9, "Loot_Happy")]);
+        assert_eq!(
+            parse_name_map(&data),
+            vec![
+                (7, "creator:Buff".to_string()),
+                (9, "Loot_Happy".to_string())
+            ]
+        );
+
+        // Cut inside the second name, then inside the second header
+        assert_eq!(parse_name_map(&data[..data.len() - 3]).len(), 1);
+        assert_eq!(parse_name_map(&data[..8 + 12 + 12 + 4]).len(), 1);
+        assert!(parse_name_map(&data[..6]).is_empty());
+    }
+
+    #[test]
+    fn formats_keys_like_modding_tools() {
+        assert_eq!(
+            key(0x0333406C, 0, 0xABCDEF).to_string(),
+            "0333406C:00000000:0000000000ABCDEF"
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use crate::calculate_file_hash;
+use crate::cpu;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tauri::Emitter;
+use walkdir::WalkDir;
+
+/// Files with identical content
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    pub hash: String,
+    /// Size of one copy
+    pub size: u64,
+    /// Sorted paths, hard links to the same file count once
+    pub paths: Vec<String>,
+    /// Bytes freed by hard-linking all but one copy
+    pub reclaimable_bytes: u64,
+}
+
+/// What deduplicating a library would save
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DedupEstimate {
+    /// Largest savings first
+    pub duplicate_groups: Vec<DuplicateGroup>,
+    pub reclaimable_bytes: u64,
+    pub files_scanned: usize,
+}
+
+/// Progress payload emitted on `dedup-estimate://progress`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DedupEstimateProgress {
+    pub operation_id: String,
+    pub hashed: usize,
+    pub total: usize,
+}
+
+/// Estimate the space hard-linking duplicate files under `mods_dir` would free, changing nothing
+/// Only files sharing their size with another one are hashed, in parallel, emitting
+/// `dedup-estimate://progress`
+#[tauri::command(async)]
+pub fn estimate_dedup_savings(
+    app_handle: tauri::AppHandle,
+    mods_dir: String,
+    operation_id: String,
+) -> Result<DedupEstimate, String> {
+    estimate_savings(Path::new(&mods_dir), |hashed, total| {
+        let _ = app_handle.emit(
+            "dedup-estimate://progress",
+            DedupEstimateProgress {
+                operation_id: operation_id.clone(),
+                hashed,
+                total,
+            },
+        );
+    })
+}
+
+pub fn estimate_savings(
+    mods_dir: &Path,
+    on_progress: impl Fn(usize, usize) + Sync,
+) -> Result<DedupEstimate, String> {
+    if !mods_dir.is_dir() {
+        return Err(format!("Directory not found: {}", mods_dir.display()));
+    }
+
+    let mut by_size: BTreeMap<u64, Vec<PathBuf>> = BTreeMap::new();
+    let mut files_scanned = 0;
+    for entry in WalkDir::new(mods_dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+    {
+        files_scanned += 1;
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        // Empty files have nothing to reclaim
+        if size > 0 {
+            by_size.entry(size).or_default().push(entry.into_path());
+        }
+    }
+
+    let candidates: Vec<(u64, PathBuf)> = by_size
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .flat_map(|(size, paths)| paths.into_iter().map(move |path| (size, path)))
+        .collect();
+    let total = candidates.len();
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(cpu::recommended_workers().scanning)
+        .build()
+        .map_err(|e| format!("Failed to create hashing thread pool: {}", e))?;
+    let hashed = AtomicUsize::new(0);
+    on_progress(0, total);
+
+    let hashes: Vec<(u64, PathBuf, Result<String, String>)> = pool.install(|| {
+        candidates
+            .into_par_iter()
+            .map(|(size, path)| {
+                let hash = calculate_file_hash(path.to_string_lossy().to_string());
+                on_progress(hashed.fetch_add(1, Ordering::SeqCst) + 1, total);
+                (size, path, hash)
+            })
+            .collect()
+    });
+
+    // Files that vanished or can't be read are left out of the estimate
+    let mut groups: BTreeMap<(String, u64), Vec<PathBuf>> = BTreeMap::new();
+    for (size, path, hash) in hashes {
+        if let Ok(hash) = hash {
+            groups.entry((hash, size)).or_default().push(path);
+        }
+    }
+
+    let mut duplicate_groups: Vec<DuplicateGroup> = groups
+        .into_iter()
+        .filter_map(|((hash, size), mut paths)| {
+            paths.sort();
+            let copies = distinct_files(&paths);
+            (copies > 1).then(|| DuplicateGroup {
+                hash,
+                size,
+                paths: paths
+                    .iter()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect(),
+                reclaimable_bytes: size * (copies as u64 - 1),
+            })
+        })
+        .collect();
+    duplicate_groups.sort_by(|a, b| {
+        b.reclaimable_bytes
+            .cmp(&a.reclaimable_bytes)
+            .then_with(|| a.paths.cmp(&b.paths))
+    });
+
+    Ok(DedupEstimate {
+        reclaimable_bytes: duplicate_groups.iter().map(|g| g.reclaimable_bytes).sum(),
+        duplicate_groups,
+        files_scanned,
+    })
+}
+
+/// Number of distinct files behind `paths`, paths already hard-linked together count once
+#[cfg(unix)]
+fn distinct_files(paths: &[PathBuf]) -> usize {
+    use std::os::unix::fs::MetadataExt;
+
+    let mut seen = HashSet::new();
+    paths
+        .iter()
+        .filter(|path| match fs::metadata(path) {
+            Ok(metadata) => seen.insert((metadata.dev(), metadata.ino())),
+            Err(_) => true,
+        })
+        .count()
+}
+
+/// File ids are not exposed on stable Rust for Windows, every path counts as a copy
+#[cfg(not(unix))]
+fn distinct_files(paths: &[PathBuf]) -> usize {
+    paths.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tempfile::tempdir;
+
+    #[test]
+    fn sums_bytes_freed_by_linking_extra_copies() {
+        let dir = tempdir().unwrap();
+        let mods = dir.path();
+        fs::create_dir_all(mods.join("A")).unwrap();
+        fs::create_dir_all(mods.join("B")).unwrap();
+        let hair = vec![1u8; 1000];
+        let chair = vec![2u8; 300];
+        // Three copies of the hair, two of the chair
+        fs::write(mods.join("A/hair.package"), &hair).unwrap();
+        fs::write(mods.join("B/hair.package"), &hair).unwrap();
+        fs::write(mods.join("hair_copy.package"), &hair).unwrap();
+        fs::write(mods.join("A/chair.package"), &chair).unwrap();
+        fs::write(mods.join("B/chair.package"), &chair).unwrap();
+        // Same size as the chair, different content
+        fs::write(mods.join("lamp.package"), vec![3u8; 300]).unwrap();
+        fs::write(mods.join("unique.package"), vec![4u8; 50]).unwrap();
+
+        let progress = Mutex::new(Vec::new());
+        let estimate = estimate_savings(mods, |hashed, total| {
+            progress.lock().unwrap().push((hashed, total))
+        })
+        .unwrap();
+
+        assert_eq!(estimate.files_scanned, 7);
+        assert_eq!(estimate.duplicate_groups.len(), 2);
+        assert_eq!(estimate.duplicate_groups[0].size, 1000);
+        assert_eq!(estimate.duplicate_groups[0].reclaimable_bytes, 2000);
+        assert_eq!(estimate.duplicate_groups[1].paths.len(), 2);
+        assert_eq!(estimate.duplicate_groups[1].reclaimable_bytes, 300);
+        assert_eq!(estimate.reclaimable_bytes, 2300);
+        // The unique-sized file is never hashed
+        // Workers report concurrently, only the highest count is certain to be complete
+        assert_eq!(progress.into_inner().unwrap().iter().max(), Some(&(6, 6)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn existing_hard_links_free_nothing() {
+        let dir = tempdir().unwrap();
+        let mods = dir.path();
+        fs::write(mods.join("chair.package"), vec![2u8; 300]).unwrap();
+        fs::write(mods.join("chair_copy.package"), vec![2u8; 300]).unwrap();
+        fs::hard_link(mods.join("chair.package"), mods.join("chair_link.package")).unwrap();
+
+        let estimate = estimate_savings(mods, |_, _| {}).unwrap();
+
+        assert_eq!(estimate.duplicate_groups[0].paths.len(), 3);
+        assert_eq!(estimate.reclaimable_bytes, 300);
+    }
+}
@@ -0,0 +1,171 @@
+use crate::calculate_file_hash;
+use crate::extract::verify_archive_hash;
+use crate::replace::temp_path_for;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{copy, BufReader, BufWriter, Cursor, Write};
+use std::path::Path;
+
+/// Sizes and target hash of a generated patch
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PackageDelta {
+    pub patch_bytes: u64,
+    pub new_bytes: u64,
+    /// SHA-256 of the new version, to check the result once the patch is applied
+    pub new_hash: String,
+}
+
+/// Write a binary patch turning `old_path` into `new_path`
+/// The patch is a bidiff stream compressed with zlib
+#[tauri::command(async)]
+pub fn compute_package_delta(
+    old_path: String,
+    new_path: String,
+    patch_out: String,
+) -> Result<PackageDelta, String> {
+    compute_delta(
+        Path::new(&old_path),
+        Path::new(&new_path),
+        Path::new(&patch_out),
+    )
+}
+
+/// Rebuild the new version of a package from the old one and a patch
+/// The result only replaces `new_out` if it hashes to `expected_hash`
+#[tauri::command(async)]
+pub fn apply_package_delta(
+    old_path: String,
+    patch_path: String,
+    new_out: String,
+    expected_hash: String,
+) -> Result<(), String> {
+    apply_delta(
+        Path::new(&old_path),
+        Path::new(&patch_path),
+        Path::new(&new_out),
+        &expected_hash,
+    )
+}
+
+pub fn compute_delta(old: &Path, new: &Path, patch_out: &Path) -> Result<PackageDelta, String> {
+    let old_bytes =
+        fs::read(old).map_err(|e| format!("Failed to read {}: {}", old.display(), e))?;
+    let new_bytes =
+        fs::read(new).map_err(|e| format!("Failed to read {}: {}", new.display(), e))?;
+
+    let file = File::create(patch_out)
+        .map_err(|e| format!("Failed to create {}: {}", patch_out.display(), e))?;
+    let mut encoder = ZlibEncoder::new(BufWriter::new(file), Compression::default());
+    bidiff::simple_diff(&old_bytes, &new_bytes, &mut encoder)
+        .and_then(|_| encoder.finish()?.flush())
+        .map_err(|e| format!("Failed to write patch {}: {}", patch_out.display(), e))?;
+
+    Ok(PackageDelta {
+        patch_bytes: fs::metadata(patch_out).map(|m| m.len()).unwrap_or(0),
+        new_bytes: new_bytes.len() as u64,
+        new_hash: calculate_file_hash(new.to_string_lossy().to_string())?,
+    })
+}
+
+pub fn apply_delta(
+    old: &Path,
+    patch: &Path,
+    new_out: &Path,
+    expected_hash: &str,
+) -> Result<(), String> {
+    // The patch reader seeks around the old file, keeping it in memory avoids a syscall per copy
+    let old_bytes =
+        fs::read(old).map_err(|e| format!("Failed to read {}: {}", old.display(), e))?;
+    let patch_file =
+        File::open(patch).map_err(|e| format!("Failed to open {}: {}", patch.display(), e))?;
+    let mut reader = bipatch::Reader::new(
+        ZlibDecoder::new(BufReader::new(patch_file)),
+        Cursor::new(old_bytes),
+    )
+    .map_err(|e| format!("Invalid patch {}: {}", patch.display(), e))?;
+
+    let temp_path = temp_path_for(new_out)?;
+    let outcome = File::create(&temp_path)
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            copy(&mut reader, &mut writer)?;
+            writer.into_inner()?.sync_all()
+        })
+        .map_err(|e| format!("Failed to apply patch {}: {}", patch.display(), e))
+        .and_then(|_| verify_archive_hash(&temp_path, expected_hash))
+        .and_then(|_| {
+            fs::rename(&temp_path, new_out)
+                .map_err(|e| format!("Failed to write {}: {}", new_out.display(), e))
+        });
+
+    if outcome.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbpf::test_support::{build_package, key};
+    use crate::extract::CHECKSUM_MISMATCH_ERROR;
+    use tempfile::tempdir;
+
+    fn versions(dir: &Path) -> (std::path::PathBuf, std::path::PathBuf) {
+        let old = dir.join("hair_v1.package");
+        let new = dir.join("hair_v2.package");
+        let texture = vec![7u8; 32 * 1024];
+        let mut retextured = texture.clone();
+        retextured[100..200].fill(9);
+        fs::write(
+            &old,
+            build_package(&[
+                (key(0x034AEECB, 0, 1), b"caspart v1".to_vec()),
+                (key(0x00B2D882, 0, 1), texture),
+            ]),
+        )
+        .unwrap();
+        fs::write(
+            &new,
+            build_package(&[
+                (key(0x034AEECB, 0, 1), b"caspart v2".to_vec()),
+                (key(0x00B2D882, 0, 1), retextured),
+                (key(0x0333406C, 0, 2), b"<I n=\"tuning\"/>".to_vec()),
+            ]),
+        )
+        .unwrap();
+        (old, new)
+    }
+
+    #[test]
+    fn round_trip_reproduces_new_version() {
+        let dir = tempdir().unwrap();
+        let (old, new) = versions(dir.path());
+        let patch = dir.path().join("hair.patch");
+        let rebuilt = dir.path().join("rebuilt.package");
+
+        let delta = compute_delta(&old, &new, &patch).unwrap();
+        assert!(delta.patch_bytes < delta.new_bytes / 10);
+
+        apply_delta(&old, &patch, &rebuilt, &delta.new_hash).unwrap();
+        assert_eq!(fs::read(&rebuilt).unwrap(), fs::read(&new).unwrap());
+    }
+
+    #[test]
+    fn keeps_output_untouched_on_hash_mismatch() {
+        let dir = tempdir().unwrap();
+        let (old, new) = versions(dir.path());
+        let patch = dir.path().join("hair.patch");
+        let rebuilt = dir.path().join("rebuilt.package");
+        compute_delta(&old, &new, &patch).unwrap();
+
+        let error = apply_delta(&old, &patch, &rebuilt, &"0".repeat(64)).unwrap_err();
+
+        assert!(error.starts_with(CHECKSUM_MISMATCH_ERROR));
+        assert!(!rebuilt.exists());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 3);
+    }
+}
@@ -0,0 +1,524 @@
+use crate::dbpf::{self, XML_TUNING_TYPES};
+use crate::descriptor::ModDescriptor;
+use crate::script;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+/// Frameworks other mods commonly build on, and the Python package each one ships
+const DEFAULT_FRAMEWORK_MARKERS: [(&str, &str); 3] = [
+    ("XML Injector", "xml_injector"),
+    ("Lot 51 Core Library", "lot51_core"),
+    ("Sims 4 Community Library", "sims4communitylib"),
+];
+
+/// Tuning and compiled modules larger than this are not searched for framework references
+const MAX_SCANNED_RESOURCE_SIZE: u32 = 4 * 1024 * 1024;
+
+/// Dependency declared by a descriptor that no descriptor in the set provides
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MissingDependency {
+    /// Mod declaring the dependency
+    pub mod_name: String,
+    pub dependency: String,
+}
+
+/// Python package whose use reveals a dependency on a framework
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FrameworkMarker {
+    /// Name shown to the user, e.g. "XML Injector"
+    pub framework: String,
+    /// Top-level module of the framework; tuning references it as `m="module.sub"`,
+    /// scripts import it by name
+    pub module: String,
+}
+
+/// Frameworks a mod file relies on or ships
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FrameworkUsage {
+    pub path: String,
+    /// Frameworks referenced by the mod, sorted
+    pub requires: Vec<String>,
+    /// Frameworks the mod is itself (a script defining the framework's module), sorted
+    pub provides: Vec<String>,
+}
+
+/// Built-in framework markers, the starting point for an updated list
+#[tauri::command]
+pub fn default_framework_markers() -> Vec<FrameworkMarker> {
+    DEFAULT_FRAMEWORK_MARKERS
+        .iter()
+        .map(|(framework, module)| FrameworkMarker {
+            framework: framework.to_string(),
+            module: module.to_string(),
+        })
+        .collect()
+}
+
+/// Find the frameworks each package and script under `mods_dir` needs or provides
+/// A framework some mod requires but none provides is not installed
+/// `markers` replaces the built-in list when given
+#[tauri::command(async)]
+pub fn detect_framework_requirements(
+    mods_dir: String,
+    markers: Option<Vec<FrameworkMarker>>,
+) -> Result<Vec<FrameworkUsage>, String> {
+    let markers = markers.unwrap_or_else(default_framework_markers);
+    detect_frameworks(Path::new(&mods_dir), &markers)
+}
+
+pub fn detect_frameworks(
+    mods_dir: &Path,
+    markers: &[FrameworkMarker],
+) -> Result<Vec<FrameworkUsage>, String> {
+    if !mods_dir.is_dir() {
+        return Err(format!("Directory not found: {}", mods_dir.display()));
+    }
+
+    let mut mods: Vec<PathBuf> = dbpf::find_packages(mods_dir);
+    mods.extend(script::find_scripts(mods_dir));
+    mods.sort();
+
+    // Unreadable files reference nothing, other scans report them
+    Ok(mods
+        .par_iter()
+        .filter_map(|path| {
+            let is_script = path
+                .extension()
+                .map(|ext| ext.eq_ignore_ascii_case("ts4script"))
+                .unwrap_or(false);
+            let (requires, provides) = if is_script {
+                script_frameworks(path, markers)
+            } else {
+                (package_frameworks(path, markers), Vec::new())
+            };
+            (!requires.is_empty() || !provides.is_empty()).then(|| FrameworkUsage {
+                path: path.to_string_lossy().to_string(),
+                requires,
+                provides,
+            })
+        })
+        .collect())
+}
+
+/// Frameworks referenced from the package's XML tuning
+fn package_frameworks(path: &Path, markers: &[FrameworkMarker]) -> Vec<String> {
+    let Ok(entries) = dbpf::read_index(path) else {
+        return Vec::new();
+    };
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+    let mut reader = BufReader::new(file);
+    let mut found = BTreeSet::new();
+
+    for entry in entries.iter().filter(|entry| {
+        XML_TUNING_TYPES.contains(&entry.key.type_id) && entry.mem_size <= MAX_SCANNED_RESOURCE_SIZE
+    }) {
+        let Ok(data) = dbpf::read_resource(&mut reader, entry) else {
+            continue;
+        };
+        for marker in markers {
+            if text_references(&data, &marker.module) {
+                found.insert(marker.framework.clone());
+            }
+        }
+    }
+    found.into_iter().collect()
+}
+
+/// (frameworks imported by the script, frameworks it defines)
+fn script_frameworks(path: &Path, markers: &[FrameworkMarker]) -> (Vec<String>, Vec<String>) {
+    let modules = script::inspect_script(path)
+        .map(|inspection| inspection.modules)
+        .unwrap_or_default();
+    let provides: BTreeSet<String> = markers
+        .iter()
+        .filter(|marker| {
+            modules
+                .iter()
+                .any(|module| is_module_or_submodule(module.as_bytes(), &marker.module))
+        })
+        .map(|marker| marker.framework.clone())
+        .collect();
+
+    let mut requires = BTreeSet::new();
+    let archive = File::open(path)
+        .ok()
+        .and_then(|file| ZipArchive::new(BufReader::new(file)).ok());
+    let Some(mut archive) = archive else {
+        return (Vec::new(), provides.into_iter().collect());
+    };
+    for i in 0..archive.len() {
+        let Ok(mut entry) = archive.by_index(i) else {
+            continue;
+        };
+        if script::module_name(entry.name()).is_none()
+            || entry.size() > MAX_SCANNED_RESOURCE_SIZE as u64
+        {
+            continue;
+        }
+        let mut code = Vec::new();
+        if entry.read_to_end(&mut code).is_err() {
+            continue;
+        }
+        for marker in markers {
+            if !provides.contains(&marker.framework) && code_references(&code, &marker.module) {
+                requires.insert(marker.framework.clone());
+            }
+        }
+    }
+
+    (
+        requires.into_iter().collect(),
+        provides.into_iter().collect(),
+    )
+}
+
+/// Module names are compared ignoring ASCII case, both in tuning and in compiled code
+fn is_module_or_submodule(name: &[u8], module: &str) -> bool {
+    let module = module.as_bytes();
+    !module.is_empty()
+        && name.len() >= module.len()
+        && name[..module.len()].eq_ignore_ascii_case(module)
+        && (name.len() == module.len() || name[module.len()] == b'.')
+}
+
+/// Whether XML mentions `module` as a whole dotted name, e.g. `m="module.sub"`
+fn text_references(text: &[u8], module: &str) -> bool {
+    positions(text, module).any(|start| {
+        let before = start.checked_sub(1).map(|i| text[i]);
+        let end = start + module.len();
+        !before.is_some_and(|b| is_identifier_byte(b) || b == b'.')
+            && !text.get(end).copied().is_some_and(is_identifier_byte)
+    })
+}
+
+/// Whether compiled code holds a name equal to `module` or one of its submodules
+/// Marshal stores each name behind its length, one byte for short ones and four otherwise,
+/// so the length tells where the name ends
+fn code_references(code: &[u8], module: &str) -> bool {
+    positions(code, module).any(|start| {
+        let short = start.checked_sub(1).map(|i| code[i] as usize);
+        let long = start
+            .checked_sub(4)
+            .map(|i| u32::from_le_bytes(code[i..start].try_into().unwrap()) as usize);
+        [short, long].into_iter().flatten().any(|length| {
+            code.get(start..start + length)
+                .is_some_and(|name| is_module_or_submodule(name, module))
+        })
+    })
+}
+
+/// Start of every case-insensitive occurrence of `needle`
+fn positions<'a>(haystack: &'a [u8], needle: &'a str) -> impl Iterator<Item = usize> + 'a {
+    let needle = needle.as_bytes();
+    haystack
+        .windows(needle.len().max(1))
+        .enumerate()
+        .filter(move |(_, window)| !needle.is_empty() && window.eq_ignore_ascii_case(needle))
+        .map(|(start, _)| start)
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+/// Find circular dependency chains in a set of descriptors
+/// Each cycle lists mod names in dependency order, starting from the alphabetically first one
+/// Dependencies outside the set are ignored here, see `find_missing_dependencies`
+#[tauri::command]
+pub fn check_dependency_cycles(descriptors: Vec<ModDescriptor>) -> Vec<Vec<String>> {
+    find_cycles(&DependencyGraph::new(&descriptors))
+}
+
+/// List dependencies that no descriptor in the set provides
+#[tauri::command]
+pub fn find_missing_dependencies(descriptors: Vec<ModDescriptor>) -> Vec<MissingDependency> {
+    let graph = DependencyGraph::new(&descriptors);
+    let mut missing = Vec::new();
+
+    for (key, dependencies) in &graph.edges {
+        for dependency in dependencies {
+            if !graph.names.contains_key(&normalize(dependency)) {
+                missing.push(MissingDependency {
+                    mod_name: graph.names[key].clone(),
+                    dependency: dependency.clone(),
+                });
+            }
+        }
+    }
+    missing
+}
+
+/// Mods keyed by case-insensitive name, descriptors without a name are skipped
+struct DependencyGraph {
+    /// Normalized name -> name as written in the descriptor
+    names: BTreeMap<String, String>,
+    /// Normalized name -> declared dependencies as written
+    edges: BTreeMap<String, Vec<String>>,
+}
+
+impl DependencyGraph {
+    fn new(descriptors: &[ModDescriptor]) -> Self {
+        let mut names = BTreeMap::new();
+        let mut edges: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        for descriptor in descriptors {
+            let Some(name) = descriptor.name.as_deref().map(str::trim) else {
+                continue;
+            };
+            if name.is_empty() {
+                continue;
+            }
+            let key = normalize(name);
+            names.entry(key.clone()).or_insert_with(|| name.to_string());
+            edges
+                .entry(key)
+                .or_default()
+                .extend(descriptor.dependencies.iter().cloned());
+        }
+
+        Self { names, edges }
+    }
+
+    /// Dependencies of `key` that are part of the graph
+    fn neighbours(&self, key: &str) -> Vec<String> {
+        self.edges
+            .get(key)
+            .into_iter()
+            .flatten()
+            .map(|dependency| normalize(dependency))
+            .filter(|dependency| self.names.contains_key(dependency))
+            .collect()
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Visit {
+    InProgress,
+    Done,
+}
+
+/// Depth-first search, every edge back onto the current path closes a cycle
+fn find_cycles(graph: &DependencyGraph) -> Vec<Vec<String>> {
+    let mut state: HashMap<String, Visit> = HashMap::new();
+    let mut path = Vec::new();
+    let mut cycles = BTreeSet::new();
+
+    for key in graph.names.keys() {
+        if !state.contains_key(key) {
+            visit(graph, key, &mut state, &mut path, &mut cycles);
+        }
+    }
+
+    cycles
+        .into_iter()
+        .map(|cycle: Vec<String>| cycle.iter().map(|key| graph.names[key].clone()).collect())
+        .collect()
+}
+
+fn visit(
+    graph: &DependencyGraph,
+    key: &str,
+    state: &mut HashMap<String, Visit>,
+    path: &mut Vec<String>,
+    cycles: &mut BTreeSet<Vec<String>>,
+) {
+    state.insert(key.to_string(), Visit::InProgress);
+    path.push(key.to_string());
+
+    for next in graph.neighbours(key) {
+        match state.get(&next) {
+            None => visit(graph, &next, state, path, cycles),
+            Some(Visit::InProgress) => {
+                let start = path.iter().position(|k| *k == next).unwrap_or(0);
+                let mut cycle = path[start..].to_vec();
+                // Rotate so the same cycle found from another entry point is deduplicated
+                let first = (0..cycle.len()).min_by_key(|&i| &cycle[i]).unwrap_or(0);
+                cycle.rotate_left(first);
+                cycles.insert(cycle);
+            }
+            Some(Visit::Done) => {}
+        }
+    }
+
+    path.pop();
+    state.insert(key.to_string(), Visit::Done);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbpf::test_support::{build_zlib_package, key};
+    use crate::test_support::write_zip;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn descriptor(name: &str, dependencies: &[&str]) -> ModDescriptor {
+        ModDescriptor {
+            name: Some(name.to_string()),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn detects_two_mod_cycle() {
+        let descriptors = vec![
+            descriptor("B", &["a"]),
+            descriptor("A", &["B"]),
+            descriptor("C", &["A"]),
+        ];
+
+        assert_eq!(
+            check_dependency_cycles(descriptors.clone()),
+            vec![vec!["A".to_string(), "B".to_string()]]
+        );
+        assert!(find_missing_dependencies(descriptors).is_empty());
+    }
+
+    #[test]
+    fn acyclic_set_has_no_cycles_and_reports_missing_separately() {
+        let descriptors = vec![
+            descriptor("Core", &[]),
+            descriptor("Addon", &["Core", "XML Injector"]),
+            descriptor("Patch", &["Addon", "Core"]),
+            ModDescriptor::default(),
+        ];
+
+        assert!(check_dependency_cycles(descriptors.clone()).is_empty());
+        assert_eq!(
+            find_missing_dependencies(descriptors),
+            vec![MissingDependency {
+                mod_name: "Addon".to_string(),
+                dependency: "XML Injector".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn self_dependency_is_a_cycle() {
+        assert_eq!(
+            check_dependency_cycles(vec![descriptor("Loop", &["loop"])]),
+            vec![vec!["Loop".to_string()]]
+        );
+    }
+
+    #[test]
+    fn detects_framework_referenced_by_tuning() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("career_addon.package"),
+            build_zlib_package(&[(
+                key(0x7DF2169C, 0, 0x8000_0000_0000_0042),
+                br#"<?xml version="1.0"?><I c="XmlInjector_Snippet" i="snippet" m="xml_injector.snippet" n="creator:CareerAddon"/>"#.to_vec(),
+            )]),
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("plain.package"),
+            build_zlib_package(&[(key(0x0333406C, 0, 1), b"<I n=\"creator:Plain\"/>".to_vec())]),
+        )
+        .unwrap();
+        write_zip(
+            &dir.path().join("XmlInjector.ts4script"),
+            &[
+                ("xml_injector/__init__.pyc", b""),
+                ("xml_injector/snippet.pyc", b"xml_injector"),
+            ],
+        );
+        let code = pyc_names(&["sims4communitylib.utils", "show_notification"]);
+        write_zip(
+            &dir.path().join("helper.ts4script"),
+            &[("helper/main.pyc", code.as_slice())],
+        );
+
+        let usage = detect_frameworks(dir.path(), &default_framework_markers()).unwrap();
+
+        assert_eq!(
+            usage,
+            vec![
+                FrameworkUsage {
+                    path: dir
+                        .path()
+                        .join("XmlInjector.ts4script")
+                        .to_string_lossy()
+                        .to_string(),
+                    requires: Vec::new(),
+                    provides: vec!["XML Injector".to_string()],
+                },
+                FrameworkUsage {
+                    path: dir
+                        .path()
+                        .join("career_addon.package")
+                        .to_string_lossy()
+                        .to_string(),
+                    requires: vec!["XML Injector".to_string()],
+                    provides: Vec::new(),
+                },
+                FrameworkUsage {
+                    path: dir
+                        .path()
+                        .join("helper.ts4script")
+                        .to_string_lossy()
+                        .to_string(),
+                    requires: vec!["Sims 4 Community Library".to_string()],
+                    provides: Vec::new(),
+                },
+            ]
+        );
+
+        // A custom list replaces the built-in one
+        let custom = vec![FrameworkMarker {
+            framework: "Helper Lib".to_string(),
+            module: "helper".to_string(),
+        }];
+        let usage = detect_frameworks(dir.path(), &custom).unwrap();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].provides, vec!["Helper Lib"]);
+    }
+
+    /// Names as marshal writes them in a pyc: short interned ASCII, length, then the name
+    fn pyc_names(names: &[&str]) -> Vec<u8> {
+        let mut code = b"\xe3\x00\x00".to_vec();
+        for name in names {
+            code.push(0xDA);
+            code.push(name.len() as u8);
+            code.extend_from_slice(name.as_bytes());
+        }
+        code
+    }
+
+    #[test]
+    fn matches_modules_on_name_boundaries() {
+        // Tuning, any case, only whole dotted names
+        assert!(text_references(
+            br#"m="XML_Injector.snippet""#,
+            "xml_injector"
+        ));
+        assert!(text_references(br#"<T>xml_injector</T>"#, "xml_injector"));
+        assert!(!text_references(
+            br#"m="my_xml_injector.snippet""#,
+            "xml_injector"
+        ));
+        assert!(!text_references(br#"m="mcmod.commands""#, "mc"));
+        assert!(!text_references(br#"m="other.mc.commands""#, "mc"));
+
+        // Compiled code, same case rule, the name has to be the whole marshal string
+        let code = pyc_names(&["Sims4CommunityLib.utils.common", "helpers", "mc_cmd_center"]);
+        assert!(code_references(&code, "sims4communitylib"));
+        assert!(!code_references(&code, "helper"));
+        assert!(!code_references(&code, "mc"));
+        assert!(code_references(&pyc_names(&["mc"]), "mc"));
+        assert!(!code_references(b"import lot51_core", "lot51_core"));
+    }
+}
@@ -0,0 +1,295 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Descriptor file names creators put at the root of a mod folder
+const FOLDER_DESCRIPTOR_NAMES: [&str; 4] = ["modinfo.json", "mod.json", "modinfo.txt", "mod.txt"];
+
+/// Largest sidecar we are willing to parse, anything bigger is not a descriptor
+const MAX_DESCRIPTOR_SIZE: u64 = 256 * 1024;
+
+/// Mod metadata read from a creator-provided sidecar file
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct ModDescriptor {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    /// Download, Patreon or documentation links
+    pub links: Vec<String>,
+    /// Names of other mods this one needs installed
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    /// Game patch the mod was made or tested for, e.g. "1.105"
+    #[serde(default)]
+    pub game_version: Option<String>,
+    /// Sidecar file the metadata was read from
+    pub source_file: String,
+}
+
+/// Read mod metadata from a sidecar descriptor next to a mod file (or inside a mod folder)
+/// Returns None when no sidecar exists or none of them contain recognizable metadata
+#[tauri::command]
+pub fn read_mod_descriptor(path: String) -> Option<ModDescriptor> {
+    descriptor_candidates(Path::new(&path))
+        .into_iter()
+        .filter(|candidate| candidate.is_file())
+        .find_map(|candidate| parse_descriptor_file(&candidate))
+}
+
+/// Conventional sidecar locations, most specific first
+/// For `Mods/Foo.package`: Foo.json, Foo.txt, Foo.package.json, Foo.package.txt, then folder-level names
+fn descriptor_candidates(mod_path: &Path) -> Vec<PathBuf> {
+    if mod_path.is_dir() {
+        return FOLDER_DESCRIPTOR_NAMES
+            .iter()
+            .map(|name| mod_path.join(name))
+            .collect();
+    }
+
+    let mut candidates = Vec::new();
+    let parent = mod_path.parent().unwrap_or(Path::new(""));
+
+    if let Some(stem) = mod_path.file_stem().and_then(|s| s.to_str()) {
+        candidates.push(parent.join(format!("{}.json", stem)));
+        candidates.push(parent.join(format!("{}.txt", stem)));
+    }
+    if let Some(file_name) = mod_path.file_name().and_then(|s| s.to_str()) {
+        candidates.push(parent.join(format!("{}.json", file_name)));
+        candidates.push(parent.join(format!("{}.txt", file_name)));
+    }
+    candidates.extend(FOLDER_DESCRIPTOR_NAMES.iter().map(|name| parent.join(name)));
+
+    // A .json/.txt mod path would otherwise be its own descriptor
+    candidates.retain(|candidate| candidate != mod_path);
+    candidates
+}
+
+/// Parse a sidecar file, picking the format from its extension
+fn parse_descriptor_file(path: &Path) -> Option<ModDescriptor> {
+    if fs::metadata(path).ok()?.len() > MAX_DESCRIPTOR_SIZE {
+        return None;
+    }
+
+    let content = fs::read(path).ok()?;
+    let content = String::from_utf8_lossy(&content);
+    let is_json = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+
+    let mut descriptor = if is_json {
+        parse_json_descriptor(&content)?
+    } else {
+        parse_text_descriptor(&content)?
+    };
+    descriptor.source_file = path.to_string_lossy().to_string();
+    Some(descriptor)
+}
+
+/// Parse descriptor text of unknown format, JSON first then `key: value` lines
+/// Used for descriptors embedded in packages, which have no extension to go by
+pub(crate) fn parse_descriptor_content(content: &str) -> Option<ModDescriptor> {
+    parse_json_descriptor(content).or_else(|| parse_text_descriptor(content))
+}
+
+/// Map a descriptor key to the field it fills, accepting the spellings creators use
+fn canonical_field(key: &str) -> Option<&'static str> {
+    let key = key.trim().to_lowercase().replace(['_', '-', ' '], "");
+    match key.as_str() {
+        "name" | "title" | "modname" => Some("name"),
+        "version" | "modversion" => Some("version"),
+        "author" | "authors" | "creator" | "creators" | "by" => Some("author"),
+        "description" | "desc" | "summary" => Some("description"),
+        "link" | "links" | "url" | "urls" | "website" | "download" | "patreon" => Some("links"),
+        "dependencies" | "dependson" | "depends" | "requires" | "requirements" | "needs" => {
+            Some("dependencies")
+        }
+        "gameversion" | "patch" | "testedon" | "testedwith" | "compatiblewith" => {
+            Some("game_version")
+        }
+        _ => None,
+    }
+}
+
+/// Store a value in the descriptor, returns whether it was recognized
+fn apply_field(descriptor: &mut ModDescriptor, key: &str, values: Vec<String>) -> bool {
+    let values: Vec<String> = values
+        .into_iter()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect();
+    if values.is_empty() {
+        return false;
+    }
+
+    let slot = match canonical_field(key) {
+        Some("name") => &mut descriptor.name,
+        Some("version") => &mut descriptor.version,
+        Some("author") => &mut descriptor.author,
+        Some("description") => &mut descriptor.description,
+        Some("game_version") => &mut descriptor.game_version,
+        Some("links") => {
+            descriptor.links.extend(values);
+            return true;
+        }
+        // Text descriptors list dependencies on one line, comma separated
+        Some("dependencies") => {
+            descriptor.dependencies.extend(
+                values
+                    .iter()
+                    .flat_map(|v| v.split(','))
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty()),
+            );
+            return true;
+        }
+        _ => return false,
+    };
+
+    if slot.is_none() {
+        *slot = Some(values.join(", "));
+    }
+    true
+}
+
+/// Parse a JSON object descriptor
+/// Values may be strings, numbers (versions) or arrays of strings (authors, links)
+fn parse_json_descriptor(content: &str) -> Option<ModDescriptor> {
+    let Value::Object(map) = serde_json::from_str::<Value>(content).ok()? else {
+        return None;
+    };
+
+    let mut descriptor = ModDescriptor::default();
+    let mut recognized = false;
+
+    for (key, value) in map {
+        let values = match value {
+            Value::String(s) => vec![s],
+            Value::Number(n) => vec![n.to_string()],
+            Value::Array(items) => items
+                .into_iter()
+                .filter_map(|item| match item {
+                    Value::String(s) => Some(s),
+                    _ => None,
+                })
+                .collect(),
+            _ => continue,
+        };
+        recognized |= apply_field(&mut descriptor, &key, values);
+    }
+
+    recognized.then_some(descriptor)
+}
+
+/// Parse a simple `key=value` / `key: value` text descriptor
+/// Lines starting with `#`, `;` or `//` are comments, unknown keys are ignored
+fn parse_text_descriptor(content: &str) -> Option<ModDescriptor> {
+    let mut descriptor = ModDescriptor::default();
+    let mut recognized = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with(';')
+            || line.starts_with("//")
+        {
+            continue;
+        }
+
+        // Split on the first separator, so URLs ("https://...") keep their colon
+        let separator = match (line.find('='), line.find(':')) {
+            (Some(eq), Some(colon)) => eq.min(colon),
+            (Some(eq), None) => eq,
+            (None, Some(colon)) => colon,
+            (None, None) => continue,
+        };
+        let (key, value) = (&line[..separator], &line[separator + 1..]);
+
+        recognized |= apply_field(&mut descriptor, key, vec![value.to_string()]);
+    }
+
+    recognized.then_some(descriptor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn reads_json_sidecar_next_to_package() {
+        let dir = tempdir().unwrap();
+        let package = dir.path().join("CoolHair.package");
+        fs::write(&package, b"DBPF").unwrap();
+        fs::write(
+            dir.path().join("CoolHair.json"),
+            r#"{ "Title": "Cool Hair", "version": 2.1, "authors": ["Ana", "Bo"],
+                "links": ["https://example.com/hair"], "unrelated": true }"#,
+        )
+        .unwrap();
+
+        let descriptor = read_mod_descriptor(package.to_string_lossy().to_string()).unwrap();
+
+        assert_eq!(descriptor.name.as_deref(), Some("Cool Hair"));
+        assert_eq!(descriptor.version.as_deref(), Some("2.1"));
+        assert_eq!(descriptor.author.as_deref(), Some("Ana, Bo"));
+        assert_eq!(descriptor.links, vec!["https://example.com/hair"]);
+        assert!(descriptor.source_file.ends_with("CoolHair.json"));
+    }
+
+    #[test]
+    fn reads_key_value_text_sidecar() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("better_buildbuy.ts4script");
+        fs::write(&script, b"PK").unwrap();
+        fs::write(
+            dir.path().join("better_buildbuy.ts4script.txt"),
+            "# exported by creator tool\nName = Better BuildBuy\nVersion: 1.4.0\nCreator=TwistedMexi\nWebsite: https://example.com/bbb\nRequires: XML Injector, Lot 51 Core\n",
+        )
+        .unwrap();
+
+        let descriptor = read_mod_descriptor(script.to_string_lossy().to_string()).unwrap();
+
+        assert_eq!(descriptor.name.as_deref(), Some("Better BuildBuy"));
+        assert_eq!(descriptor.version.as_deref(), Some("1.4.0"));
+        assert_eq!(descriptor.author.as_deref(), Some("TwistedMexi"));
+        assert_eq!(descriptor.links, vec!["https://example.com/bbb"]);
+        assert_eq!(descriptor.dependencies, vec!["XML Injector", "Lot 51 Core"]);
+    }
+
+    #[test]
+    fn falls_back_to_none_without_usable_sidecar() {
+        let dir = tempdir().unwrap();
+        let package = dir.path().join("Plain.package");
+        fs::write(&package, b"DBPF").unwrap();
+
+        assert_eq!(
+            read_mod_descriptor(package.to_string_lossy().to_string()),
+            None
+        );
+
+        // A plain readme or malformed JSON is not a descriptor
+        fs::write(dir.path().join("Plain.txt"), "Thanks for downloading!").unwrap();
+        fs::write(dir.path().join("Plain.json"), "{ not json").unwrap();
+        assert_eq!(
+            read_mod_descriptor(package.to_string_lossy().to_string()),
+            None
+        );
+    }
+
+    #[test]
+    fn reads_folder_level_descriptor() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("modinfo.json"),
+            r#"{ "name": "Folder Mod" }"#,
+        )
+        .unwrap();
+
+        let descriptor = read_mod_descriptor(dir.path().to_string_lossy().to_string()).unwrap();
+        assert_eq!(descriptor.name.as_deref(), Some("Folder Mod"));
+    }
+}
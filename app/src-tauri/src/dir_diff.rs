@@ -0,0 +1,272 @@
+use crate::archive::safe_relative_path;
+use crate::calculate_file_hash;
+use crate::manifest::remove_path;
+use crate::replace::temp_path_for;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// What it takes to make `target` identical to `source`, paths relative with forward slashes
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DirectoryDiff {
+    pub source: String,
+    pub target: String,
+    /// Files missing from the target
+    pub to_copy: Vec<String>,
+    /// Files only in the target
+    pub to_delete: Vec<String>,
+    /// Files in both whose size or content differ
+    pub to_update: Vec<String>,
+    pub unchanged: Vec<String>,
+    /// Bytes copied by `to_copy` and `to_update`
+    pub bytes_to_transfer: u64,
+}
+
+/// Outcome of `sync_directory`
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SyncReport {
+    pub copied: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    /// Steps that failed, the others were still applied
+    pub errors: Vec<String>,
+}
+
+/// Compare two trees by size, then by SHA-256 when the sizes match, without changing anything
+#[tauri::command(async)]
+pub fn compute_directory_diff(source: String, target: String) -> Result<DirectoryDiff, String> {
+    diff_directories(Path::new(&source), Path::new(&target))
+}
+
+/// Apply a plan from `compute_directory_diff`; removed files go to the trash unless `use_trash` is false
+/// Updated files are written next to the old one and renamed over it
+/// The plan is rejected as a whole if any path would leave the source or target folder
+#[tauri::command(async)]
+pub fn sync_directory(plan: DirectoryDiff, use_trash: Option<bool>) -> Result<SyncReport, String> {
+    apply_diff(&plan, use_trash.unwrap_or(true))
+}
+
+pub fn diff_directories(source: &Path, target: &Path) -> Result<DirectoryDiff, String> {
+    if !source.is_dir() {
+        return Err(format!("Directory not found: {}", source.display()));
+    }
+    let source_files = list_files(source);
+    // A missing target is an empty one, everything gets copied
+    let target_files = if target.is_dir() {
+        list_files(target)
+    } else {
+        BTreeMap::new()
+    };
+
+    let mut diff = DirectoryDiff {
+        source: source.to_string_lossy().to_string(),
+        target: target.to_string_lossy().to_string(),
+        to_copy: Vec::new(),
+        to_delete: Vec::new(),
+        to_update: Vec::new(),
+        unchanged: Vec::new(),
+        bytes_to_transfer: 0,
+    };
+
+    let mut same_size = Vec::new();
+    for (relative, &size) in &source_files {
+        match target_files.get(relative) {
+            None => {
+                diff.to_copy.push(relative.clone());
+                diff.bytes_to_transfer += size;
+            }
+            Some(&target_size) if target_size != size => {
+                diff.to_update.push(relative.clone());
+                diff.bytes_to_transfer += size;
+            }
+            Some(_) => same_size.push((relative.clone(), size)),
+        }
+    }
+    diff.to_delete = target_files
+        .keys()
+        .filter(|relative| !source_files.contains_key(*relative))
+        .cloned()
+        .collect();
+
+    let compared: Vec<(String, u64, bool)> = same_size
+        .into_par_iter()
+        .map(|(relative, size)| {
+            let hash = |root: &Path| {
+                calculate_file_hash(root.join(&relative).to_string_lossy().to_string())
+            };
+            // Unreadable files count as changed, syncing will surface the error
+            let identical = matches!((hash(source), hash(target)), (Ok(a), Ok(b)) if a == b);
+            (relative, size, identical)
+        })
+        .collect();
+    for (relative, size, identical) in compared {
+        if identical {
+            diff.unchanged.push(relative);
+        } else {
+            diff.to_update.push(relative);
+            diff.bytes_to_transfer += size;
+        }
+    }
+    diff.to_update.sort();
+
+    Ok(diff)
+}
+
+/// Files under `root` by relative path (forward slashes), with their size
+fn list_files(root: &Path) -> BTreeMap<String, u64> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            let relative = entry
+                .path()
+                .strip_prefix(root)
+                .unwrap_or(entry.path())
+                .to_string_lossy()
+                .replace('\\', "/");
+            (relative, entry.metadata().map(|m| m.len()).unwrap_or(0))
+        })
+        .collect()
+}
+
+fn apply_diff(plan: &DirectoryDiff, use_trash: bool) -> Result<SyncReport, String> {
+    let (source, target) = (Path::new(&plan.source), Path::new(&plan.target));
+    let checked = |paths: &[String]| {
+        paths
+            .iter()
+            .map(|relative| {
+                safe_relative_path(relative)
+                    .ok_or_else(|| format!("Invalid path in sync plan: {}", relative))
+            })
+            .collect::<Result<Vec<PathBuf>, String>>()
+    };
+    let (to_copy, to_update, to_delete) = (
+        checked(&plan.to_copy)?,
+        checked(&plan.to_update)?,
+        checked(&plan.to_delete)?,
+    );
+    let mut report = SyncReport::default();
+
+    for relative in &to_copy {
+        match copy_file(&source.join(relative), &target.join(relative)) {
+            Ok(()) => report.copied += 1,
+            Err(e) => report.errors.push(e),
+        }
+    }
+    for relative in &to_update {
+        match copy_file(&source.join(relative), &target.join(relative)) {
+            Ok(()) => report.updated += 1,
+            Err(e) => report.errors.push(e),
+        }
+    }
+    for relative in &to_delete {
+        match remove_path(&target.join(relative), use_trash) {
+            Ok(()) => report.deleted += 1,
+            Err(e) => report.errors.push(e),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Copy through a temporary file so an interrupted sync never leaves a truncated file
+fn copy_file(from: &Path, to: &Path) -> Result<(), String> {
+    let parent = to.parent().map(PathBuf::from).unwrap_or_default();
+    fs::create_dir_all(&parent)
+        .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+
+    let temp = temp_path_for(to)?;
+    let result = fs::copy(from, &temp).and_then(|_| fs::rename(&temp, to));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+        .map(|_| ())
+        .map_err(|e| format!("Failed to copy {}: {}", from.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write(root: &Path, relative: &str, content: &[u8]) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn plans_additions_deletions_and_modifications() {
+        let dir = tempdir().unwrap();
+        let (source, target) = (dir.path().join("source"), dir.path().join("target"));
+        write(&source, "same.package", b"identical");
+        write(&source, "CAS/new.package", b"added");
+        write(&source, "resized.package", b"longer content");
+        write(&source, "edited.package", b"version 2");
+        write(&target, "same.package", b"identical");
+        write(&target, "resized.package", b"short");
+        write(&target, "edited.package", b"version 1");
+        write(&target, "Old/removed.package", b"gone");
+
+        let diff = compute_directory_diff(
+            source.to_string_lossy().to_string(),
+            target.to_string_lossy().to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(diff.to_copy, vec!["CAS/new.package"]);
+        assert_eq!(diff.to_delete, vec!["Old/removed.package"]);
+        assert_eq!(diff.to_update, vec!["edited.package", "resized.package"]);
+        assert_eq!(diff.unchanged, vec!["same.package"]);
+        assert_eq!(diff.bytes_to_transfer, 5 + 14 + 9);
+        // Planning is read-only
+        assert_eq!(
+            fs::read(target.join("edited.package")).unwrap(),
+            b"version 1"
+        );
+
+        let report = sync_directory(diff, Some(false)).unwrap();
+        assert!(report.errors.is_empty());
+        assert_eq!((report.copied, report.updated, report.deleted), (1, 2, 1));
+
+        let after = diff_directories(&source, &target).unwrap();
+        assert!(after.to_copy.is_empty() && after.to_update.is_empty());
+        assert!(after.to_delete.is_empty());
+        assert_eq!(after.unchanged.len(), 4);
+    }
+
+    #[test]
+    fn missing_target_copies_everything() {
+        let dir = tempdir().unwrap();
+        let source = dir.path().join("source");
+        write(&source, "a.package", b"a");
+
+        let diff = diff_directories(&source, &dir.path().join("missing")).unwrap();
+
+        assert_eq!(diff.to_copy, vec!["a.package"]);
+        assert!(diff_directories(&dir.path().join("missing"), &source).is_err());
+    }
+
+    #[test]
+    fn rejects_plans_leaving_the_folders() {
+        let dir = tempdir().unwrap();
+        let (source, target) = (dir.path().join("source"), dir.path().join("target"));
+        write(&source, "a.package", b"a");
+        write(&target, "b.package", b"b");
+        write(dir.path(), "outside.package", b"keep me");
+
+        let mut diff = diff_directories(&source, &target).unwrap();
+        diff.to_delete.push("../outside.package".to_string());
+
+        assert!(sync_directory(diff, Some(false)).is_err());
+        // Nothing from the plan was applied
+        assert!(dir.path().join("outside.package").exists());
+        assert!(target.join("b.package").exists());
+        assert!(!target.join("a.package").exists());
+    }
+}
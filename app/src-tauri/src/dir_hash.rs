@@ -0,0 +1,156 @@
+use crate::calculate_file_hash;
+use crate::operations::{CancellationToken, OperationRegistry};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+use walkdir::WalkDir;
+
+/// SHA-256 of one file of a hashed directory
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FileHash {
+    /// Path relative to the hashed directory, with forward slashes
+    pub relative_path: String,
+    pub hash: String,
+    pub size: u64,
+}
+
+/// Hashes of every file in a directory
+/// When cancelled, `files` holds what was hashed before the stop and `cancelled` is set
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DirectoryHashResult {
+    pub files: Vec<FileHash>,
+    /// Number of files in the directory, hashed or not
+    pub total_files: usize,
+    pub cancelled: bool,
+}
+
+/// Progress payload emitted on `hash-dir://progress`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HashDirectoryProgress {
+    pub operation_id: String,
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Hash every file under `root` (sorted by path), for backup verification
+/// Emits `hash-dir://progress` after each file and stops between files on `cancel_operation`
+#[tauri::command(async)]
+pub fn hash_directory(
+    app_handle: tauri::AppHandle,
+    registry: tauri::State<'_, OperationRegistry>,
+    root: String,
+    operation_id: String,
+) -> Result<DirectoryHashResult, String> {
+    let operation = registry.start(&operation_id);
+
+    hash_files(Path::new(&root), operation.token(), |done, total| {
+        let _ = app_handle.emit(
+            "hash-dir://progress",
+            HashDirectoryProgress {
+                operation_id: operation_id.clone(),
+                done,
+                total,
+            },
+        );
+    })
+}
+
+fn hash_files(
+    root: &Path,
+    token: &CancellationToken,
+    on_progress: impl Fn(usize, usize),
+) -> Result<DirectoryHashResult, String> {
+    if !root.is_dir() {
+        return Err(format!("Directory not found: {}", root.display()));
+    }
+
+    let paths: Vec<PathBuf> = WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+    let total = paths.len();
+
+    let mut files = Vec::with_capacity(total);
+    on_progress(0, total);
+
+    for path in &paths {
+        if token.is_cancelled() {
+            return Ok(DirectoryHashResult {
+                files,
+                total_files: total,
+                cancelled: true,
+            });
+        }
+
+        files.push(FileHash {
+            relative_path: path
+                .strip_prefix(root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/"),
+            hash: calculate_file_hash(path.to_string_lossy().to_string())?,
+            size: path.metadata().map(|m| m.len()).unwrap_or(0),
+        });
+        on_progress(files.len(), total);
+    }
+
+    Ok(DirectoryHashResult {
+        files,
+        total_files: total,
+        cancelled: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn profile(root: &Path) {
+        fs::create_dir_all(root.join("Mods")).unwrap();
+        for i in 0..5 {
+            fs::write(root.join(format!("Mods/{}.package", i)), [i as u8; 8]).unwrap();
+        }
+    }
+
+    #[test]
+    fn hashes_every_file() {
+        let dir = tempdir().unwrap();
+        profile(dir.path());
+
+        let result = hash_files(dir.path(), &CancellationToken::default(), |_, _| {}).unwrap();
+
+        assert!(!result.cancelled);
+        assert_eq!(result.total_files, 5);
+        assert_eq!(result.files[0].relative_path, "Mods/0.package");
+        assert_eq!(result.files[0].size, 8);
+        assert_eq!(result.files[0].hash.len(), 64);
+    }
+
+    #[test]
+    fn cancelling_mid_walk_returns_partial_result() {
+        let dir = tempdir().unwrap();
+        profile(dir.path());
+        let token = CancellationToken::default();
+
+        let result = hash_files(dir.path(), &token, |done, _| {
+            if done == 2 {
+                token.cancel();
+            }
+        })
+        .unwrap();
+
+        assert!(result.cancelled);
+        assert_eq!(result.total_files, 5);
+        let hashed: Vec<&str> = result
+            .files
+            .iter()
+            .map(|f| f.relative_path.as_str())
+            .collect();
+        assert_eq!(hashed, vec!["Mods/0.package", "Mods/1.package"]);
+    }
+}
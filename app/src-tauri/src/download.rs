@@ -0,0 +1,748 @@
+use crate::calculate_file_hash;
+use crate::extract::CHECKSUM_MISMATCH_ERROR;
+use crate::operations::{CancellationToken, OperationRegistry, CANCELLED_ERROR};
+use reqwest::header::{CONTENT_LENGTH, RANGE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+
+/// Unit of resume and repair, each chunk's CRC-32 is journaled as it arrives
+pub(crate) const CHUNK_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Repair rounds attempted after a hash mismatch before giving up
+const MAX_REPAIR_ATTEMPTS: usize = 3;
+
+/// Progress payload emitted on `download://progress`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DownloadProgress {
+    pub operation_id: String,
+    pub downloaded: u64,
+    /// None when the server doesn't announce a size
+    pub total: Option<u64>,
+}
+
+/// One repair round, triggered by a hash mismatch
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecoveryAttempt {
+    /// Indices of the re-fetched chunks
+    pub chunks: Vec<u64>,
+    pub bytes: u64,
+}
+
+/// Summary of a download
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DownloadReport {
+    pub path: String,
+    /// Bytes received from the network, repairs included
+    pub bytes_downloaded: u64,
+    /// Whether an earlier partial download was continued
+    pub resumed: bool,
+    /// SHA-256 of the finished file, computed from the bytes as they were written
+    /// (the file is only read back after a repair)
+    pub hash: String,
+    pub recovery_attempts: Vec<RecoveryAttempt>,
+}
+
+/// Download `url` to `dest_path`, resuming a previous partial download when possible
+/// With `expected_hash`, a mismatching file is repaired by re-fetching only the chunks
+/// that differ from what was received, up to a few attempts
+/// A file that still mismatches is deleted, or kept as `<dest>.invalid` when `delete_invalid` is false
+/// Emits `download://progress` and can be stopped with `cancel_operation(operation_id)`,
+/// the partial file is kept so the next call resumes it
+#[tauri::command]
+pub async fn download_archive(
+    app_handle: tauri::AppHandle,
+    registry: tauri::State<'_, OperationRegistry>,
+    url: String,
+    dest_path: String,
+    expected_hash: Option<String>,
+    delete_invalid: Option<bool>,
+    operation_id: String,
+) -> Result<DownloadReport, String> {
+    let operation = registry.start(&operation_id);
+
+    download_verified(
+        &url,
+        Path::new(&dest_path),
+        expected_hash.as_deref(),
+        delete_invalid.unwrap_or(true),
+        CHUNK_SIZE,
+        operation.token(),
+        |downloaded, total| {
+            let _ = app_handle.emit(
+                "download://progress",
+                DownloadProgress {
+                    operation_id: operation_id.clone(),
+                    downloaded,
+                    total,
+                },
+            );
+        },
+    )
+    .await
+}
+
+pub async fn download_verified(
+    url: &str,
+    dest: &Path,
+    expected_hash: Option<&str>,
+    delete_invalid: bool,
+    chunk_size: u64,
+    token: &CancellationToken,
+    on_progress: impl Fn(u64, Option<u64>),
+) -> Result<DownloadReport, String> {
+    let part_path = sibling(dest, "part");
+    let mut journal = ChunkJournal::open(sibling(dest, "part.chunks"))?;
+    let client = reqwest::Client::new();
+
+    let fetched = fetch_remaining(
+        &client,
+        url,
+        &part_path,
+        &mut journal,
+        chunk_size,
+        token,
+        &on_progress,
+    )
+    .await?;
+    let mut bytes_downloaded = fetched.bytes;
+
+    let mut recovery_attempts = Vec::new();
+    let mut hash = match fetched.hash {
+        Some(hash) => hash,
+        None => calculate_file_hash(part_path.to_string_lossy().to_string())?,
+    };
+
+    if let Some(expected) = expected_hash.map(str::trim) {
+        while !hash.eq_ignore_ascii_case(expected) {
+            if recovery_attempts.len() == MAX_REPAIR_ATTEMPTS {
+                journal.remove();
+                let kept = if delete_invalid {
+                    let _ = fs::remove_file(&part_path);
+                    String::new()
+                } else {
+                    let invalid_path = sibling(dest, "invalid");
+                    fs::rename(&part_path, &invalid_path).map_err(|e| {
+                        format!(
+                            "Failed to move download to {}: {}",
+                            invalid_path.display(),
+                            e
+                        )
+                    })?;
+                    format!(", kept as {}", invalid_path.display())
+                };
+                return Err(format!(
+                    "{}: expected {}, got {} after {} repair attempts{}",
+                    CHECKSUM_MISMATCH_ERROR, expected, hash, MAX_REPAIR_ATTEMPTS, kept
+                ));
+            }
+            if token.is_cancelled() {
+                return Err(CANCELLED_ERROR.to_string());
+            }
+
+            let attempt = repair(&client, url, &part_path, &mut journal, chunk_size).await?;
+            bytes_downloaded += attempt.bytes;
+            recovery_attempts.push(attempt);
+            hash = calculate_file_hash(part_path.to_string_lossy().to_string())?;
+        }
+    }
+
+    fs::rename(&part_path, dest)
+        .map_err(|e| format!("Failed to move download to {}: {}", dest.display(), e))?;
+    journal.remove();
+
+    Ok(DownloadReport {
+        path: dest.to_string_lossy().to_string(),
+        bytes_downloaded,
+        resumed: fetched.resumed,
+        hash,
+        recovery_attempts,
+    })
+}
+
+/// Outcome of `fetch_remaining`
+struct Fetched {
+    /// Bytes received from the network
+    bytes: u64,
+    resumed: bool,
+    /// SHA-256 of the whole part file, None when nothing had to be fetched
+    hash: Option<String>,
+}
+
+/// Download whatever is missing from `part_path`, hashing the file as it is written
+async fn fetch_remaining(
+    client: &reqwest::Client,
+    url: &str,
+    part_path: &Path,
+    journal: &mut ChunkJournal,
+    chunk_size: u64,
+    token: &CancellationToken,
+    on_progress: &impl Fn(u64, Option<u64>),
+) -> Result<Fetched, String> {
+    let part_len = fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+    if journal.size == Some(part_len) {
+        return Ok(Fetched {
+            bytes: 0,
+            resumed: part_len > 0,
+            hash: None,
+        });
+    }
+
+    // Only chunks whose CRC made it to the journal are trusted, the tail is fetched again
+    // Resuming stays on a chunk boundary so every chunk is journaled from its first byte
+    let mut resume_from = 0;
+    while journal.crcs.contains_key(&(resume_from / chunk_size)) {
+        resume_from += chunk_size;
+    }
+    let resume_from = resume_from.min(part_len / chunk_size * chunk_size);
+
+    let mut request = client.get(url);
+    if resume_from > 0 {
+        request = request.header(RANGE, format!("bytes={}-", resume_from));
+    }
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+
+    let start = match response.status() {
+        StatusCode::PARTIAL_CONTENT if resume_from > 0 => resume_from,
+        // Every chunk was journaled but the process stopped before recording the size
+        StatusCode::RANGE_NOT_SATISFIABLE if resume_from > 0 && resume_from == part_len => {
+            journal.finish(part_len)?;
+            return Ok(Fetched {
+                bytes: 0,
+                resumed: true,
+                hash: None,
+            });
+        }
+        // The server ignored the range, start over
+        status if status.is_success() => {
+            journal.clear()?;
+            0
+        }
+        status => return Err(format!("Server returned {} for {}", status, url)),
+    };
+    let total = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(|length| start + length);
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(part_path)
+        .map_err(|e| format!("Failed to open {}: {}", part_path.display(), e))?;
+    file.set_len(start)
+        .and_then(|_| file.seek(SeekFrom::Start(start)))
+        .map_err(|e| format!("Failed to prepare {}: {}", part_path.display(), e))?;
+
+    // A resumed download only reads back the part it already had
+    let mut digest = Sha256::new();
+    if start > 0 {
+        File::open(part_path)
+            .and_then(|file| io::copy(&mut file.take(start), &mut digest))
+            .map_err(|e| format!("Failed to read {}: {}", part_path.display(), e))?;
+    }
+
+    let mut offset = start;
+    let mut hasher = crc32fast::Hasher::new();
+    on_progress(offset, total);
+
+    while let Some(bytes) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?
+    {
+        if token.is_cancelled() {
+            return Err(CANCELLED_ERROR.to_string());
+        }
+        file.write_all(&bytes)
+            .map_err(|e| format!("Failed to write {}: {}", part_path.display(), e))?;
+        digest.update(&bytes);
+
+        // Split the received bytes on chunk boundaries to journal each completed chunk
+        let mut rest: &[u8] = &bytes;
+        while !rest.is_empty() {
+            let room = (chunk_size - offset % chunk_size) as usize;
+            let (head, tail) = rest.split_at(room.min(rest.len()));
+            hasher.update(head);
+            offset += head.len() as u64;
+            rest = tail;
+            if offset % chunk_size == 0 {
+                let crc = std::mem::take(&mut hasher).finalize();
+                journal.record((offset - 1) / chunk_size, crc)?;
+            }
+        }
+        on_progress(offset, total);
+    }
+
+    if offset % chunk_size != 0 {
+        journal.record(offset / chunk_size, hasher.finalize())?;
+    }
+    file.sync_all()
+        .map_err(|e| format!("Failed to sync {}: {}", part_path.display(), e))?;
+    journal.finish(offset)?;
+
+    Ok(Fetched {
+        bytes: offset - start,
+        resumed: start > 0,
+        hash: Some(format!("{:x}", digest.finalize())),
+    })
+}
+
+/// Re-fetch the chunks whose content on disk no longer matches what was received
+/// When every chunk matches, the corruption happened in transit and everything is fetched again
+async fn repair(
+    client: &reqwest::Client,
+    url: &str,
+    part_path: &Path,
+    journal: &mut ChunkJournal,
+    chunk_size: u64,
+) -> Result<RecoveryAttempt, String> {
+    let size = fs::metadata(part_path)
+        .map_err(|e| format!("Failed to read {}: {}", part_path.display(), e))?
+        .len();
+    let chunk_count = size.div_ceil(chunk_size);
+
+    let mut chunks = damaged_chunks(part_path, journal, chunk_size, chunk_count)?;
+    if chunks.is_empty() {
+        chunks = (0..chunk_count).collect();
+    }
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .open(part_path)
+        .map_err(|e| format!("Failed to open {}: {}", part_path.display(), e))?;
+    let mut bytes = 0;
+
+    for &index in &chunks {
+        let start = index * chunk_size;
+        let end = (start + chunk_size).min(size) - 1;
+        let response = client
+            .get(url)
+            .header(RANGE, format!("bytes={}-{}", start, end))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+        if response.status() != StatusCode::PARTIAL_CONTENT {
+            return Err(format!(
+                "{}: the server doesn't support partial downloads, the file can't be repaired",
+                CHECKSUM_MISMATCH_ERROR
+            ));
+        }
+        let data = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+        if data.len() as u64 != end - start + 1 {
+            return Err(format!("Server returned a truncated range for {}", url));
+        }
+
+        file.seek(SeekFrom::Start(start))
+            .and_then(|_| file.write_all(&data))
+            .map_err(|e| format!("Failed to write {}: {}", part_path.display(), e))?;
+        journal.record(index, crc32fast::hash(&data))?;
+        bytes += data.len() as u64;
+    }
+    file.sync_all()
+        .map_err(|e| format!("Failed to sync {}: {}", part_path.display(), e))?;
+
+    Ok(RecoveryAttempt { chunks, bytes })
+}
+
+/// Chunks whose CRC on disk differs from the journal (or was never journaled)
+fn damaged_chunks(
+    part_path: &Path,
+    journal: &ChunkJournal,
+    chunk_size: u64,
+    chunk_count: u64,
+) -> Result<Vec<u64>, String> {
+    let mut file = File::open(part_path)
+        .map_err(|e| format!("Failed to open {}: {}", part_path.display(), e))?;
+    let mut buffer = vec![0u8; chunk_size as usize];
+    let mut damaged = Vec::new();
+
+    for index in 0..chunk_count {
+        let mut read = 0;
+        while read < buffer.len() {
+            match file.read(&mut buffer[read..]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(e) => return Err(format!("Failed to read {}: {}", part_path.display(), e)),
+            }
+        }
+        if journal.crcs.get(&index) != Some(&crc32fast::hash(&buffer[..read])) {
+            damaged.push(index);
+        }
+    }
+
+    Ok(damaged)
+}
+
+/// `<dest>.<suffix>` next to the destination
+fn sibling(dest: &Path, suffix: &str) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", suffix));
+    dest.with_file_name(name)
+}
+
+/// Append-only record of the CRC-32 of every chunk received, later lines win
+/// `chunk <index> <crc>` per chunk, `size <bytes>` once the download completed
+struct ChunkJournal {
+    path: PathBuf,
+    crcs: HashMap<u64, u32>,
+    size: Option<u64>,
+}
+
+impl ChunkJournal {
+    fn open(path: PathBuf) -> Result<Self, String> {
+        let mut journal = Self {
+            path,
+            crcs: HashMap::new(),
+            size: None,
+        };
+
+        // A torn last line from a crash is simply ignored
+        let content = fs::read_to_string(&journal.path).unwrap_or_default();
+        for line in content.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match parts.as_slice() {
+                ["chunk", index, crc] => {
+                    if let (Ok(index), Ok(crc)) = (index.parse(), u32::from_str_radix(crc, 16)) {
+                        journal.crcs.insert(index, crc);
+                    }
+                }
+                ["size", size] => journal.size = size.parse().ok(),
+                _ => {}
+            }
+        }
+
+        Ok(journal)
+    }
+
+    fn record(&mut self, index: u64, crc: u32) -> Result<(), String> {
+        self.append(&format!("chunk {} {:08x}\n", index, crc))?;
+        self.crcs.insert(index, crc);
+        Ok(())
+    }
+
+    fn finish(&mut self, size: u64) -> Result<(), String> {
+        self.append(&format!("size {}\n", size))?;
+        self.size = Some(size);
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        fs::write(&self.path, "")
+            .map_err(|e| format!("Failed to reset {}: {}", self.path.display(), e))?;
+        self.crcs.clear();
+        self.size = None;
+        Ok(())
+    }
+
+    fn remove(&self) {
+        let _ = fs::remove_file(&self.path);
+    }
+
+    fn append(&self, line: &str) -> Result<(), String> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::range_server;
+    use std::sync::Mutex;
+    use tempfile::tempdir;
+
+    const TEST_CHUNK: u64 = 1024;
+
+    fn content() -> Vec<u8> {
+        (0..5000u32).map(|i| (i * 7 % 251) as u8).collect()
+    }
+
+    fn sha256(data: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(data))
+    }
+
+    #[tokio::test]
+    async fn refetches_corrupted_middle_chunk() {
+        let dir = tempdir().unwrap();
+        let dest = dir.path().join("pack.zip");
+        let body = content();
+        let (url, requests) = range_server(body.clone());
+        let token = CancellationToken::default();
+
+        // A previous run got everything, then a crash left garbage in the second chunk
+        let mut journal = ChunkJournal::open(sibling(&dest, "part.chunks")).unwrap();
+        let client = reqwest::Client::new();
+        fetch_remaining(
+            &client,
+            &url,
+            &sibling(&dest, "part"),
+            &mut journal,
+            TEST_CHUNK,
+            &token,
+            &|_, _| {},
+        )
+        .await
+        .unwrap();
+        let mut part = fs::read(sibling(&dest, "part")).unwrap();
+        part[1500..1600].fill(0);
+        fs::write(sibling(&dest, "part"), part).unwrap();
+
+        let report = download_verified(
+            &url,
+            &dest,
+            Some(&sha256(&body)),
+            true,
+            TEST_CHUNK,
+            &token,
+            |_, _| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(fs::read(&dest).unwrap(), body);
+        assert_eq!(
+            report.recovery_attempts,
+            vec![RecoveryAttempt {
+                chunks: vec![1],
+                bytes: TEST_CHUNK,
+            }]
+        );
+        assert!(report.resumed);
+        assert_eq!(report.bytes_downloaded, TEST_CHUNK);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![None, Some("1024-2047".to_string())]
+        );
+        assert!(!sibling(&dest, "part").exists());
+        assert!(!sibling(&dest, "part.chunks").exists());
+    }
+
+    #[tokio::test]
+    async fn resumes_from_last_journaled_chunk() {
+        let dir = tempdir().unwrap();
+        let dest = dir.path().join("pack.zip");
+        let body = content();
+        let (url, requests) = range_server(body.clone());
+
+        // Two chunks made it to the journal, the third was cut short
+        fs::write(sibling(&dest, "part"), &body[..2500]).unwrap();
+        let mut journal = ChunkJournal::open(sibling(&dest, "part.chunks")).unwrap();
+        journal.record(0, crc32fast::hash(&body[..1024])).unwrap();
+        journal
+            .record(1, crc32fast::hash(&body[1024..2048]))
+            .unwrap();
+
+        let progress = Mutex::new(Vec::new());
+        let report = download_verified(
+            &url,
+            &dest,
+            Some(&sha256(&body)),
+            true,
+            TEST_CHUNK,
+            &CancellationToken::default(),
+            |downloaded, total| progress.lock().unwrap().push((downloaded, total)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(fs::read(&dest).unwrap(), body);
+        assert!(report.resumed);
+        assert_eq!(report.bytes_downloaded, 5000 - 2048);
+        assert!(report.recovery_attempts.is_empty());
+        assert_eq!(*requests.lock().unwrap(), vec![Some("2048-".to_string())]);
+        let progress = progress.into_inner().unwrap();
+        assert_eq!(progress.first(), Some(&(2048, Some(5000))));
+        assert_eq!(progress.last(), Some(&(5000, Some(5000))));
+    }
+
+    #[tokio::test]
+    async fn completes_when_every_chunk_was_journaled() {
+        let dir = tempdir().unwrap();
+        let dest = dir.path().join("pack.zip");
+        let body = content()[..4096].to_vec();
+        let (url, requests) = range_server(body.clone());
+
+        // Stopped after the last chunk was journaled, before the size was written
+        fs::write(sibling(&dest, "part"), &body).unwrap();
+        let mut journal = ChunkJournal::open(sibling(&dest, "part.chunks")).unwrap();
+        for (index, chunk) in body.chunks(TEST_CHUNK as usize).enumerate() {
+            journal
+                .record(index as u64, crc32fast::hash(chunk))
+                .unwrap();
+        }
+
+        let report = download_verified(
+            &url,
+            &dest,
+            Some(&sha256(&body)),
+            true,
+            TEST_CHUNK,
+            &CancellationToken::default(),
+            |_, _| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(fs::read(&dest).unwrap(), body);
+        assert!(report.resumed);
+        assert_eq!(report.bytes_downloaded, 0);
+        assert_eq!(*requests.lock().unwrap(), vec![Some("4096-".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn resumes_a_partial_last_chunk_from_its_start() {
+        let dir = tempdir().unwrap();
+        let dest = dir.path().join("pack.zip");
+        let body = content();
+        let (url, requests) = range_server(body.clone());
+
+        fs::write(sibling(&dest, "part"), &body).unwrap();
+        let mut journal = ChunkJournal::open(sibling(&dest, "part.chunks")).unwrap();
+        for (index, chunk) in body.chunks(TEST_CHUNK as usize).enumerate() {
+            journal
+                .record(index as u64, crc32fast::hash(chunk))
+                .unwrap();
+        }
+
+        let report = download_verified(
+            &url,
+            &dest,
+            Some(&sha256(&body)),
+            true,
+            TEST_CHUNK,
+            &CancellationToken::default(),
+            |_, _| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(fs::read(&dest).unwrap(), body);
+        assert_eq!(report.bytes_downloaded, 5000 - 4096);
+        assert_eq!(*requests.lock().unwrap(), vec![Some("4096-".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_repair_attempts() {
+        let dir = tempdir().unwrap();
+        let dest = dir.path().join("pack.zip");
+        let (url, requests) = range_server(content());
+
+        let error = download_verified(
+            &url,
+            &dest,
+            Some(&"0".repeat(64)),
+            true,
+            TEST_CHUNK,
+            &CancellationToken::default(),
+            |_, _| {},
+        )
+        .await
+        .unwrap_err();
+
+        assert!(error.starts_with(CHECKSUM_MISMATCH_ERROR));
+        // The first download, then every chunk re-fetched on each attempt
+        assert_eq!(requests.lock().unwrap().len(), 1 + 5 * MAX_REPAIR_ATTEMPTS);
+        assert!(!dest.exists());
+        assert!(!sibling(&dest, "part").exists());
+
+        // Kept for inspection when asked to
+        let error = download_verified(
+            &url,
+            &dest,
+            Some(&"0".repeat(64)),
+            false,
+            TEST_CHUNK,
+            &CancellationToken::default(),
+            |_, _| {},
+        )
+        .await
+        .unwrap_err();
+        assert!(error.ends_with(&format!("kept as {}", sibling(&dest, "invalid").display())));
+        assert_eq!(fs::read(sibling(&dest, "invalid")).unwrap(), content());
+        assert!(!dest.exists());
+    }
+
+    #[tokio::test]
+    async fn streamed_hash_matches_file_hash() {
+        let dir = tempdir().unwrap();
+        let body = content();
+        let (url, _) = range_server(body.clone());
+        let client = reqwest::Client::new();
+        let token = CancellationToken::default();
+        let file_hash = |path: &Path| calculate_file_hash(path.to_string_lossy().to_string());
+
+        // Fresh download
+        let fresh = dir.path().join("fresh.part");
+        let mut journal = ChunkJournal::open(dir.path().join("fresh.chunks")).unwrap();
+        let fetched = fetch_remaining(
+            &client,
+            &url,
+            &fresh,
+            &mut journal,
+            TEST_CHUNK,
+            &token,
+            &|_, _| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(fetched.hash, Some(file_hash(&fresh).unwrap()));
+
+        // Resumed download, the existing prefix is part of the digest
+        let resumed = dir.path().join("resumed.part");
+        fs::write(&resumed, &body[..2048]).unwrap();
+        let mut journal = ChunkJournal::open(dir.path().join("resumed.chunks")).unwrap();
+        journal.record(0, crc32fast::hash(&body[..1024])).unwrap();
+        journal
+            .record(1, crc32fast::hash(&body[1024..2048]))
+            .unwrap();
+        let fetched = fetch_remaining(
+            &client,
+            &url,
+            &resumed,
+            &mut journal,
+            TEST_CHUNK,
+            &token,
+            &|_, _| {},
+        )
+        .await
+        .unwrap();
+        assert!(fetched.resumed);
+        assert_eq!(fetched.hash, Some(file_hash(&resumed).unwrap()));
+        assert_eq!(fetched.hash, Some(sha256(&body)));
+
+        let report = download_verified(
+            &url,
+            &dir.path().join("pack.zip"),
+            None,
+            true,
+            TEST_CHUNK,
+            &token,
+            |_, _| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            report.hash,
+            file_hash(&dir.path().join("pack.zip")).unwrap()
+        );
+    }
+}
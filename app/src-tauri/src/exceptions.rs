@@ -0,0 +1,276 @@
+use crate::manifest::{self, InstallRecord};
+use crate::script::{inspect_script, module_name};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+/// Evidence weights, a script named in a traceback is the strongest hint
+const SCRIPT_FILE_SCORE: u32 = 3;
+const MODULE_SCORE: u32 = 2;
+const PACKAGE_FILE_SCORE: u32 = 2;
+const MOD_NAME_SCORE: u32 = 1;
+
+/// Mod names shorter than this match too much unrelated text to count
+const MIN_MOD_NAME_LEN: usize = 4;
+
+/// Traceback paths under the game's own script root are never a mod's fault
+const GAME_SCRIPT_PREFIX: &str = "t:\\ingame\\";
+
+/// Installed mod implicated by an exception log
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExceptionSuspect {
+    pub mod_id: String,
+    pub mod_name: String,
+    /// Strongest piece of the log pointing at this mod (file name, module or mod name)
+    pub matched_token: String,
+    /// Sum of the evidence found, higher is more likely to be the culprit
+    pub score: u32,
+}
+
+/// What an installed mod can be recognized by in a log
+#[derive(Debug, Default)]
+pub struct ModFingerprint {
+    pub id: String,
+    pub name: String,
+    /// Lowercase names of the installed .ts4script files
+    pub script_files: Vec<String>,
+    /// Lowercase names of the installed .package files
+    pub package_files: Vec<String>,
+    /// Python modules provided by the installed scripts
+    pub modules: HashSet<String>,
+}
+
+/// Scan a lastException log and rank the installed mods it implicates, most likely first
+#[tauri::command(async)]
+pub fn correlate_exception(
+    app_handle: tauri::AppHandle,
+    log_path: String,
+) -> Result<Vec<ExceptionSuspect>, String> {
+    let log = fs::read(&log_path)
+        .map_err(|e| format!("Failed to read exception log {}: {}", log_path, e))?;
+    let installs = manifest::open_app_manifest(&app_handle)?.list_installs()?;
+    let fingerprints: Vec<ModFingerprint> = installs.iter().map(fingerprint).collect();
+
+    Ok(correlate(&String::from_utf8_lossy(&log), &fingerprints))
+}
+
+/// Collect file names and, for scripts still on disk, the modules they provide
+pub fn fingerprint(record: &InstallRecord) -> ModFingerprint {
+    let mut fingerprint = ModFingerprint {
+        id: record.id.clone(),
+        name: record.name.clone(),
+        ..Default::default()
+    };
+
+    for file in &record.files {
+        let path = Path::new(&file.path);
+        let Some(file_name) = path.file_name().map(|n| n.to_string_lossy().to_lowercase()) else {
+            continue;
+        };
+
+        if file_name.ends_with(".ts4script") {
+            if let Ok(inspection) = inspect_script(path) {
+                fingerprint.modules.extend(inspection.modules);
+            }
+            fingerprint.script_files.push(file_name);
+        } else if file_name.ends_with(".package") {
+            fingerprint.package_files.push(file_name);
+        }
+    }
+
+    fingerprint
+}
+
+pub fn correlate(log: &str, mods: &[ModFingerprint]) -> Vec<ExceptionSuspect> {
+    let file_tokens = file_name_tokens(log);
+    let traceback_modules: Vec<String> = traceback_paths(log)
+        .iter()
+        .filter(|path| !path.to_lowercase().starts_with(GAME_SCRIPT_PREFIX))
+        .filter_map(|path| module_name(path))
+        .collect();
+    let lower_log = log.to_lowercase();
+
+    // Mod index -> (score, strongest token with its weight)
+    let mut evidence: BTreeMap<usize, (u32, u32, String)> = BTreeMap::new();
+    let mut add = |index: usize, weight: u32, token: &str| {
+        let entry = evidence
+            .entry(index)
+            .or_insert_with(|| (0, 0, String::new()));
+        entry.0 += weight;
+        if weight > entry.1 {
+            entry.1 = weight;
+            entry.2 = token.to_string();
+        }
+    };
+
+    for (index, fingerprint) in mods.iter().enumerate() {
+        for token in &file_tokens {
+            if fingerprint.script_files.contains(token) {
+                add(index, SCRIPT_FILE_SCORE, token);
+            } else if fingerprint.package_files.contains(token) {
+                add(index, PACKAGE_FILE_SCORE, token);
+            }
+        }
+
+        // A traceback path may carry extra leading folders, so any dotted suffix can be the module
+        let mut matched_modules = HashSet::new();
+        for module in &traceback_modules {
+            let parts: Vec<&str> = module.split('.').collect();
+            if let Some(found) = (0..parts.len())
+                .map(|start| parts[start..].join("."))
+                .find(|candidate| fingerprint.modules.contains(candidate))
+            {
+                if matched_modules.insert(found.clone()) {
+                    add(index, MODULE_SCORE, &found);
+                }
+            }
+        }
+
+        let name = fingerprint.name.trim().to_lowercase();
+        if name.len() >= MIN_MOD_NAME_LEN && lower_log.contains(&name) {
+            add(index, MOD_NAME_SCORE, fingerprint.name.trim());
+        }
+    }
+
+    let mut suspects: Vec<ExceptionSuspect> = evidence
+        .into_iter()
+        .map(|(index, (score, _, matched_token))| ExceptionSuspect {
+            mod_id: mods[index].id.clone(),
+            mod_name: mods[index].name.clone(),
+            matched_token,
+            score,
+        })
+        .collect();
+    suspects.sort_by(|a, b| b.score.cmp(&a.score).then(a.mod_name.cmp(&b.mod_name)));
+    suspects
+}
+
+/// Paths of the `File "..."` lines of Python tracebacks
+fn traceback_paths(log: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut rest = log;
+    while let Some(start) = rest.find("File \"") {
+        rest = &rest[start + 6..];
+        let Some(end) = rest.find('"') else {
+            break;
+        };
+        paths.push(rest[..end].to_string());
+        rest = &rest[end..];
+    }
+    paths
+}
+
+/// Distinct lowercase .ts4script / .package file names mentioned anywhere in the log
+fn file_name_tokens(log: &str) -> HashSet<String> {
+    log.split(|c: char| {
+        c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>' | '\\' | '/' | '(' | ')' | ',')
+    })
+    .map(|token| token.to_lowercase())
+    .filter(|token| token.ends_with(".ts4script") || token.ends_with(".package"))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::InstalledFile;
+    use crate::test_support::write_zip;
+    use tempfile::tempdir;
+
+    const LAST_EXCEPTION: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<root>
+<report><version>2</version><sku>ACCOUNT</sku>
+<desyncdata>[manus] Error invoking command (Exception: 'NoneType' object has no attribute 'sim_info')
+Traceback (most recent call last):
+  File "T:\InGame\Gameplay\Scripts\Server\sims4\commands.py", line 412, in invoke_command
+  File "Mods\Funky Moods\funkymoods\moods\tracker.py", line 88, in on_tick
+  File "funkymoods/moods/tracker.py", line 40, in _update
+AttributeError: 'NoneType' object has no attribute 'sim_info'
+Loaded CAS part from Clothing_Pack.package
+</desyncdata></report>
+</root>"#;
+
+    fn install(id: &str, name: &str, files: Vec<String>) -> InstallRecord {
+        InstallRecord {
+            id: id.to_string(),
+            name: name.to_string(),
+            version: None,
+            installed_at: 1,
+            files: files
+                .into_iter()
+                .map(|path| InstalledFile { path, hash: None })
+                .collect(),
+            tags: Vec::new(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn blames_installed_script_named_in_traceback() {
+        let dir = tempdir().unwrap();
+        let script = write_zip(
+            &dir.path().join("FunkyMoods.ts4script"),
+            &[
+                ("funkymoods/__init__.pyc", b""),
+                ("funkymoods/moods/tracker.pyc", b""),
+            ],
+        );
+        let mods: Vec<ModFingerprint> = [
+            install("moods", "Funky Moods", vec![script]),
+            install(
+                "clothes",
+                "Clothing Pack",
+                vec![dir
+                    .path()
+                    .join("Clothing_Pack.package")
+                    .to_string_lossy()
+                    .to_string()],
+            ),
+            install(
+                "mccc",
+                "MC Command Center",
+                vec![dir
+                    .path()
+                    .join("mc_cmd_center.ts4script")
+                    .to_string_lossy()
+                    .to_string()],
+            ),
+        ]
+        .iter()
+        .map(fingerprint)
+        .collect();
+
+        let suspects = correlate(LAST_EXCEPTION, &mods);
+
+        assert_eq!(
+            suspects,
+            vec![
+                ExceptionSuspect {
+                    mod_id: "moods".to_string(),
+                    mod_name: "Funky Moods".to_string(),
+                    matched_token: "funkymoods.moods.tracker".to_string(),
+                    score: MODULE_SCORE + MOD_NAME_SCORE,
+                },
+                ExceptionSuspect {
+                    mod_id: "clothes".to_string(),
+                    mod_name: "Clothing Pack".to_string(),
+                    matched_token: "clothing_pack.package".to_string(),
+                    score: PACKAGE_FILE_SCORE,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_game_script_paths() {
+        let mods = vec![ModFingerprint {
+            id: "override".to_string(),
+            name: "Cmd".to_string(),
+            modules: HashSet::from(["sims4.commands".to_string()]),
+            ..Default::default()
+        }];
+
+        assert!(correlate(LAST_EXCEPTION, &mods).is_empty());
+    }
+}
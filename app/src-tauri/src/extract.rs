@@ -0,0 +1,1377 @@
+use crate::archive::safe_relative_path;
+use crate::calculate_file_hash;
+use crate::filetype;
+use crate::replace::temp_path_for;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{create_dir_all, File};
+use std::io::{copy, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tauri::Emitter;
+use zip::ZipArchive;
+
+/// File names Windows reserves for devices, with or without an extension
+const RESERVED_WINDOWS_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Error prefix returned when an archive doesn't match its expected hash, for the frontend to match on
+pub const CHECKSUM_MISMATCH_ERROR: &str = "Checksum mismatch";
+
+/// Levels of zip-in-zip unwrapped by `extract_nested`, deeper archives are left as-is
+/// Bounds the work a zip quine (an archive containing itself) can cause
+const MAX_NESTED_DEPTH: usize = 3;
+
+/// Characters that are illegal in Windows file names
+const ILLEGAL_FILENAME_CHARS: [char; 7] = ['<', '>', ':', '"', '|', '?', '*'];
+
+/// What to do with archive entries whose names can't be written on Windows
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum InvalidNamePolicy {
+    /// Replace illegal characters / rename reserved names and report the remap
+    #[default]
+    Sanitize,
+    /// Refuse to extract anything and list the offending entries
+    Reject,
+}
+
+/// What to do with file entries whose paths differ only by case (`Mod.package` / `mod.package`)
+/// Windows and the game treat them as one file, case-sensitive filesystems (Proton) keep both
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CaseCollisionPolicy {
+    /// Write the later entries under a numbered name, e.g. `mod (2).package`
+    #[default]
+    Rename,
+    /// Keep only the first entry, like a case-insensitive filesystem would
+    Merge,
+    /// Write every entry as-is and only report the collision
+    Report,
+}
+
+/// Optional behaviour of `extract_zip`, all fields default when omitted
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct ExtractOptions {
+    pub invalid_names: InvalidNamePolicy,
+    /// Skip entries already present with the right size and CRC (resuming an interrupted run)
+    pub resume: bool,
+    pub case_collisions: CaseCollisionPolicy,
+    /// Write through a temporary file, then re-read every written file and compare it with the
+    /// archive's CRC-32 to catch corruption during the write
+    pub verify_after: bool,
+    /// Write files that fail verification again (verify_after only)
+    pub repair: bool,
+    /// Extract zips found inside the archive in place of the zip file, recursively
+    pub extract_nested: bool,
+    /// fsync every written file, then the folders that received them, so the install survives a
+    /// power loss or crash; off by default as it makes extraction several times slower on HDDs
+    /// and noticeably slower on SSDs (each file waits for the disk instead of the page cache)
+    pub durable: bool,
+}
+
+/// Archive entry written under a different name than the one stored in the archive
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RenamedEntry {
+    pub original: String,
+    pub sanitized: String,
+}
+
+/// File entry whose path matches an earlier entry when compared case-insensitively
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CaseCollision {
+    /// Path of the earlier entry, which is always written
+    pub kept: String,
+    /// Path of the colliding entry
+    pub colliding: String,
+    /// Where the colliding entry was written, None when it was skipped (merge)
+    pub written_as: Option<String>,
+}
+
+/// Zip found inside the archive and extracted in its place
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NestedArchive {
+    /// Path of the inner zip relative to the destination
+    pub archive: String,
+    /// 1 for a zip directly in the downloaded archive, 2 for a zip inside that one...
+    pub depth: usize,
+    pub files_written: usize,
+}
+
+/// Summary of an extraction
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ExtractionReport {
+    /// Number of files written
+    pub files_written: usize,
+    /// Files left untouched because they were already extracted (resume only)
+    pub files_skipped: usize,
+    /// Entries renamed to be valid on Windows, numbered when that name was already taken
+    pub renamed: Vec<RenamedEntry>,
+    /// Entries differing only by case from an earlier one
+    pub case_collisions: Vec<CaseCollision>,
+    /// Written files whose content doesn't match the archive (verify_after only)
+    /// With repair, only the files that were still wrong after being written again
+    pub verification_failures: Vec<String>,
+    /// Files that failed verification and matched once written again
+    pub repaired: Vec<String>,
+    /// Inner zips that were unwrapped (extract_nested only)
+    pub nested_unwrapped: Vec<NestedArchive>,
+    /// Inner zips left untouched because they are nested deeper than the limit
+    pub nested_too_deep: Vec<String>,
+}
+
+/// Expected files that are gone or emptied since they were written
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct FilePresenceReport {
+    pub checked: usize,
+    /// Files that no longer exist
+    pub missing: Vec<String>,
+    /// Files that exist but are empty (some antivirus truncate instead of deleting)
+    pub empty: Vec<String>,
+}
+
+/// Progress payload emitted on `extract://progress`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ExtractProgress {
+    pub zip_path: String,
+    /// Files written so far
+    pub written: usize,
+    /// Files this run has to write (already extracted files are not counted when resuming)
+    pub total: usize,
+}
+
+/// One archive of an `extract_archives` batch
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExtractJob {
+    pub zip_path: String,
+    pub dest_dir: String,
+    #[serde(default)]
+    pub options: ExtractOptions,
+}
+
+/// Outcome of one job, a failed job doesn't stop the others
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExtractJobResult {
+    pub zip_path: String,
+    pub report: Option<ExtractionReport>,
+    pub error: Option<String>,
+}
+
+/// Progress payload emitted on `extract-archives://progress`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ExtractArchivesProgress {
+    /// Index of the job in the batch
+    pub job: usize,
+    pub zip_path: String,
+    /// Files written so far by this job
+    pub written: usize,
+    /// Files this job has to write
+    pub total: usize,
+    /// Jobs finished (successfully or not)
+    pub jobs_done: usize,
+    pub jobs_total: usize,
+}
+
+/// Extract a ZIP archive into `dest_dir`
+/// Entry names are validated against Windows naming rules before anything is written
+/// Emits `extract://progress` as files are written
+#[tauri::command(async)]
+pub fn extract_zip(
+    app_handle: tauri::AppHandle,
+    zip_path: String,
+    dest_dir: String,
+    options: Option<ExtractOptions>,
+) -> Result<ExtractionReport, String> {
+    extract_archive(
+        Path::new(&zip_path),
+        Path::new(&dest_dir),
+        &options.unwrap_or_default(),
+        |written, total| {
+            let _ = app_handle.emit(
+                "extract://progress",
+                ExtractProgress {
+                    zip_path: zip_path.clone(),
+                    written,
+                    total,
+                },
+            );
+        },
+    )
+}
+
+/// Extract several archives at once, at most `max_parallel` at a time
+/// Every job writes its files on the global pool, so the batch never uses more threads than a
+/// single extraction; emits `extract-archives://progress` per job with the batch totals
+#[tauri::command(async)]
+pub fn extract_archives(
+    app_handle: tauri::AppHandle,
+    jobs: Vec<ExtractJob>,
+    max_parallel: usize,
+) -> Vec<ExtractJobResult> {
+    extract_batch(&jobs, max_parallel, |progress| {
+        let _ = app_handle.emit("extract-archives://progress", progress);
+    })
+}
+
+pub fn extract_batch(
+    jobs: &[ExtractJob],
+    max_parallel: usize,
+    on_progress: impl Fn(ExtractArchivesProgress) + Sync,
+) -> Vec<ExtractJobResult> {
+    let jobs_total = jobs.len();
+    let next_job = AtomicUsize::new(0);
+    let jobs_done = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<ExtractJobResult>>> =
+        Mutex::new(jobs.iter().map(|_| None).collect());
+
+    // Each lane takes the next pending job until none are left
+    let lanes = max_parallel.clamp(1, jobs_total.max(1));
+    (0..lanes).into_par_iter().for_each(|_| loop {
+        let index = next_job.fetch_add(1, Ordering::SeqCst);
+        let Some(job) = jobs.get(index) else {
+            break;
+        };
+        let progress = |written, total| {
+            on_progress(ExtractArchivesProgress {
+                job: index,
+                zip_path: job.zip_path.clone(),
+                written,
+                total,
+                jobs_done: jobs_done.load(Ordering::SeqCst),
+                jobs_total,
+            })
+        };
+
+        let outcome = extract_archive(
+            Path::new(&job.zip_path),
+            Path::new(&job.dest_dir),
+            &job.options,
+            progress,
+        );
+        jobs_done.fetch_add(1, Ordering::SeqCst);
+        let (written, total) = match &outcome {
+            Ok(report) => (report.files_written, report.files_written),
+            Err(_) => (0, 0),
+        };
+        progress(written, total);
+
+        results.lock().unwrap()[index] = Some(match outcome {
+            Ok(report) => ExtractJobResult {
+                zip_path: job.zip_path.clone(),
+                report: Some(report),
+                error: None,
+            },
+            Err(e) => ExtractJobResult {
+                zip_path: job.zip_path.clone(),
+                report: None,
+                error: Some(e),
+            },
+        });
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Check the archive's SHA-256 against the one published by the download source, then extract it
+/// Nothing is written when the hash differs
+#[tauri::command(async)]
+pub fn verify_then_extract(
+    app_handle: tauri::AppHandle,
+    zip_path: String,
+    expected_hash: String,
+    dest_dir: String,
+    options: Option<ExtractOptions>,
+) -> Result<ExtractionReport, String> {
+    verify_archive_hash(Path::new(&zip_path), &expected_hash)?;
+    extract_zip(app_handle, zip_path, dest_dir, options)
+}
+
+/// Fail with `CHECKSUM_MISMATCH_ERROR` unless the file hashes to `expected_hash` (hex, any case)
+pub fn verify_archive_hash(zip_path: &Path, expected_hash: &str) -> Result<(), String> {
+    let actual = calculate_file_hash(zip_path.to_string_lossy().to_string())?;
+    let expected = expected_hash.trim();
+
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{}: expected {}, got {}",
+            CHECKSUM_MISMATCH_ERROR, expected, actual
+        ))
+    }
+}
+
+/// Confirm that freshly written files are still there and non-empty
+/// Antivirus software may quarantine `.ts4script`/`.package` files right after they are written,
+/// anything reported here is likely to have been removed by it
+#[tauri::command(async)]
+pub fn check_files_present(paths: Vec<String>) -> FilePresenceReport {
+    let mut report = FilePresenceReport {
+        checked: paths.len(),
+        ..Default::default()
+    };
+
+    for path in paths {
+        match std::fs::metadata(&path) {
+            Ok(metadata) if metadata.is_file() && metadata.len() == 0 => report.empty.push(path),
+            Ok(_) => {}
+            Err(_) => report.missing.push(path),
+        }
+    }
+
+    report
+}
+
+pub fn extract_archive(
+    zip_path: &Path,
+    dest_dir: &Path,
+    options: &ExtractOptions,
+    on_progress: impl Fn(usize, usize) + Sync,
+) -> Result<ExtractionReport, String> {
+    let (mut report, written) = extract_entries(zip_path, dest_dir, options, &on_progress)?;
+    if options.extract_nested {
+        unwrap_nested(dest_dir, written, options, 1, &mut report)?;
+    }
+    Ok(report)
+}
+
+/// Extract inner zips next to where they were written, then remove them, until `MAX_NESTED_DEPTH`
+/// `written` holds the files just written, relative to `dest_dir`
+fn unwrap_nested(
+    dest_dir: &Path,
+    written: Vec<PathBuf>,
+    options: &ExtractOptions,
+    depth: usize,
+    report: &mut ExtractionReport,
+) -> Result<(), String> {
+    for relative in written {
+        let path = dest_dir.join(&relative);
+        if !is_inner_zip(&path) {
+            continue;
+        }
+        let name = relative.to_string_lossy().replace('\\', "/");
+        if depth > MAX_NESTED_DEPTH {
+            report.nested_too_deep.push(name);
+            continue;
+        }
+
+        let parent = relative.parent().map(Path::to_path_buf).unwrap_or_default();
+        let (inner, inner_written) =
+            extract_entries(&path, &dest_dir.join(&parent), options, &|_, _| {})
+                .map_err(|e| format!("Failed to extract nested archive {}: {}", name, e))?;
+        std::fs::remove_file(&path)
+            .map_err(|e| format!("Failed to remove nested archive {}: {}", name, e))?;
+
+        report.files_written += inner.files_written;
+        report.files_skipped += inner.files_skipped;
+        report.renamed.extend(inner.renamed);
+        report.case_collisions.extend(inner.case_collisions);
+        report
+            .verification_failures
+            .extend(inner.verification_failures);
+        report.repaired.extend(inner.repaired);
+        report.nested_unwrapped.push(NestedArchive {
+            archive: name,
+            depth,
+            files_written: inner.files_written,
+        });
+
+        let inner_written = inner_written.into_iter().map(|p| parent.join(p)).collect();
+        unwrap_nested(dest_dir, inner_written, options, depth + 1, report)?;
+    }
+    Ok(())
+}
+
+/// Zip by content, .ts4script files are zips by design and stay as they are
+fn is_inner_zip(path: &Path) -> bool {
+    let is_script = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("ts4script"))
+        .unwrap_or(false);
+    if is_script {
+        return false;
+    }
+
+    let mut header = Vec::with_capacity(filetype::ARCHIVE_MAGIC_LEN);
+    File::open(path)
+        .and_then(|file| {
+            file.take(filetype::ARCHIVE_MAGIC_LEN as u64)
+                .read_to_end(&mut header)
+        })
+        .is_ok()
+        && filetype::archive_format(&header) == Some("zip")
+}
+
+/// Extract one archive, returning the report and the files written (relative to `dest_dir`)
+fn extract_entries(
+    zip_path: &Path,
+    dest_dir: &Path,
+    options: &ExtractOptions,
+    on_progress: &(impl Fn(usize, usize) + Sync),
+) -> Result<(ExtractionReport, Vec<PathBuf>), String> {
+    let file = File::open(zip_path).map_err(|e| e.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
+
+    // Metadata pass: resolve every output path before touching the disk
+    let mut report = ExtractionReport::default();
+    let mut invalid_names: Vec<String> = Vec::new();
+    let mut entries: Vec<(usize, PathBuf, bool)> = Vec::new();
+    // Lowercased output path -> output path of the first file entry using it
+    let mut seen_paths: HashMap<String, PathBuf> = HashMap::new();
+    // Lowercased output paths produced by renaming an invalid name
+    let mut sanitized_paths: HashSet<String> = HashSet::new();
+
+    for i in 0..archive.len() {
+        let file = archive.by_index_raw(i).map_err(|e| e.to_string())?;
+        let name = file.name().to_string();
+
+        let Some(relative) = safe_relative_path(&name) else {
+            invalid_names.push(format!("{} (path escapes the destination folder)", name));
+            continue;
+        };
+
+        let (output, problems) = sanitize_relative_path(&relative);
+        let was_sanitized = !problems.is_empty();
+        if was_sanitized && options.invalid_names == InvalidNamePolicy::Reject {
+            invalid_names.push(format!("{} ({})", name, problems.join(", ")));
+            continue;
+        }
+        let rename = |report: &mut ExtractionReport, path: &Path| {
+            report.renamed.push(RenamedEntry {
+                original: name.clone(),
+                sanitized: path.to_string_lossy().replace('\\', "/"),
+            })
+        };
+
+        if file.is_dir() {
+            if was_sanitized {
+                rename(&mut report, &output);
+            }
+            entries.push((i, output, true));
+            continue;
+        }
+
+        let Some(kept) = seen_paths.get(&case_key(&output)).cloned() else {
+            if was_sanitized {
+                rename(&mut report, &output);
+                sanitized_paths.insert(case_key(&output));
+            }
+            seen_paths.insert(case_key(&output), output.clone());
+            entries.push((i, output, false));
+            continue;
+        };
+
+        // `a?.txt` and `a_.txt` are different files that sanitize to the same name, always keep
+        // both whatever the case collision policy
+        if was_sanitized || sanitized_paths.contains(&case_key(&output)) {
+            let taken: HashSet<String> = seen_paths.keys().cloned().collect();
+            let numbered = numbered_path(&output, &taken);
+            rename(&mut report, &numbered);
+            seen_paths.insert(case_key(&numbered), numbered.clone());
+            sanitized_paths.insert(case_key(&numbered));
+            entries.push((i, numbered, false));
+            continue;
+        }
+
+        let written_as = match options.case_collisions {
+            CaseCollisionPolicy::Merge => None,
+            CaseCollisionPolicy::Report => Some(output.clone()),
+            CaseCollisionPolicy::Rename => {
+                let taken: HashSet<String> = seen_paths.keys().cloned().collect();
+                let renamed = numbered_path(&output, &taken);
+                seen_paths.insert(case_key(&renamed), renamed.clone());
+                Some(renamed)
+            }
+        };
+        report.case_collisions.push(CaseCollision {
+            kept: kept.to_string_lossy().replace('\\', "/"),
+            colliding: output.to_string_lossy().replace('\\', "/"),
+            written_as: written_as
+                .as_ref()
+                .map(|path| path.to_string_lossy().replace('\\', "/")),
+        });
+        if let Some(path) = written_as {
+            entries.push((i, path, false));
+        }
+    }
+
+    if !invalid_names.is_empty() {
+        return Err(format!(
+            "Invalid file names in archive: {}",
+            invalid_names.join("; ")
+        ));
+    }
+
+    // First pass: collect all file content (must be sequential due to ZipArchive)
+    let mut files_to_create: Vec<(PathBuf, Vec<u8>, u32)> = Vec::new();
+    let mut dirs_to_create: Vec<PathBuf> = Vec::new();
+
+    for (index, output, is_dir) in entries {
+        if is_dir {
+            dirs_to_create.push(output);
+        } else {
+            if options.resume {
+                let entry = archive.by_index_raw(index).map_err(|e| e.to_string())?;
+                if is_already_extracted(&dest_dir.join(&output), entry.size(), entry.crc32()) {
+                    report.files_skipped += 1;
+                    continue;
+                }
+            }
+
+            // Read file content into memory
+            let mut file = archive.by_index(index).map_err(|e| e.to_string())?;
+            let mut buffer = Vec::new();
+            copy(&mut file, &mut buffer).map_err(|e| e.to_string())?;
+            let crc32 = file.crc32();
+            files_to_create.push((output, buffer, crc32));
+        }
+    }
+
+    // Create all directories first (sequential to avoid race conditions)
+    for dir_name in &dirs_to_create {
+        create_dir_all(dest_dir.join(dir_name)).map_err(|e| e.to_string())?;
+    }
+
+    // Create parent directories for all files (sequential)
+    for (file_name, _, _) in &files_to_create {
+        if let Some(p) = dest_dir.join(file_name).parent() {
+            create_dir_all(p).map_err(|e| e.to_string())?;
+        }
+    }
+
+    // Second pass: write all files in parallel with rayon
+    let error_mutex = Mutex::new(Option::<String>::None);
+    let total = files_to_create.len();
+    let written = AtomicUsize::new(0);
+    on_progress(0, total);
+
+    files_to_create
+        .par_iter()
+        .for_each(|(file_name, content, _)| {
+            if error_mutex.lock().unwrap().is_some() {
+                return;
+            }
+
+            let outpath = dest_dir.join(file_name);
+            let result = if options.verify_after {
+                write_through_temp(&outpath, content, options.durable)
+            } else {
+                write_file(&outpath, content, options.durable).map_err(|e| e.to_string())
+            };
+            if let Err(e) = result {
+                *error_mutex.lock().unwrap() =
+                    Some(format!("Failed to write {}: {}", file_name.display(), e));
+                return;
+            }
+            on_progress(written.fetch_add(1, Ordering::Relaxed) + 1, total);
+        });
+
+    // Check for errors from parallel operations
+    if let Some(e) = error_mutex.into_inner().unwrap() {
+        return Err(e);
+    }
+
+    report.files_written = files_to_create.len();
+    if options.verify_after {
+        verify_written(dest_dir, &files_to_create, options, &mut report);
+    }
+    if options.durable {
+        let mut folders: Vec<PathBuf> = dirs_to_create
+            .iter()
+            .chain(files_to_create.iter().map(|(file_name, _, _)| file_name))
+            .filter_map(|relative| dest_dir.join(relative).parent().map(Path::to_path_buf))
+            .chain(dest_dir.parent().map(Path::to_path_buf))
+            .collect();
+        folders.sort();
+        folders.dedup();
+        for folder in folders {
+            sync_directory(&folder)
+                .map_err(|e| format!("Failed to sync {}: {}", folder.display(), e))?;
+        }
+    }
+    let written = files_to_create
+        .into_iter()
+        .map(|(file_name, _, _)| file_name)
+        .collect();
+    Ok((report, written))
+}
+
+/// Write `content` to `path`, waiting for it to reach the disk when `durable`
+fn write_file(path: &Path, content: &[u8], durable: bool) -> std::io::Result<()> {
+    if !durable {
+        return std::fs::write(path, content);
+    }
+    let mut file = File::create(path)?;
+    file.write_all(content)?;
+    file.sync_all()
+}
+
+/// fsync a folder so the entries created in it are persisted
+/// Windows can't open folders as files, NTFS journals the entries with the file metadata instead
+#[cfg(unix)]
+fn sync_directory(path: &Path) -> std::io::Result<()> {
+    File::open(path)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_directory(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Write `content` next to `path` and rename it into place, so `path` never holds a partial file
+fn write_through_temp(path: &Path, content: &[u8], durable: bool) -> Result<(), String> {
+    let temp = temp_path_for(path)?;
+    let result = write_file(&temp, content, durable).and_then(|_| std::fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result.map_err(|e| e.to_string())
+}
+
+/// Re-read the written files and compare them with the archive, writing mismatches again on repair
+fn verify_written(
+    dest_dir: &Path,
+    files: &[(PathBuf, Vec<u8>, u32)],
+    options: &ExtractOptions,
+    report: &mut ExtractionReport,
+) {
+    let mismatched: Vec<&(PathBuf, Vec<u8>, u32)> = files
+        .par_iter()
+        .filter(|(file_name, content, crc32)| {
+            !is_already_extracted(&dest_dir.join(file_name), content.len() as u64, *crc32)
+        })
+        .collect();
+
+    for (file_name, content, crc32) in mismatched {
+        let outpath = dest_dir.join(file_name);
+        let name = file_name.to_string_lossy().replace('\\', "/");
+
+        if options.repair
+            && write_through_temp(&outpath, content, options.durable).is_ok()
+            && is_already_extracted(&outpath, content.len() as u64, *crc32)
+        {
+            report.repaired.push(name);
+        } else {
+            report.verification_failures.push(name);
+        }
+    }
+}
+
+/// Key under which a case-insensitive filesystem would store `path`
+fn case_key(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/").to_lowercase()
+}
+
+/// First `name (n).ext` variant of `path` not already in `taken` (compared case-insensitively)
+fn numbered_path(path: &Path, taken: &HashSet<String>) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+
+    (2..)
+        .map(|n| path.with_file_name(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| !taken.contains(&case_key(candidate)))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Whether `path` already holds the entry's content, judged by size then CRC-32
+/// A file cut short by an interrupted run fails the size check without being read
+fn is_already_extracted(path: &Path, size: u64, crc32: u32) -> bool {
+    match std::fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() && metadata.len() == size => {}
+        _ => return false,
+    }
+
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => hasher.update(&buffer[..n]),
+            Err(_) => return false,
+        }
+    }
+    hasher.finalize() == crc32
+}
+
+/// Make every component of a relative path valid on Windows
+/// Returns the sanitized path and the problems that were fixed (empty if the name was valid)
+fn sanitize_relative_path(relative: &Path) -> (PathBuf, Vec<String>) {
+    let mut sanitized = PathBuf::new();
+    let mut problems = Vec::new();
+
+    for component in relative.iter() {
+        let component = component.to_string_lossy();
+        let (fixed, component_problems) = sanitize_component(&component);
+        sanitized.push(fixed);
+        problems.extend(component_problems);
+    }
+
+    (sanitized, problems)
+}
+
+/// Validate a single file or folder name against Windows rules, fixing it if needed
+fn sanitize_component(component: &str) -> (String, Vec<String>) {
+    let mut problems = Vec::new();
+
+    let mut fixed: String = component
+        .chars()
+        .map(|c| {
+            if ILLEGAL_FILENAME_CHARS.contains(&c) || c.is_control() {
+                let problem = if c.is_control() {
+                    "contains a control character".to_string()
+                } else {
+                    format!("contains illegal character '{}'", c)
+                };
+                if !problems.contains(&problem) {
+                    problems.push(problem);
+                }
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    // Windows silently drops trailing dots and spaces, which breaks the path we expect
+    let trimmed_len = fixed.trim_end_matches(['.', ' ']).len();
+    if trimmed_len != fixed.len() {
+        problems.push("ends with a dot or space".to_string());
+        fixed.truncate(trimmed_len);
+        if fixed.is_empty() {
+            fixed.push('_');
+        }
+    }
+
+    // "CON", "con.txt" and "Nul.package" are all device names
+    let stem = fixed.split('.').next().unwrap_or("").trim_end();
+    if RESERVED_WINDOWS_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+    {
+        problems.push(format!("'{}' is a reserved name on Windows", stem));
+        fixed.insert(stem.len(), '_');
+    }
+
+    (fixed, problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::write_zip;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn reserved_name_zip(dir: &Path) -> String {
+        write_zip(
+            &dir.join("mod.zip"),
+            &[
+                ("Mod/", b""),
+                ("Mod/valid.package", b"DBPF"),
+                ("Mod/CON", b"device"),
+                ("Mod/nul.package", b"DBPF"),
+                ("Mod/what?.txt", b"readme"),
+                ("LPT1/notes.txt ", b"notes"),
+            ],
+        )
+    }
+
+    #[test]
+    fn sanitizes_reserved_and_illegal_names() {
+        let dir = tempdir().unwrap();
+        let zip_path = reserved_name_zip(dir.path());
+        let dest = dir.path().join("out");
+
+        let report = extract_archive(
+            Path::new(&zip_path),
+            &dest,
+            &ExtractOptions::default(),
+            |_, _| {},
+        )
+        .unwrap();
+
+        assert_eq!(report.files_written, 5);
+        let sanitized: Vec<&str> = report
+            .renamed
+            .iter()
+            .map(|r| r.sanitized.as_str())
+            .collect();
+        assert_eq!(
+            sanitized,
+            vec![
+                "Mod/CON_",
+                "Mod/nul_.package",
+                "Mod/what_.txt",
+                "LPT1_/notes.txt"
+            ]
+        );
+        assert_eq!(fs::read(dest.join("Mod/CON_")).unwrap(), b"device");
+        assert!(dest.join("Mod/valid.package").exists());
+        assert!(dest.join("LPT1_/notes.txt").exists());
+    }
+
+    #[test]
+    fn sanitizes_colons_in_entry_names() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("notes.zip"),
+            &[("Mods/Note: read me.txt", b"thanks")],
+        );
+        let dest = dir.path().join("out");
+
+        let report = extract_archive(
+            Path::new(&zip_path),
+            &dest,
+            &ExtractOptions::default(),
+            |_, _| {},
+        )
+        .unwrap();
+
+        assert_eq!(report.files_written, 1);
+        assert_eq!(report.renamed[0].sanitized, "Mods/Note_ read me.txt");
+        assert_eq!(
+            fs::read(dest.join("Mods/Note_ read me.txt")).unwrap(),
+            b"thanks"
+        );
+    }
+
+    #[test]
+    fn numbers_entries_colliding_once_sanitized() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("notes.zip"),
+            &[
+                ("Mod/a?.txt", b"question"),
+                ("Mod/a_.txt", b"underscore"),
+                ("Mod/b_.txt", b"first"),
+                ("Mod/b*.txt", b"second"),
+            ],
+        );
+        let dest = dir.path().join("out");
+
+        for case_collisions in [CaseCollisionPolicy::Report, CaseCollisionPolicy::Merge] {
+            let options = ExtractOptions {
+                case_collisions,
+                ..Default::default()
+            };
+            let report = extract_archive(Path::new(&zip_path), &dest, &options, |_, _| {}).unwrap();
+
+            assert_eq!(report.files_written, 4);
+            assert!(report.case_collisions.is_empty());
+            let renamed: Vec<(&str, &str)> = report
+                .renamed
+                .iter()
+                .map(|r| (r.original.as_str(), r.sanitized.as_str()))
+                .collect();
+            assert_eq!(
+                renamed,
+                vec![
+                    ("Mod/a?.txt", "Mod/a_.txt"),
+                    ("Mod/a_.txt", "Mod/a_ (2).txt"),
+                    ("Mod/b*.txt", "Mod/b_ (2).txt"),
+                ]
+            );
+            assert_eq!(fs::read(dest.join("Mod/a_.txt")).unwrap(), b"question");
+            assert_eq!(
+                fs::read(dest.join("Mod/a_ (2).txt")).unwrap(),
+                b"underscore"
+            );
+            assert_eq!(fs::read(dest.join("Mod/b_.txt")).unwrap(), b"first");
+            assert_eq!(fs::read(dest.join("Mod/b_ (2).txt")).unwrap(), b"second");
+        }
+    }
+
+    #[test]
+    fn rejects_reserved_names_without_writing_anything() {
+        let dir = tempdir().unwrap();
+        let zip_path = reserved_name_zip(dir.path());
+        let dest = dir.path().join("out");
+        let options = ExtractOptions {
+            invalid_names: InvalidNamePolicy::Reject,
+            ..Default::default()
+        };
+
+        let error = extract_archive(Path::new(&zip_path), &dest, &options, |_, _| {}).unwrap_err();
+
+        assert!(error.contains("Mod/CON ('CON' is a reserved name on Windows)"));
+        assert!(error.contains("Mod/what?.txt (contains illegal character '?')"));
+        assert!(error.contains("LPT1/notes.txt  ("));
+        assert!(!error.contains("valid.package"));
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn refuses_entries_escaping_the_destination() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(&dir.path().join("slip.zip"), &[("../evil.package", b"x")]);
+
+        let error = extract_archive(
+            Path::new(&zip_path),
+            &dir.path().join("out"),
+            &ExtractOptions::default(),
+            |_, _| {},
+        )
+        .unwrap_err();
+
+        assert!(error.contains("escapes the destination"));
+        assert!(!dir.path().join("evil.package").exists());
+    }
+
+    #[test]
+    fn resume_only_writes_missing_or_damaged_files() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("pack.zip"),
+            &[
+                ("Pack/a.package", b"first package"),
+                ("Pack/b.package", b"second package"),
+                ("Pack/c.package", b"third package"),
+                ("Pack/d.package", b"fourth package"),
+            ],
+        );
+        let dest = dir.path().join("out");
+        extract_archive(
+            Path::new(&zip_path),
+            &dest,
+            &ExtractOptions::default(),
+            |_, _| {},
+        )
+        .unwrap();
+
+        // Simulate an interrupted run: one file missing, one cut short, one same-size but corrupt
+        fs::remove_file(dest.join("Pack/a.package")).unwrap();
+        fs::write(dest.join("Pack/b.package"), b"second").unwrap();
+        fs::write(dest.join("Pack/c.package"), b"third pXckage").unwrap();
+
+        let options = ExtractOptions {
+            resume: true,
+            ..Default::default()
+        };
+        let progress = Mutex::new(Vec::new());
+        let report = extract_archive(Path::new(&zip_path), &dest, &options, |written, total| {
+            progress.lock().unwrap().push((written, total))
+        })
+        .unwrap();
+
+        assert_eq!(report.files_written, 3);
+        assert_eq!(report.files_skipped, 1);
+        let mut progress = progress.into_inner().unwrap();
+        progress.sort();
+        assert_eq!(progress, vec![(0, 3), (1, 3), (2, 3), (3, 3)]);
+        for (name, content) in [
+            ("a", "first package"),
+            ("b", "second package"),
+            ("c", "third package"),
+            ("d", "fourth package"),
+        ] {
+            assert_eq!(
+                fs::read_to_string(dest.join(format!("Pack/{}.package", name))).unwrap(),
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn verify_after_detects_and_repairs_corruption_after_write() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("pack.zip"),
+            &[
+                ("Pack/a.package", b"first package"),
+                ("Pack/b.package", b"second package"),
+            ],
+        );
+
+        let extract = |repair, dest: &Path| {
+            let options = ExtractOptions {
+                verify_after: true,
+                repair,
+                ..Default::default()
+            };
+            // Both files are written once the last progress event fires, corrupt one of them
+            extract_archive(Path::new(&zip_path), dest, &options, |written, total| {
+                if written == total {
+                    fs::write(dest.join("Pack/b.package"), b"second pXckage").unwrap();
+                }
+            })
+            .unwrap()
+        };
+
+        let dest = dir.path().join("detected");
+        let report = extract(false, &dest);
+        assert_eq!(report.files_written, 2);
+        assert_eq!(report.verification_failures, vec!["Pack/b.package"]);
+        assert!(report.repaired.is_empty());
+        assert_eq!(
+            fs::read(dest.join("Pack/b.package")).unwrap(),
+            b"second pXckage"
+        );
+
+        let dest = dir.path().join("repaired");
+        let report = extract(true, &dest);
+        assert!(report.verification_failures.is_empty());
+        assert_eq!(report.repaired, vec!["Pack/b.package"]);
+        assert_eq!(
+            fs::read(dest.join("Pack/b.package")).unwrap(),
+            b"second package"
+        );
+        // Nothing left behind by the temporary writes
+        assert_eq!(
+            file_names(&dest.join("Pack")),
+            vec!["a.package", "b.package"]
+        );
+    }
+
+    #[test]
+    fn durable_extraction_syncs_files_and_folders() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("pack.zip"),
+            &[
+                ("Pack/", b""),
+                ("Pack/Empty/", b""),
+                ("Pack/a.package", b"first package"),
+                ("Pack/Sub/b.package", b"second package"),
+            ],
+        );
+        let dest = dir.path().join("durable");
+
+        for verify_after in [false, true] {
+            let options = ExtractOptions {
+                durable: true,
+                verify_after,
+                ..Default::default()
+            };
+            let report = extract_archive(Path::new(&zip_path), &dest, &options, |_, _| {}).unwrap();
+
+            assert_eq!(report.files_written, 2);
+            assert!(report.verification_failures.is_empty());
+            assert_eq!(
+                fs::read(dest.join("Pack/Sub/b.package")).unwrap(),
+                b"second package"
+            );
+            assert!(dest.join("Pack/Empty").is_dir());
+        }
+    }
+
+    #[test]
+    fn extracts_archives_concurrently_isolating_failures() {
+        let dir = tempdir().unwrap();
+        let mut jobs: Vec<ExtractJob> = ["hair", "chair", "lamp"]
+            .iter()
+            .map(|name| {
+                let entries: Vec<(String, Vec<u8>)> = (0..20)
+                    .map(|i| {
+                        (
+                            format!("{}/{}.package", name, i),
+                            name.repeat(i + 1).into_bytes(),
+                        )
+                    })
+                    .collect();
+                let entries: Vec<(&str, &[u8])> = entries
+                    .iter()
+                    .map(|(entry, content)| (entry.as_str(), content.as_slice()))
+                    .collect();
+                ExtractJob {
+                    zip_path: write_zip(&dir.path().join(format!("{}.zip", name)), &entries),
+                    dest_dir: dir.path().join("out").to_string_lossy().to_string(),
+                    options: ExtractOptions::default(),
+                }
+            })
+            .collect();
+        jobs.insert(
+            1,
+            ExtractJob {
+                zip_path: dir.path().join("missing.zip").to_string_lossy().to_string(),
+                dest_dir: dir.path().join("out").to_string_lossy().to_string(),
+                options: ExtractOptions::default(),
+            },
+        );
+
+        let events = Mutex::new(Vec::new());
+        let results = extract_batch(&jobs, 2, |progress| {
+            events.lock().unwrap().push(progress);
+        });
+
+        assert_eq!(results.len(), 4);
+        assert!(results[1].error.is_some());
+        for (index, name) in [(0, "hair"), (2, "chair"), (3, "lamp")] {
+            assert_eq!(results[index].zip_path, jobs[index].zip_path);
+            assert_eq!(results[index].report.as_ref().unwrap().files_written, 20);
+            assert_eq!(
+                fs::read(dir.path().join(format!("out/{}/19.package", name))).unwrap(),
+                name.repeat(20).into_bytes()
+            );
+        }
+        let events = events.into_inner().unwrap();
+        assert!(events.iter().all(|event| event.jobs_total == 4));
+        assert_eq!(events.iter().map(|e| e.jobs_done).max(), Some(4));
+    }
+
+    #[test]
+    fn reports_files_removed_after_extraction() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("mod.zip"),
+            &[
+                ("Mod/kept.package", b"DBPF"),
+                ("Mod/script.ts4script", b"PK"),
+                ("Mod/truncated.package", b"DBPF"),
+            ],
+        );
+        let dest = dir.path().join("out");
+        extract_archive(
+            Path::new(&zip_path),
+            &dest,
+            &ExtractOptions::default(),
+            |_, _| {},
+        )
+        .unwrap();
+
+        // What a quarantine looks like from the outside
+        fs::remove_file(dest.join("Mod/script.ts4script")).unwrap();
+        fs::write(dest.join("Mod/truncated.package"), b"").unwrap();
+
+        let expected: Vec<String> = ["kept.package", "script.ts4script", "truncated.package"]
+            .iter()
+            .map(|name| dest.join("Mod").join(name).to_string_lossy().to_string())
+            .collect();
+        let report = check_files_present(expected.clone());
+
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.missing, vec![expected[1].clone()]);
+        assert_eq!(report.empty, vec![expected[2].clone()]);
+    }
+
+    /// Wrap `entries` in `levels` zips, each one the only entry of the next as `Mod/inner.zip`
+    fn nested_zip(dir: &Path, levels: usize, entries: &[(&str, &[u8])]) -> String {
+        let mut path = write_zip(&dir.join("level0.zip"), entries);
+        for level in 1..=levels {
+            let inner = fs::read(&path).unwrap();
+            path = write_zip(
+                &dir.join(format!("level{}.zip", level)),
+                &[("Mod/inner.zip", &inner), ("readme.txt", b"hi")],
+            );
+        }
+        path
+    }
+
+    #[test]
+    fn unwraps_zip_in_zip() {
+        let dir = tempdir().unwrap();
+        let zip_path = nested_zip(
+            dir.path(),
+            1,
+            &[
+                ("hair.package", b"DBPF"),
+                ("script.ts4script", b"PK\x03\x04script"),
+            ],
+        );
+        let dest = dir.path().join("out");
+        let options = ExtractOptions {
+            extract_nested: true,
+            ..Default::default()
+        };
+
+        let report = extract_archive(Path::new(&zip_path), &dest, &options, |_, _| {}).unwrap();
+
+        assert_eq!(
+            report.nested_unwrapped,
+            vec![NestedArchive {
+                archive: "Mod/inner.zip".to_string(),
+                depth: 1,
+                files_written: 2,
+            }]
+        );
+        assert_eq!(report.files_written, 4);
+        assert!(report.nested_too_deep.is_empty());
+        assert_eq!(fs::read(dest.join("Mod/hair.package")).unwrap(), b"DBPF");
+        // The script is a zip by design and is kept
+        assert!(dest.join("Mod/script.ts4script").exists());
+        assert!(!dest.join("Mod/inner.zip").exists());
+
+        // Without the option the inner zip is written as a file
+        let plain = dir.path().join("plain");
+        extract_archive(
+            Path::new(&zip_path),
+            &plain,
+            &ExtractOptions::default(),
+            |_, _| {},
+        )
+        .unwrap();
+        assert!(plain.join("Mod/inner.zip").exists());
+    }
+
+    #[test]
+    fn stops_unwrapping_at_depth_limit() {
+        let dir = tempdir().unwrap();
+        let zip_path = nested_zip(
+            dir.path(),
+            MAX_NESTED_DEPTH + 1,
+            &[("hair.package", b"DBPF")],
+        );
+        let dest = dir.path().join("out");
+        let options = ExtractOptions {
+            extract_nested: true,
+            ..Default::default()
+        };
+
+        let report = extract_archive(Path::new(&zip_path), &dest, &options, |_, _| {}).unwrap();
+
+        let depths: Vec<usize> = report.nested_unwrapped.iter().map(|n| n.depth).collect();
+        assert_eq!(depths, vec![1, 2, 3]);
+        assert_eq!(report.nested_too_deep, vec!["Mod/Mod/Mod/Mod/inner.zip"]);
+        assert!(dest.join("Mod/Mod/Mod/Mod/inner.zip").exists());
+        assert!(!dest.join("Mod/Mod/Mod/Mod/hair.package").exists());
+    }
+
+    fn case_colliding_zip(dir: &Path) -> String {
+        write_zip(
+            &dir.join("mod.zip"),
+            &[
+                ("Mod/Hair.package", b"first"),
+                ("Mod/hair.package", b"second"),
+                ("Mod/HAIR.package", b"third"),
+                ("Mod/other.package", b"other"),
+            ],
+        )
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn resolves_case_colliding_entries() {
+        let dir = tempdir().unwrap();
+        // The policies only differ on disk where the filesystem keeps both spellings
+        fs::write(dir.path().join("probe"), b"").unwrap();
+        if dir.path().join("PROBE").exists() {
+            return;
+        }
+        let zip_path = case_colliding_zip(dir.path());
+
+        let extract = |policy, dest: &str| {
+            let options = ExtractOptions {
+                case_collisions: policy,
+                ..Default::default()
+            };
+            extract_archive(
+                Path::new(&zip_path),
+                &dir.path().join(dest),
+                &options,
+                |_, _| {},
+            )
+            .unwrap()
+        };
+
+        let report = extract(CaseCollisionPolicy::Rename, "renamed");
+        assert_eq!(report.files_written, 4);
+        assert_eq!(
+            report.case_collisions,
+            vec![
+                CaseCollision {
+                    kept: "Mod/Hair.package".to_string(),
+                    colliding: "Mod/hair.package".to_string(),
+                    written_as: Some("Mod/hair (2).package".to_string()),
+                },
+                CaseCollision {
+                    kept: "Mod/Hair.package".to_string(),
+                    colliding: "Mod/HAIR.package".to_string(),
+                    written_as: Some("Mod/HAIR (3).package".to_string()),
+                },
+            ]
+        );
+        let renamed = dir.path().join("renamed/Mod");
+        assert_eq!(
+            file_names(&renamed),
+            vec![
+                "HAIR (3).package",
+                "Hair.package",
+                "hair (2).package",
+                "other.package"
+            ]
+        );
+        assert_eq!(
+            fs::read(renamed.join("hair (2).package")).unwrap(),
+            b"second"
+        );
+
+        let report = extract(CaseCollisionPolicy::Merge, "merged");
+        assert_eq!(report.files_written, 2);
+        assert!(report
+            .case_collisions
+            .iter()
+            .all(|c| c.written_as.is_none()));
+        let merged = dir.path().join("merged/Mod");
+        assert_eq!(file_names(&merged), vec!["Hair.package", "other.package"]);
+        assert_eq!(fs::read(merged.join("Hair.package")).unwrap(), b"first");
+
+        let report = extract(CaseCollisionPolicy::Report, "reported");
+        assert_eq!(report.files_written, 4);
+        assert_eq!(report.case_collisions.len(), 2);
+        assert_eq!(
+            file_names(&dir.path().join("reported/Mod")),
+            vec![
+                "HAIR.package",
+                "Hair.package",
+                "hair.package",
+                "other.package"
+            ]
+        );
+    }
+
+    #[test]
+    fn verifies_hash_before_extracting() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(&dir.path().join("mod.zip"), &[("Mod/a.package", b"DBPF")]);
+        let hash = calculate_file_hash(zip_path.clone()).unwrap();
+        let dest = dir.path().join("out");
+
+        verify_archive_hash(Path::new(&zip_path), &format!(" {} ", hash.to_uppercase())).unwrap();
+        extract_archive(
+            Path::new(&zip_path),
+            &dest,
+            &ExtractOptions::default(),
+            |_, _| {},
+        )
+        .unwrap();
+        assert!(dest.join("Mod/a.package").exists());
+    }
+
+    #[test]
+    fn rejects_mismatching_hash() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(&dir.path().join("mod.zip"), &[("Mod/a.package", b"DBPF")]);
+        let wrong = "0".repeat(64);
+
+        let error = verify_archive_hash(Path::new(&zip_path), &wrong).unwrap_err();
+
+        assert!(error.starts_with(CHECKSUM_MISMATCH_ERROR));
+        assert!(error.contains(&wrong));
+    }
+}
@@ -0,0 +1,334 @@
+use crate::dbpf::DBPF_MAGIC;
+use crate::manifest::{self, Manifest};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+/// Only the start of the file is needed to recognise a signature
+const SNIFF_BYTES: u64 = 8192;
+
+/// Local file header, empty archive and spanned archive signatures
+const ZIP_MAGICS: [&[u8]; 3] = [b"PK\x03\x04", b"PK\x05\x06", b"PK\x07\x08"];
+
+/// Signatures of the archive formats mods get repackaged with
+const ARCHIVE_MAGICS: [(&[u8], &str); 6] = [
+    (b"PK\x03\x04", "zip"),
+    (b"PK\x05\x06", "zip"),
+    (b"Rar!\x1a\x07", "rar"),
+    (b"7z\xBC\xAF\x27\x1C", "7z"),
+    (b"\x1F\x8B", "gzip"),
+    (b"BZh", "bzip2"),
+];
+
+/// Number of leading bytes `archive_format` needs
+pub const ARCHIVE_MAGIC_LEN: usize = 6;
+
+/// What a file actually contains, regardless of its name
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct FileTypeInfo {
+    /// Lowercase extension from the file name, if any
+    pub extension: Option<String>,
+    /// MIME type recognised from the content, None when the signature is unknown
+    pub detected_mime: Option<String>,
+    /// Sims 4 package (DBPF container)
+    pub is_dbpf: bool,
+    /// ZIP container (also true for .ts4script files)
+    pub is_zip: bool,
+}
+
+/// Identify a file from its leading bytes
+/// Used to triage files the scanner could not classify from their extension
+#[tauri::command]
+pub fn detect_file_type(path: String) -> Result<FileTypeInfo, String> {
+    let path = Path::new(&path);
+    let file = File::open(path).map_err(|e| format!("Failed to open file: {}", e))?;
+
+    let mut header = Vec::new();
+    file.take(SNIFF_BYTES)
+        .read_to_end(&mut header)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+
+    Ok(sniff(extension, &header))
+}
+
+/// Name of the archive format whose signature starts `header`, if any
+pub fn archive_format(header: &[u8]) -> Option<&'static str> {
+    ARCHIVE_MAGICS
+        .iter()
+        .find(|(magic, _)| header.starts_with(magic))
+        .map(|(_, format)| *format)
+}
+
+/// A rename that gives a file the extension matching its content
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ExtensionFix {
+    pub from: String,
+    pub to: String,
+}
+
+/// Suggest the right name for a mod file whose extension does not match its content
+/// e.g. `hair.package.txt` saved by a browser, or a package downloaded as `.zip`
+/// Returns None when the extension is already right or the content is not recognised
+#[tauri::command]
+pub fn suggest_extension_fix(path: String) -> Option<ExtensionFix> {
+    let path = Path::new(&path);
+    let extension = correct_extension(path)?;
+    let target = renamed_with_extension(path, extension)?;
+
+    Some(ExtensionFix {
+        from: path.to_string_lossy().to_string(),
+        to: target.to_string_lossy().to_string(),
+    })
+}
+
+/// Rename a file to the extension matching its content and update the manifest
+#[tauri::command]
+pub fn fix_extension(app_handle: tauri::AppHandle, path: String) -> Result<ExtensionFix, String> {
+    let mut manifest = manifest::open_app_manifest(&app_handle)?;
+    apply_extension_fix(Path::new(&path), &mut manifest)
+}
+
+pub fn apply_extension_fix(path: &Path, manifest: &mut Manifest) -> Result<ExtensionFix, String> {
+    let fix = suggest_extension_fix(path.to_string_lossy().to_string()).ok_or_else(|| {
+        format!(
+            "{} already has the right extension or its content is not recognised",
+            path.display()
+        )
+    })?;
+    if Path::new(&fix.to).exists() {
+        return Err(format!("{} already exists", fix.to));
+    }
+
+    fs::rename(&fix.from, &fix.to).map_err(|e| format!("Failed to rename {}: {}", fix.from, e))?;
+    if let Err(e) = manifest.rename_paths(&[(fix.from.clone(), fix.to.clone())]) {
+        let _ = fs::rename(&fix.to, &fix.from);
+        return Err(e);
+    }
+    Ok(fix)
+}
+
+/// Extension the file should have given its content, None when it already has it
+fn correct_extension(path: &Path) -> Option<&'static str> {
+    let mut header = Vec::new();
+    File::open(path)
+        .ok()?
+        .take(ARCHIVE_MAGIC_LEN as u64)
+        .read_to_end(&mut header)
+        .ok()?;
+
+    let current = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let expected = if header.starts_with(DBPF_MAGIC) {
+        "package"
+    } else if ZIP_MAGICS.iter().any(|magic| header.starts_with(magic)) {
+        // Scripts and plain archives share the format, both names are fine
+        if current == "ts4script" || current == "zip" {
+            return None;
+        }
+        if is_script_archive(path) {
+            "ts4script"
+        } else {
+            "zip"
+        }
+    } else {
+        archive_format(&header).filter(|format| *format == "rar" || *format == "7z")?
+    };
+
+    (current != expected).then_some(expected)
+}
+
+/// Script mods are ZIPs of compiled Python
+fn is_script_archive(path: &Path) -> bool {
+    let stem_says_script = path
+        .file_stem()
+        .map(|stem| {
+            stem.to_string_lossy()
+                .to_lowercase()
+                .ends_with(".ts4script")
+        })
+        .unwrap_or(false);
+    if stem_says_script {
+        return true;
+    }
+
+    File::open(path)
+        .ok()
+        .and_then(|file| ZipArchive::new(file).ok())
+        .map(|archive| {
+            archive
+                .file_names()
+                .any(|name| name.to_lowercase().ends_with(".pyc"))
+        })
+        .unwrap_or(false)
+}
+
+/// `hair.package.txt` -> `hair.package`, `hair.txt` -> `hair.package`, `hair` -> `hair.package`
+fn renamed_with_extension(path: &Path, extension: &str) -> Option<PathBuf> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let stem = path.file_stem()?.to_string_lossy().to_string();
+    let suffix = format!(".{}", extension);
+
+    let new_name = if stem.to_lowercase().ends_with(&suffix) {
+        stem
+    } else if path.extension().is_some() {
+        format!("{}{}", stem, suffix)
+    } else {
+        format!("{}{}", name, suffix)
+    };
+    Some(path.with_file_name(new_name))
+}
+
+fn sniff(extension: Option<String>, header: &[u8]) -> FileTypeInfo {
+    let is_dbpf = header.starts_with(DBPF_MAGIC);
+    let is_zip = ZIP_MAGICS.iter().any(|magic| header.starts_with(magic));
+
+    let detected_mime = if is_dbpf {
+        Some("application/x-dbpf".to_string())
+    } else {
+        infer::get(header).map(|kind| kind.mime_type().to_string())
+    };
+
+    FileTypeInfo {
+        extension,
+        detected_mime,
+        is_dbpf,
+        is_zip,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbpf::test_support::{build_package, key};
+    use crate::manifest::{InstallRecord, InstalledFile};
+    use crate::test_support::write_zip;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn detect(path: &Path) -> FileTypeInfo {
+        detect_file_type(path.to_string_lossy().to_string()).unwrap()
+    }
+
+    #[test]
+    fn recognises_packages_and_scripts() {
+        let dir = tempdir().unwrap();
+
+        let package = dir.path().join("hair.package");
+        fs::write(&package, build_package(&[(key(1, 0, 1), b"x".to_vec())])).unwrap();
+        let info = detect(&package);
+        assert_eq!(info.extension.as_deref(), Some("package"));
+        assert_eq!(info.detected_mime.as_deref(), Some("application/x-dbpf"));
+        assert!(info.is_dbpf);
+        assert!(!info.is_zip);
+
+        let script = write_zip(&dir.path().join("mod.TS4SCRIPT"), &[("mod.pyc", b"code")]);
+        let info = detect(Path::new(&script));
+        assert_eq!(info.extension.as_deref(), Some("ts4script"));
+        assert_eq!(info.detected_mime.as_deref(), Some("application/zip"));
+        assert!(info.is_zip);
+        assert!(!info.is_dbpf);
+    }
+
+    #[test]
+    fn sees_through_misleading_extensions() {
+        let dir = tempdir().unwrap();
+
+        let png = dir.path().join("thumbnail.package");
+        fs::write(&png, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        let info = detect(&png);
+        assert_eq!(info.detected_mime.as_deref(), Some("image/png"));
+        assert!(!info.is_dbpf);
+
+        let rar = dir.path().join("mod.zip");
+        fs::write(&rar, b"Rar!\x1a\x07\x01\x00rest").unwrap();
+        let info = detect(&rar);
+        assert_eq!(info.detected_mime.as_deref(), Some("application/vnd.rar"));
+        assert!(!info.is_zip);
+    }
+
+    #[test]
+    fn renames_package_saved_as_txt() {
+        let dir = tempdir().unwrap();
+        let browser_named = dir.path().join("hair.package.txt");
+        let renamed = dir.path().join("top.txt");
+        let package = build_package(&[(key(1, 0, 1), b"x".to_vec())]);
+        fs::write(&browser_named, &package).unwrap();
+        fs::write(&renamed, &package).unwrap();
+
+        let fix = suggest_extension_fix(renamed.to_string_lossy().to_string()).unwrap();
+        assert_eq!(fix.to, dir.path().join("top.package").to_string_lossy());
+
+        let mut manifest = Manifest::open_in_memory().unwrap();
+        manifest
+            .upsert_install(&InstallRecord {
+                id: "hair".to_string(),
+                name: "Hair".to_string(),
+                version: None,
+                installed_at: 1,
+                files: vec![InstalledFile {
+                    path: browser_named.to_string_lossy().to_string(),
+                    hash: Some("abc".to_string()),
+                }],
+                tags: Vec::new(),
+                note: None,
+            })
+            .unwrap();
+
+        let fix = apply_extension_fix(&browser_named, &mut manifest).unwrap();
+
+        let fixed = dir.path().join("hair.package");
+        assert_eq!(fix.to, fixed.to_string_lossy());
+        assert!(fixed.is_file());
+        assert!(!browser_named.exists());
+        assert_eq!(
+            manifest.recorded_hash(&fixed.to_string_lossy()).unwrap(),
+            Some("abc".to_string())
+        );
+    }
+
+    #[test]
+    fn correctly_named_files_need_no_fix() {
+        let dir = tempdir().unwrap();
+        let package = dir.path().join("hair.package");
+        fs::write(&package, build_package(&[(key(1, 0, 1), b"x".to_vec())])).unwrap();
+        let script = write_zip(&dir.path().join("mod.zip"), &[("mod.pyc", b"code")]);
+        let notes = dir.path().join("notes.txt");
+        fs::write(&notes, b"just some notes").unwrap();
+
+        assert_eq!(
+            suggest_extension_fix(package.to_string_lossy().to_string()),
+            None
+        );
+        assert_eq!(suggest_extension_fix(script), None);
+        assert_eq!(
+            suggest_extension_fix(notes.to_string_lossy().to_string()),
+            None
+        );
+
+        let mut manifest = Manifest::open_in_memory().unwrap();
+        assert!(apply_extension_fix(&package, &mut manifest).is_err());
+    }
+
+    #[test]
+    fn unknown_content_has_no_mime() {
+        let dir = tempdir().unwrap();
+        let text = dir.path().join("readme");
+        fs::write(&text, b"just some notes").unwrap();
+
+        let info = detect(&text);
+        assert_eq!(info.extension, None);
+        assert_eq!(info.detected_mime, None);
+        assert!(!info.is_dbpf && !info.is_zip);
+
+        assert!(detect_file_type("missing.bin".to_string()).is_err());
+    }
+}
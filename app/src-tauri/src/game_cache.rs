@@ -0,0 +1,148 @@
+use crate::manifest::remove_path;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+
+/// Cache entries of the game's user folder that are rebuilt on the next launch
+/// Anything else in that folder (Saves, Mods, Tray, options) is never touched
+const CACHE_ENTRIES: [&str; 3] = [
+    "localthumbcache.package",
+    "avatarcache.package",
+    "onlinethumbnailcache",
+];
+
+/// Windows sharing / lock violation, returned when the game has the file open
+const ERROR_SHARING_VIOLATION: i32 = 32;
+const ERROR_LOCK_VIOLATION: i32 = 33;
+
+/// Delete the game's thumbnail and avatar caches (to the trash), returning the removed paths
+/// Refuses to run while the game has the cache files open
+#[tauri::command(async)]
+pub fn clear_game_cache(config_dir: String) -> Result<Vec<String>, String> {
+    clear_cache(Path::new(&config_dir), true)
+}
+
+pub fn clear_cache(config_dir: &Path, use_trash: bool) -> Result<Vec<String>, String> {
+    if !config_dir.is_dir() {
+        return Err(format!("Game folder not found: {}", config_dir.display()));
+    }
+    // The caches sit at the root of the user folder, never inside these
+    if let Some(name) = config_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+    {
+        if ["mods", "saves", "tray"].contains(&name.as_str()) {
+            return Err(format!(
+                "{} is not the game's user folder",
+                config_dir.display()
+            ));
+        }
+    }
+    ensure_game_closed(config_dir)?;
+
+    let mut removed = Vec::new();
+    for entry in CACHE_ENTRIES {
+        let path = config_dir.join(entry);
+        if path.symlink_metadata().is_err() {
+            continue;
+        }
+        remove_path(&path, use_trash)?;
+        removed.push(path.to_string_lossy().to_string());
+    }
+
+    Ok(removed)
+}
+
+/// Fail if the game has one of its cache files open, a sign that it is running
+pub fn ensure_game_closed(config_dir: &Path) -> Result<(), String> {
+    match CACHE_ENTRIES
+        .iter()
+        .map(|entry| config_dir.join(entry))
+        .find(|path| path.is_file() && is_file_in_use(path))
+    {
+        Some(path) => Err(format!(
+            "The game appears to be running ({} is in use), close it and try again",
+            path.display()
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Whether another process holds `path` open exclusively or locked
+pub fn is_file_in_use(path: &Path) -> bool {
+    let file = match OpenOptions::new().read(true).write(true).open(path) {
+        Ok(file) => file,
+        Err(e) => return is_sharing_error(&e),
+    };
+
+    matches!(
+        fs4::FileExt::try_lock(&file),
+        Err(fs4::TryLockError::WouldBlock)
+    )
+}
+
+fn is_sharing_error(error: &io::Error) -> bool {
+    cfg!(windows)
+        && matches!(
+            error.raw_os_error(),
+            Some(ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION)
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::tempdir;
+
+    fn config_fixture(root: &Path) {
+        fs::write(root.join("localthumbcache.package"), b"DBPF").unwrap();
+        fs::write(root.join("avatarcache.package"), b"DBPF").unwrap();
+        fs::create_dir_all(root.join("onlinethumbnailcache")).unwrap();
+        fs::write(root.join("onlinethumbnailcache/123.jpg"), b"jpg").unwrap();
+        fs::create_dir_all(root.join("Mods")).unwrap();
+        fs::write(root.join("Mods/localthumbcache.package"), b"DBPF").unwrap();
+        fs::create_dir_all(root.join("Saves")).unwrap();
+        fs::write(root.join("Saves/Slot_00000001.save"), b"save").unwrap();
+        fs::write(root.join("Options.ini"), b"[options]").unwrap();
+    }
+
+    #[test]
+    fn clears_only_cache_entries() {
+        let dir = tempdir().unwrap();
+        config_fixture(dir.path());
+
+        let removed = clear_cache(dir.path(), false).unwrap();
+
+        assert_eq!(removed.len(), 3);
+        assert!(removed[0].ends_with("localthumbcache.package"));
+        for entry in CACHE_ENTRIES {
+            assert!(!dir.path().join(entry).exists());
+        }
+        assert!(dir.path().join("Mods/localthumbcache.package").exists());
+        assert!(dir.path().join("Saves/Slot_00000001.save").exists());
+        assert!(dir.path().join("Options.ini").exists());
+
+        // Nothing left to clear
+        assert!(clear_cache(dir.path(), false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn refuses_mods_folder_and_running_game() {
+        let dir = tempdir().unwrap();
+        config_fixture(dir.path());
+
+        assert!(clear_cache(&dir.path().join("Mods"), false).is_err());
+        assert!(dir.path().join("Mods/localthumbcache.package").exists());
+
+        let held = File::open(dir.path().join("localthumbcache.package")).unwrap();
+        fs4::FileExt::lock(&held).unwrap();
+
+        let error = clear_cache(dir.path(), false).unwrap_err();
+        assert!(error.contains("appears to be running"));
+        assert!(dir.path().join("avatarcache.package").exists());
+
+        drop(held);
+        assert_eq!(clear_cache(dir.path(), false).unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Where the game records its version, relative to the folder given to `read_game_version`
+/// The user folder (Documents/Electronic Arts/The Sims 4) holds GameVersion.txt, installs from
+/// the EA App and Steam share the same Game/Bin layout, the macOS bundle keeps it under Contents
+const VERSION_FILES: [&str; 5] = [
+    "GameVersion.txt",
+    "Game/Bin/Default.ini",
+    "Bin/Default.ini",
+    "Default.ini",
+    "Contents/Bin/Default.ini",
+];
+
+/// Outcome of a game version lookup
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct GameVersionLookup {
+    /// Dotted version, e.g. 1.105.332.1020
+    pub version: Option<String>,
+    /// File the version was read from
+    pub source: Option<String>,
+    /// Why no version was found
+    pub reason: Option<String>,
+}
+
+/// Read the game version from the game's user folder or install folder
+/// Returns no version, with the reason, when no version file is found or none can be parsed
+#[tauri::command(async)]
+pub fn read_game_version(install_or_config_dir: String) -> GameVersionLookup {
+    find_game_version(Path::new(&install_or_config_dir))
+}
+
+pub fn find_game_version(dir: &Path) -> GameVersionLookup {
+    let not_found = |reason: String| GameVersionLookup {
+        version: None,
+        source: None,
+        reason: Some(reason),
+    };
+    if !dir.is_dir() {
+        return not_found(format!("Folder not found: {}", dir.display()));
+    }
+
+    let candidates: Vec<PathBuf> = VERSION_FILES
+        .iter()
+        .map(|file| dir.join(file))
+        .filter(|path| path.is_file())
+        .collect();
+    if candidates.is_empty() {
+        return not_found(format!(
+            "No GameVersion.txt or Default.ini in {}",
+            dir.display()
+        ));
+    }
+
+    for path in &candidates {
+        let Ok(content) = std::fs::read(path) else {
+            continue;
+        };
+        if let Some(version) = parse_game_version(&content) {
+            return GameVersionLookup {
+                version: Some(version),
+                source: Some(path.to_string_lossy().to_string()),
+                reason: None,
+            };
+        }
+    }
+
+    not_found(format!(
+        "No game version in {}",
+        candidates
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
+
+/// Extract the version from a version file
+/// GameVersion.txt starts with a few binary bytes before the version text, Default.ini stores it
+/// as `gameversion = ...`; both are reduced to the dotted numbers
+pub fn parse_game_version(content: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(content);
+
+    let ini_value = text.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("gameversion")
+            .then_some(value)
+    });
+    match ini_value {
+        Some(value) => dotted_version(value),
+        None => dotted_version(&text),
+    }
+}
+
+/// First run of at least two dot-separated numbers in `text`
+fn dotted_version(text: &str) -> Option<String> {
+    text.split(|c: char| !c.is_ascii_digit() && c != '.')
+        .map(|run| run.trim_matches('.'))
+        .find(|run| {
+            let parts: Vec<&str> = run.split('.').collect();
+            parts.len() >= 2 && parts.iter().all(|part| !part.is_empty())
+        })
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn parses_representative_version_files() {
+        // GameVersion.txt from the user folder, binary prefix included
+        assert_eq!(
+            parse_game_version(b"\x0e\x00\x00\x001.105.332.1020"),
+            Some("1.105.332.1020".to_string())
+        );
+        assert_eq!(
+            parse_game_version(b"\xef\xbb\xbf1.98.158.1020\r\n"),
+            Some("1.98.158.1020".to_string())
+        );
+        // Default.ini from the install folder
+        assert_eq!(
+            parse_game_version(
+                b"[Config]\r\nbuildnumber = 19\r\nGameVersion = 1.107.151.1020\r\nsku = 0\r\n"
+            ),
+            Some("1.107.151.1020".to_string())
+        );
+        assert_eq!(parse_game_version(b"[Config]\nsku = 0\n"), None);
+        assert_eq!(parse_game_version(b""), None);
+    }
+
+    #[test]
+    fn finds_version_in_user_and_install_layouts() {
+        let dir = tempdir().unwrap();
+        let config = dir.path().join("The Sims 4");
+        fs::create_dir_all(&config).unwrap();
+        fs::write(
+            config.join("GameVersion.txt"),
+            b"\x0e\x00\x00\x001.105.332.1020",
+        )
+        .unwrap();
+        let install = dir.path().join("steamapps/common/The Sims 4");
+        fs::create_dir_all(install.join("Game/Bin")).unwrap();
+        fs::write(
+            install.join("Game/Bin/Default.ini"),
+            "gameversion = 1.107.151.1020\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            find_game_version(&config).version.as_deref(),
+            Some("1.105.332.1020")
+        );
+        let lookup = find_game_version(&install);
+        assert_eq!(lookup.version.as_deref(), Some("1.107.151.1020"));
+        assert!(lookup.source.unwrap().ends_with("Default.ini"));
+
+        let missing = find_game_version(dir.path());
+        assert_eq!(missing.version, None);
+        assert!(missing.reason.unwrap().starts_with("No GameVersion.txt"));
+    }
+}
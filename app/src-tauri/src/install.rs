@@ -0,0 +1,465 @@
+use crate::archive::safe_relative_path;
+use crate::download;
+use crate::extract::{self, ExtractOptions};
+use crate::manifest::{self, InstallRecord, InstalledFile, Manifest};
+use crate::operations::{CancellationToken, OperationRegistry, CANCELLED_ERROR};
+use crate::progress::{InstallPhase, Progress, ProgressTracker, URL_INSTALL_PHASE_WEIGHTS};
+use crate::{analyze_zip_content, calculate_file_hash, create_symlink, FAKE_SCORE_LIMIT};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+/// What to install and where
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstallRequest {
+    /// Manifest id of the mod
+    pub id: String,
+    pub name: String,
+    pub version: Option<String>,
+    /// Archive to install
+    pub zip_path: String,
+    /// Folder the archive is extracted into (the mod's library folder)
+    pub dest_dir: String,
+    /// Where to link the extracted folder, typically inside the game's Mods folder
+    pub link_path: Option<String>,
+    #[serde(default)]
+    pub options: ExtractOptions,
+}
+
+/// How to install a mod downloaded by `install_from_url`, all fields default when omitted
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct UrlInstallOptions {
+    /// Manifest id, also the mod's folder name (a single path component); defaults to the
+    /// archive name from the URL
+    pub id: Option<String>,
+    /// Defaults to the id
+    pub name: Option<String>,
+    pub version: Option<String>,
+    /// SHA-256 the download must match, mismatching chunks are re-fetched
+    pub expected_hash: Option<String>,
+    /// Install even when the archive looks like a fake mod
+    pub allow_suspicious: bool,
+    /// Where to link the installed folder
+    pub link_path: Option<String>,
+    pub extract: ExtractOptions,
+}
+
+/// Extract, hash and link a mod, then record it in the manifest
+/// Emits `install://progress` with one overall percentage across all phases
+#[tauri::command(async)]
+pub fn install_mod(
+    app_handle: tauri::AppHandle,
+    request: InstallRequest,
+    operation_id: String,
+) -> Result<InstallRecord, String> {
+    let mut manifest = manifest::open_app_manifest(&app_handle)?;
+    let tracker = ProgressTracker::new(&operation_id, |progress: Progress| {
+        let _ = app_handle.emit("install://progress", progress);
+    });
+
+    install(&request, &mut manifest, &tracker)
+}
+
+/// Download a mod archive and install it into `mods_dir/<id>` in one go
+/// The download resumes and verifies like `download_archive`, archives that look like fakes are
+/// refused, and the mod folder only appears once extraction completed
+/// Emits `install://progress` across all phases and can be stopped with
+/// `cancel_operation(operation_id)` until the extracted folder is moved into place
+#[tauri::command]
+pub async fn install_from_url(
+    app_handle: tauri::AppHandle,
+    registry: tauri::State<'_, OperationRegistry>,
+    url: String,
+    mods_dir: String,
+    options: Option<UrlInstallOptions>,
+    operation_id: String,
+) -> Result<InstallRecord, String> {
+    let operation = registry.start(&operation_id);
+    let mut manifest = manifest::open_app_manifest(&app_handle)?;
+    let tracker = ProgressTracker::with_weights(
+        &operation_id,
+        &URL_INSTALL_PHASE_WEIGHTS,
+        |progress: Progress| {
+            let _ = app_handle.emit("install://progress", progress);
+        },
+    );
+
+    install_url(
+        &url,
+        Path::new(&mods_dir),
+        &options.unwrap_or_default(),
+        &mut manifest,
+        operation.token(),
+        &tracker,
+    )
+    .await
+}
+
+pub async fn install_url<F: Fn(Progress) + Sync>(
+    url: &str,
+    mods_dir: &Path,
+    options: &UrlInstallOptions,
+    manifest: &mut Manifest,
+    token: &CancellationToken,
+    tracker: &ProgressTracker<F>,
+) -> Result<InstallRecord, String> {
+    let archive_name = archive_name_from_url(url)?;
+    let id = match &options.id {
+        Some(id) => id.clone(),
+        None => Path::new(&archive_name)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| archive_name.clone()),
+    };
+    let is_folder_name = safe_relative_path(&id).is_some_and(|path| path.components().count() == 1);
+    if !is_folder_name {
+        return Err(format!("Invalid mod id: {}", id));
+    }
+    let dest_dir = mods_dir.join(&id);
+    if dest_dir.exists() {
+        return Err(format!("{} already exists", dest_dir.display()));
+    }
+    fs::create_dir_all(mods_dir)
+        .map_err(|e| format!("Failed to create {}: {}", mods_dir.display(), e))?;
+
+    // Stable name, so retrying after a failed or cancelled download resumes it
+    let archive_path = mods_dir.join(format!(".{}.download", archive_name));
+
+    tracker.update(InstallPhase::Downloading, 0, 1);
+    download::download_verified(
+        url,
+        &archive_path,
+        options.expected_hash.as_deref(),
+        true,
+        download::CHUNK_SIZE,
+        token,
+        |downloaded, total| match total {
+            Some(total) => tracker.update(
+                InstallPhase::Downloading,
+                downloaded as usize,
+                total as usize,
+            ),
+            None => tracker.update(InstallPhase::Downloading, 0, 1),
+        },
+    )
+    .await?;
+    tracker.update(InstallPhase::Downloading, 1, 1);
+
+    let request = InstallRequest {
+        name: options.name.clone().unwrap_or_else(|| id.clone()),
+        id,
+        version: options.version.clone(),
+        zip_path: archive_path.to_string_lossy().to_string(),
+        dest_dir: dest_dir.to_string_lossy().to_string(),
+        link_path: options.link_path.clone(),
+        options: options.extract.clone(),
+    };
+    let outcome = install_downloaded(&request, options.allow_suspicious, manifest, token, tracker);
+    let _ = fs::remove_file(&archive_path);
+    outcome
+}
+
+/// Check the downloaded archive for fake signals, extract it next to its final folder and
+/// move it into place, then hash, link and record it
+fn install_downloaded<F: Fn(Progress) + Sync>(
+    request: &InstallRequest,
+    allow_suspicious: bool,
+    manifest: &mut Manifest,
+    token: &CancellationToken,
+    tracker: &ProgressTracker<F>,
+) -> Result<InstallRecord, String> {
+    tracker.update(InstallPhase::Analyzing, 0, 1);
+    let analysis = analyze_zip_content(request.zip_path.clone())?;
+    let fake_score: u32 = analysis.fake_signals.iter().map(|s| s.weight).sum();
+    if fake_score >= FAKE_SCORE_LIMIT && !allow_suspicious {
+        let reasons: Vec<&str> = analysis
+            .fake_signals
+            .iter()
+            .map(|s| s.description.as_str())
+            .collect();
+        return Err(format!(
+            "Refusing to install {}, it looks like a fake mod: {}",
+            request.name,
+            reasons.join("; ")
+        ));
+    }
+    tracker.update(InstallPhase::Analyzing, 1, 1);
+
+    if token.is_cancelled() {
+        return Err(CANCELLED_ERROR.to_string());
+    }
+
+    let dest_dir = Path::new(&request.dest_dir);
+    let staging = dest_dir.with_file_name(format!(".{}.{}.staging", request.id, Uuid::new_v4()));
+    tracker.update(InstallPhase::Extracting, 0, 1);
+    let extracted = extract::extract_archive(
+        Path::new(&request.zip_path),
+        &staging,
+        &request.options,
+        |written, total| tracker.update(InstallPhase::Extracting, written, total),
+    )
+    .and_then(|_| {
+        if token.is_cancelled() {
+            return Err(CANCELLED_ERROR.to_string());
+        }
+        fs::rename(&staging, dest_dir)
+            .map_err(|e| format!("Failed to move {} into place: {}", dest_dir.display(), e))
+    });
+    if let Err(e) = extracted {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+
+    record_install(request, manifest, tracker)
+}
+
+/// Last path segment of the URL, the name the archive is saved under
+fn archive_name_from_url(url: &str) -> Result<String, String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    parsed
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|name| !name.is_empty() && *name != "." && *name != "..")
+        .map(str::to_string)
+        .ok_or_else(|| format!("No file name in URL {}", url))
+}
+
+pub fn install<F: Fn(Progress) + Sync>(
+    request: &InstallRequest,
+    manifest: &mut Manifest,
+    tracker: &ProgressTracker<F>,
+) -> Result<InstallRecord, String> {
+    tracker.update(InstallPhase::Extracting, 0, 1);
+    extract::extract_archive(
+        Path::new(&request.zip_path),
+        Path::new(&request.dest_dir),
+        &request.options,
+        |written, total| tracker.update(InstallPhase::Extracting, written, total),
+    )?;
+
+    record_install(request, manifest, tracker)
+}
+
+/// Hash the extracted files, create the link and record the install
+fn record_install<F: Fn(Progress) + Sync>(
+    request: &InstallRequest,
+    manifest: &mut Manifest,
+    tracker: &ProgressTracker<F>,
+) -> Result<InstallRecord, String> {
+    let dest_dir = Path::new(&request.dest_dir);
+    let extracted: Vec<PathBuf> = WalkDir::new(dest_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+
+    let mut files = Vec::with_capacity(extracted.len() + 1);
+    tracker.update(InstallPhase::Hashing, 0, extracted.len());
+    for (index, path) in extracted.iter().enumerate() {
+        let path = path.to_string_lossy().to_string();
+        files.push(InstalledFile {
+            hash: Some(calculate_file_hash(path.clone())?),
+            path,
+        });
+        tracker.update(InstallPhase::Hashing, index + 1, extracted.len());
+    }
+
+    tracker.update(InstallPhase::Linking, 0, 1);
+    if let Some(link_path) = &request.link_path {
+        create_symlink(request.dest_dir.clone(), link_path.clone())?;
+        files.push(InstalledFile {
+            path: link_path.clone(),
+            hash: None,
+        });
+    }
+
+    let record = InstallRecord {
+        id: request.id.clone(),
+        name: request.name.clone(),
+        version: request.version.clone(),
+        installed_at: manifest::unix_now(),
+        files,
+        tags: Vec::new(),
+        note: None,
+    };
+    manifest.upsert_install(&record)?;
+    tracker.update(InstallPhase::Linking, 1, 1);
+
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{range_server, write_zip};
+    use sha2::{Digest, Sha256};
+    use std::sync::Mutex;
+    use tempfile::tempdir;
+
+    #[test]
+    fn overall_progress_is_monotonic_and_completes() {
+        let dir = tempdir().unwrap();
+        let entries: Vec<(String, Vec<u8>)> = (0..20)
+            .map(|i| (format!("Mod/file_{}.package", i), vec![i as u8; 1024]))
+            .collect();
+        let borrowed: Vec<(&str, &[u8])> = entries
+            .iter()
+            .map(|(name, content)| (name.as_str(), content.as_slice()))
+            .collect();
+        let zip_path = write_zip(&dir.path().join("mod.zip"), &borrowed);
+        let request = InstallRequest {
+            id: "mod".to_string(),
+            name: "Mod".to_string(),
+            version: None,
+            zip_path,
+            dest_dir: dir.path().join("library/mod").to_string_lossy().to_string(),
+            link_path: Some(dir.path().join("Mods").to_string_lossy().to_string()),
+            options: ExtractOptions::default(),
+        };
+
+        let events = Mutex::new(Vec::new());
+        let tracker = ProgressTracker::new("install-1", |p| events.lock().unwrap().push(p));
+        let mut manifest = Manifest::open_in_memory().unwrap();
+
+        let record = install(&request, &mut manifest, &tracker).unwrap();
+
+        assert_eq!(record.files.len(), 21);
+        assert!(dir.path().join("Mods/Mod/file_3.package").exists());
+        assert_eq!(
+            manifest.get_install("mod").unwrap().unwrap().files.len(),
+            21
+        );
+
+        let events = events.into_inner().unwrap();
+        assert!(events
+            .windows(2)
+            .all(|pair| pair[0].overall_progress <= pair[1].overall_progress));
+        let last = events.last().unwrap();
+        assert_eq!(last.phase, InstallPhase::Linking);
+        assert!((last.overall_progress - 100.0).abs() < 1e-9);
+        for phase in [
+            InstallPhase::Extracting,
+            InstallPhase::Hashing,
+            InstallPhase::Linking,
+        ] {
+            assert!(events.iter().any(|p| p.phase == phase));
+        }
+    }
+
+    #[tokio::test]
+    async fn installs_from_url_end_to_end() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("fixture.zip"),
+            &[
+                ("Hair/hair.package", b"DBPF hair"),
+                ("Hair/hair_thumbnail.package", b"DBPF thumb"),
+            ],
+        );
+        let body = fs::read(&zip_path).unwrap();
+        let (url, _) = range_server(body.clone());
+        let mods = dir.path().join("Mods");
+        let options = UrlInstallOptions {
+            expected_hash: Some(format!("{:x}", Sha256::digest(&body))),
+            ..Default::default()
+        };
+
+        let events = Mutex::new(Vec::new());
+        let tracker = ProgressTracker::with_weights("url-1", &URL_INSTALL_PHASE_WEIGHTS, |p| {
+            events.lock().unwrap().push(p)
+        });
+        let mut manifest = Manifest::open_in_memory().unwrap();
+
+        let record = install_url(
+            &url,
+            &mods,
+            &options,
+            &mut manifest,
+            &CancellationToken::default(),
+            &tracker,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(record.id, "pack");
+        assert_eq!(record.files.len(), 2);
+        assert!(record.files.iter().all(|file| file.hash.is_some()));
+        assert_eq!(
+            fs::read(mods.join("pack/Hair/hair.package")).unwrap(),
+            b"DBPF hair"
+        );
+        assert!(manifest.get_install("pack").unwrap().is_some());
+        // No download, partial or staging leftovers next to the mod
+        assert_eq!(fs::read_dir(&mods).unwrap().count(), 1);
+
+        let events = events.into_inner().unwrap();
+        assert_eq!(events[0].phase, InstallPhase::Downloading);
+        assert!(events
+            .windows(2)
+            .all(|pair| pair[0].overall_progress <= pair[1].overall_progress));
+        assert!((events.last().unwrap().overall_progress - 100.0).abs() < 1e-9);
+        assert!(events.iter().any(|p| p.phase == InstallPhase::Analyzing));
+    }
+
+    #[tokio::test]
+    async fn refuses_fake_archive_from_url() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("fake.zip"),
+            &[("Download here.url", b"[InternetShortcut]")],
+        );
+        let (url, _) = range_server(fs::read(&zip_path).unwrap());
+        let mods = dir.path().join("Mods");
+        let tracker = ProgressTracker::new("url-2", |_| {});
+        let mut manifest = Manifest::open_in_memory().unwrap();
+
+        let error = install_url(
+            &url,
+            &mods,
+            &UrlInstallOptions::default(),
+            &mut manifest,
+            &CancellationToken::default(),
+            &tracker,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(error.contains("fake mod"));
+        assert_eq!(fs::read_dir(&mods).unwrap().count(), 0);
+        assert!(manifest.list_installs().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_ids_that_are_not_a_single_folder_name() {
+        let dir = tempdir().unwrap();
+        let mods = dir.path().join("Mods");
+        let tracker = ProgressTracker::new("url-3", |_| {});
+        let mut manifest = Manifest::open_in_memory().unwrap();
+
+        for id in ["../escape", "Hair/Long", "/tmp/abs", ".."] {
+            let options = UrlInstallOptions {
+                id: Some(id.to_string()),
+                ..Default::default()
+            };
+            let error = install_url(
+                "http://127.0.0.1:9/pack.zip",
+                &mods,
+                &options,
+                &mut manifest,
+                &CancellationToken::default(),
+                &tracker,
+            )
+            .await
+            .unwrap_err();
+            assert!(error.starts_with("Invalid mod id"), "{}", error);
+        }
+        assert!(!mods.exists());
+        assert!(!dir.path().join("escape").exists());
+    }
+}
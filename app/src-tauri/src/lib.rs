@@ -1,82 +1,68 @@
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::fs::{copy as fs_copy, create_dir_all, metadata, read_dir, remove_dir_all, File};
-use std::io::{copy, Read, Write};
+use std::fs::{copy as fs_copy, create_dir_all, metadata, read_dir, File};
+use std::io::{BufReader, Read, Write};
 use std::path::Path;
 use std::sync::Mutex;
-use std::time::Instant;
 use uuid::Uuid;
 use zip::ZipArchive;
 
+mod access;
+mod archive;
+mod archive_parts;
+mod backup;
+mod benchmark;
+mod classify;
+mod compatibility;
+mod conflicts;
+mod cpu;
+mod dbpf;
+mod dedup;
+mod delta;
+mod dependencies;
+mod descriptor;
+mod dir_diff;
+mod dir_hash;
+mod download;
+mod exceptions;
+mod extract;
+mod filetype;
+mod game_cache;
+mod game_version;
+mod install;
+mod logs;
+mod manifest;
+mod merge;
+mod operations;
+mod organize;
+mod overrides;
+mod package_metadata;
+mod profiles;
+mod profiling;
+mod progress;
+mod remote;
+mod replace;
+mod report;
+mod script;
+mod strip;
+mod symlinks;
+#[cfg(test)]
+mod test_support;
+mod text_encoding;
+mod thumbnails;
+mod transaction;
+mod tray;
+mod tree_hash;
+mod validate;
+mod walk;
+
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-#[tauri::command]
-fn extract_zip(zip_path: String, dest_dir: String) -> Result<(), String> {
-    let file = File::open(&zip_path).map_err(|e| e.to_string())?;
-    let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
-
-    // First pass: collect all file metadata and content (must be sequential due to ZipArchive)
-    let mut files_to_create: Vec<(String, Vec<u8>, bool)> = Vec::new();
-    let mut dirs_to_create: Vec<String> = Vec::new();
-
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
-        let name = file.name().to_string();
-
-        if name.ends_with('/') {
-            dirs_to_create.push(name);
-        } else {
-            // Read file content into memory
-            let mut buffer = Vec::new();
-            copy(&mut file, &mut buffer).map_err(|e| e.to_string())?;
-            files_to_create.push((name, buffer, false));
-        }
-    }
-
-    // Create all directories first (sequential to avoid race conditions)
-    for dir_name in dirs_to_create {
-        let outpath = Path::new(&dest_dir).join(&dir_name);
-        create_dir_all(&outpath).map_err(|e| e.to_string())?;
-    }
-
-    // Create parent directories for all files (sequential)
-    for (file_name, _, _) in &files_to_create {
-        let outpath = Path::new(&dest_dir).join(file_name);
-        if let Some(p) = outpath.parent() {
-            create_dir_all(p).map_err(|e| e.to_string())?;
-        }
-    }
-
-    // Second pass: write all files in parallel with rayon
-    let error_mutex = Mutex::new(Option::<String>::None);
-
-    files_to_create
-        .par_iter()
-        .for_each(|(file_name, content, _)| {
-            if error_mutex.lock().unwrap().is_some() {
-                return;
-            }
-
-            let outpath = Path::new(&dest_dir).join(file_name);
-            if let Err(e) = std::fs::write(&outpath, content) {
-                *error_mutex.lock().unwrap() =
-                    Some(format!("Failed to write {}: {}", file_name, e));
-            }
-        });
-
-    // Check for errors from parallel operations
-    if let Some(e) = error_mutex.into_inner().unwrap() {
-        return Err(e);
-    }
-
-    Ok(())
-}
-
 /// Create a symbolic link (directory junction on Windows, symlink on Unix)
 #[tauri::command]
 fn create_symlink(source: String, target: String) -> Result<(), String> {
@@ -225,6 +211,121 @@ pub struct ZipAnalysis {
     pub suspicious_files: Vec<String>,
     /// Total number of files in the ZIP
     pub total_files: usize,
+    /// Whether the ZIP contains anything the game can load (.package or .ts4script)
+    pub is_functional_mod: bool,
+    /// Fake mod signals found in the ZIP, strongest first
+    pub fake_signals: Vec<FakeSignal>,
+    /// Entries dated at the epoch (or with an empty date) or in the future
+    pub suspicious_timestamps: Vec<String>,
+    /// Metadata left by other mod managers or archivers, which should not be installed
+    pub manager_markers: Vec<ManagerMarker>,
+    /// .package entries that are really archives (zip, rar, ...) renamed
+    pub disguised_archives: Vec<String>,
+    /// Other entries that are archives themselves (zip-in-zip), `extract_nested` unwraps the zip ones
+    pub nested_archives: Vec<String>,
+}
+
+/// Folder or file belonging to another tool's export rather than to the mod itself
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ManagerMarker {
+    /// Tool that left the marker
+    pub manager: String,
+    /// Path of the marker inside the ZIP (folders end with '/'), everything under it can be skipped
+    pub path: String,
+}
+
+/// Folder names other tools create next to the mods they manage
+const MANAGER_MARKER_DIRS: [(&str, &str); 3] = [
+    ("__MACOSX", "macOS Archive Utility"),
+    (".vortex", "Vortex"),
+    (".simsforge", "SimsForge"),
+];
+
+/// File names other tools write into the folders they manage
+const MANAGER_MARKER_FILES: [(&str, &str); 4] = [
+    ("__vortex_staging_folder", "Vortex"),
+    ("vortex.deployment.json", "Vortex"),
+    ("meta.ini", "Mod Organizer 2"),
+    (".ds_store", "macOS Finder"),
+];
+
+/// Find the first marker in an entry path, returning it with the path up to the marker
+fn find_manager_marker(name: &str) -> Option<ManagerMarker> {
+    let components: Vec<&str> = name.split(['/', '\\']).filter(|c| !c.is_empty()).collect();
+
+    for (depth, component) in components.iter().enumerate() {
+        let is_last = depth == components.len() - 1 && !name.ends_with(['/', '\\']);
+        let lower = component.to_lowercase();
+
+        let manager = if is_last {
+            MANAGER_MARKER_FILES
+                .iter()
+                .find(|(marker, _)| *marker == lower)
+                // Vortex names its deployment manifests vortex.deployment.<modtype>.json
+                .or_else(|| {
+                    (lower.starts_with("vortex.deployment.") && lower.ends_with(".json"))
+                        .then_some(&MANAGER_MARKER_FILES[1])
+                })
+        } else {
+            MANAGER_MARKER_DIRS
+                .iter()
+                .find(|(marker, _)| marker.eq_ignore_ascii_case(component))
+        };
+
+        if let Some((_, manager)) = manager {
+            let mut path = components[..=depth].join("/");
+            if !is_last {
+                path.push('/');
+            }
+            return Some(ManagerMarker {
+                manager: manager.to_string(),
+                path,
+            });
+        }
+    }
+
+    None
+}
+
+/// Single reason to suspect a ZIP is not a genuine mod
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FakeSignal {
+    /// Stable identifier the frontend can match on
+    pub code: String,
+    /// Contribution to the fake score (0-100 scale, like the frontend rules)
+    pub weight: u32,
+    /// Human-readable explanation
+    pub description: String,
+}
+
+/// Fake score at which an archive is treated as fake (refused on install unless explicitly allowed)
+pub(crate) const FAKE_SCORE_LIMIT: u32 = 50;
+
+/// Weight of an archive with nothing loadable, the strongest fake signal
+const NO_MOD_FILES_WEIGHT: u32 = 50;
+
+/// Weight of an archive renamed to .package, almost always a repackaging scam
+const DISGUISED_ARCHIVE_WEIGHT: u32 = 40;
+
+/// Weight of epoch / future entry dates, common in repackaged archives but also in some legit tools
+const SUSPICIOUS_TIMESTAMP_WEIGHT: u32 = 10;
+
+/// 1980-01-01 00:00:00, the earliest DOS date (files dated 1970 are clamped to it)
+const DOS_EPOCH_TIMESTAMP: i64 = 315_532_800;
+
+/// DOS dates carry no time zone, so allow a day of slack before calling a date "future"
+const FUTURE_TIMESTAMP_TOLERANCE_SECS: i64 = 86_400;
+
+/// Whether a ZIP entry date is empty, at the epoch or in the future
+fn is_suspicious_timestamp(modified: zip::DateTime, now: i64) -> bool {
+    match modified.to_time() {
+        Ok(time) => {
+            let timestamp = time.unix_timestamp();
+            timestamp <= DOS_EPOCH_TIMESTAMP || timestamp > now + FUTURE_TIMESTAMP_TOLERANCE_SECS
+        }
+        // All-zero dates (month/day 0) don't even form a valid date
+        Err(_) => true,
+    }
 }
 
 /// Analyze ZIP content for fake mod detection
@@ -238,13 +339,18 @@ fn analyze_zip_content(zip_path: String) -> Result<ZipAnalysis, String> {
     let mut has_ts_script = false;
     let mut file_list: Vec<String> = Vec::new();
     let mut suspicious_files: Vec<String> = Vec::new();
+    let mut suspicious_timestamps: Vec<String> = Vec::new();
+    let mut manager_markers: Vec<ManagerMarker> = Vec::new();
+    let mut disguised_archives: Vec<String> = Vec::new();
+    let mut nested_archives: Vec<String> = Vec::new();
+    let now = manifest::unix_now();
 
     // Suspicious file patterns
     let suspicious_extensions = [".url", ".lnk", ".html", ".htm", ".webloc"];
     let suspicious_names = ["readme", "patreon", "support", "donate", "link", "discord"];
 
     for i in 0..archive.len() {
-        let file = archive.by_index(i).map_err(|e| format!("Failed to read ZIP entry: {}", e))?;
+        let mut file = archive.by_index(i).map_err(|e| format!("Failed to read ZIP entry: {}", e))?;
         let name = file.name().to_string();
         let name_lower = name.to_lowercase();
 
@@ -255,12 +361,34 @@ fn analyze_zip_content(zip_path: String) -> Result<ZipAnalysis, String> {
 
         file_list.push(name.clone());
 
-        // Check for valid mod files
-        if name_lower.ends_with(".package") {
-            has_package_files = true;
+        if is_suspicious_timestamp(file.last_modified(), now) {
+            suspicious_timestamps.push(name.clone());
         }
-        if name_lower.ends_with(".ts4script") {
+
+        if let Some(marker) = find_manager_marker(&name) {
+            if !manager_markers.contains(&marker) {
+                manager_markers.push(marker);
+            }
+        }
+
+        let mut header = Vec::with_capacity(filetype::ARCHIVE_MAGIC_LEN);
+        let _ = (&mut file)
+            .take(filetype::ARCHIVE_MAGIC_LEN as u64)
+            .read_to_end(&mut header);
+        let is_archive = filetype::archive_format(&header).is_some();
+
+        // Check for valid mod files, a renamed archive is not one even with the right extension
+        if name_lower.ends_with(".package") {
+            if is_archive {
+                disguised_archives.push(name.clone());
+            } else {
+                has_package_files = true;
+            }
+        } else if name_lower.ends_with(".ts4script") {
+            // Script mods are zips by design
             has_ts_script = true;
+        } else if is_archive {
+            nested_archives.push(name.clone());
         }
 
         // Check for suspicious files
@@ -274,90 +402,115 @@ fn analyze_zip_content(zip_path: String) -> Result<ZipAnalysis, String> {
         }
     }
 
+    let is_functional_mod = has_package_files || has_ts_script;
+    let fake_signals = fake_signals_for(
+        is_functional_mod,
+        disguised_archives.len(),
+        suspicious_timestamps.len(),
+    );
+
     Ok(ZipAnalysis {
         has_package_files,
         has_ts_script,
         file_list,
         suspicious_files,
         total_files: archive.len(),
+        is_functional_mod,
+        fake_signals,
+        suspicious_timestamps,
+        manager_markers,
+        disguised_archives,
+        nested_archives,
     })
 }
 
-/// Result of disk benchmark
-#[derive(Serialize, Deserialize)]
-pub struct DiskBenchmarkResult {
-    /// Measured disk speed in MB/s
-    pub speed_mbps: u64,
-    /// Total bytes written during benchmark
-    pub bytes_written: u64,
-    /// Time taken in milliseconds
-    pub elapsed_ms: u64,
+/// Weighted fake signals from what an analysis found, strongest first
+fn fake_signals_for(
+    is_functional_mod: bool,
+    disguised_archives: usize,
+    suspicious_timestamps: usize,
+) -> Vec<FakeSignal> {
+    let mut fake_signals = Vec::new();
+    if !is_functional_mod {
+        fake_signals.push(FakeSignal {
+            code: "no_mod_files".to_string(),
+            weight: NO_MOD_FILES_WEIGHT,
+            description: "No mod files detected (.package or .ts4script)".to_string(),
+        });
+    }
+    if disguised_archives > 0 {
+        fake_signals.push(FakeSignal {
+            code: "disguised_archive".to_string(),
+            weight: DISGUISED_ARCHIVE_WEIGHT,
+            description: format!(
+                "{} .package file(s) are actually archives in disguise",
+                disguised_archives
+            ),
+        });
+    }
+    if suspicious_timestamps > 0 {
+        fake_signals.push(FakeSignal {
+            code: "suspicious_timestamps".to_string(),
+            weight: SUSPICIOUS_TIMESTAMP_WEIGHT,
+            description: format!(
+                "{} file(s) dated at the epoch or in the future",
+                suspicious_timestamps
+            ),
+        });
+    }
+    fake_signals.sort_by_key(|signal| std::cmp::Reverse(signal.weight));
+    fake_signals
 }
 
-/// Benchmark disk write speed by writing test files directly in Rust
-/// This avoids IPC overhead and gives accurate disk performance measurement
-#[tauri::command]
-fn benchmark_disk_speed(app_handle: tauri::AppHandle) -> Result<DiskBenchmarkResult, String> {
-    use tauri::Manager;
-
-    // Get app data directory for temp files
-    let app_data_dir = app_handle
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
-
-    let benchmark_dir = app_data_dir.join("benchmark_temp");
-
-    // Create benchmark directory
-    create_dir_all(&benchmark_dir)
-        .map_err(|e| format!("Failed to create benchmark directory: {}", e))?;
-
-    // Configuration: 5 files of 50MB each = 250MB total
-    // Larger files reduce overhead impact and give more accurate measurements
-    const FILE_COUNT: usize = 5;
-    const FILE_SIZE: usize = 50 * 1024 * 1024; // 50MB per file
-    const TOTAL_BYTES: u64 = (FILE_COUNT * FILE_SIZE) as u64;
-
-    // Generate test data (pseudo-random pattern)
-    let test_data: Vec<u8> = (0..FILE_SIZE)
-        .map(|i| ((i * 17 + 31) % 256) as u8)
-        .collect();
+/// Preliminary verdict from an archive's directory alone
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QuickAnalysis {
+    /// Whether the preliminary score reaches the install refusal limit
+    pub likely_fake: bool,
+    /// Description of the strongest signal, if any
+    pub reason: Option<String>,
+    pub fake_score: u32,
+    pub total_files: usize,
+}
 
-    // Measure write time
-    let start = Instant::now();
+/// Fast fake screening for batches of downloads, run `analyze_zip_content` on the flagged ones
+/// Only entry names and dates are read, no entry data is decompressed, so archives renamed to
+/// .package are not detected here
+#[tauri::command]
+fn quick_analyze(zip_path: String) -> Result<QuickAnalysis, String> {
+    let file = File::open(&zip_path).map_err(|e| format!("Failed to open ZIP: {}", e))?;
+    let mut archive = ZipArchive::new(BufReader::new(file))
+        .map_err(|e| format!("Invalid ZIP file: {}", e))?;
 
-    for i in 0..FILE_COUNT {
-        let file_path = benchmark_dir.join(format!("bench_{}.bin", i));
-        let mut file = File::create(&file_path)
-            .map_err(|e| format!("Failed to create benchmark file: {}", e))?;
+    let mut is_functional_mod = false;
+    let mut suspicious_timestamps = 0;
+    let now = manifest::unix_now();
 
-        file.write_all(&test_data)
-            .map_err(|e| format!("Failed to write benchmark file: {}", e))?;
+    for i in 0..archive.len() {
+        let file = archive
+            .by_index_raw(i)
+            .map_err(|e| format!("Failed to read ZIP entry: {}", e))?;
+        let name_lower = file.name().to_lowercase();
 
-        // Ensure data is flushed to disk
-        file.sync_all()
-            .map_err(|e| format!("Failed to sync benchmark file: {}", e))?;
+        if name_lower.ends_with('/') || name_lower.ends_with('\\') {
+            continue;
+        }
+        if name_lower.ends_with(".package") || name_lower.ends_with(".ts4script") {
+            is_functional_mod = true;
+        }
+        if is_suspicious_timestamp(file.last_modified(), now) {
+            suspicious_timestamps += 1;
+        }
     }
 
-    let elapsed = start.elapsed();
-    let elapsed_ms = elapsed.as_millis() as u64;
-
-    // Calculate speed in MB/s
-    let speed_mbps = if elapsed_ms > 0 {
-        (TOTAL_BYTES / (1024 * 1024)) * 1000 / elapsed_ms
-    } else {
-        1000 // If too fast to measure, assume very fast
-    };
-
-    // Cleanup benchmark files
-    if let Err(e) = remove_dir_all(&benchmark_dir) {
-        eprintln!("Warning: Failed to cleanup benchmark directory: {}", e);
-    }
+    let fake_signals = fake_signals_for(is_functional_mod, 0, suspicious_timestamps);
+    let fake_score = fake_signals.iter().map(|s| s.weight).sum();
 
-    Ok(DiskBenchmarkResult {
-        speed_mbps,
-        bytes_written: TOTAL_BYTES,
-        elapsed_ms,
+    Ok(QuickAnalysis {
+        likely_fake: fake_score >= FAKE_SCORE_LIMIT,
+        reason: fake_signals.into_iter().next().map(|s| s.description),
+        fake_score,
+        total_files: archive.len(),
     })
 }
 
@@ -465,6 +618,11 @@ fn copy_dir_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // Extraction and copies run on the global pool, leave a core for the UI
+    let _ = rayon::ThreadPoolBuilder::new()
+        .num_threads(cpu::recommended_workers().extraction)
+        .build_global();
+
     tauri::Builder::default()
         .plugin(
             tauri_plugin_log::Builder::new()
@@ -479,9 +637,13 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .manage(operations::OperationRegistry::default())
+        .manage(benchmark::BenchmarkCache::default())
+        .manage(tree_hash::TreeHashCache::default())
         .invoke_handler(tauri::generate_handler![
             greet,
-            extract_zip,
+            extract::extract_zip,
+            extract::extract_archives,
             create_symlink,
             remove_symlink,
             list_symlinks,
@@ -489,9 +651,294 @@ pub fn run() {
             get_file_size,
             copy_directory,
             analyze_zip_content,
+            quick_analyze,
             get_or_create_machine_id,
-            benchmark_disk_speed
+            benchmark::benchmark_disk_speed,
+            benchmark::benchmark_drives,
+            logs::get_log_path,
+            logs::read_log_tail,
+            operations::cancel_operation,
+            conflicts::find_package_conflicts,
+            descriptor::read_mod_descriptor,
+            archive::plan_extraction,
+            thumbnails::extract_package_thumbnail,
+            manifest::register_install,
+            manifest::list_installs,
+            manifest::uninstall_mods,
+            manifest::set_mod_tags,
+            manifest::set_mod_note,
+            manifest::list_installs_by_tag,
+            walk::walk_directory,
+            filetype::detect_file_type,
+            remote::remote_file_differs,
+            archive::repack_archive,
+            script::inspect_ts4script,
+            benchmark::estimate_extraction_time,
+            replace::replace_file_atomic,
+            cpu::get_cpu_info,
+            tree_hash::compute_tree_hash,
+            access::check_mods_writable,
+            progress::get_install_phase_weights,
+            install::install_mod,
+            organize::organize_library,
+            extract::verify_then_extract,
+            dir_hash::hash_directory,
+            dependencies::check_dependency_cycles,
+            dependencies::find_missing_dependencies,
+            delta::compute_package_delta,
+            delta::apply_package_delta,
+            benchmark::classify_drive,
+            tray::list_tray_items,
+            tray::backup_tray,
+            exceptions::correlate_exception,
+            game_cache::clear_game_cache,
+            download::download_archive,
+            symlinks::audit_all_symlinks,
+            backup::create_backup,
+            organize::recommend_install_depth,
+            transaction::apply_mod_transaction,
+            package_metadata::read_package_metadata,
+            archive_parts::detect_archive_parts,
+            archive_parts::join_archive_parts,
+            archive::archive_fingerprint,
+            archive::check_seen_archive,
+            backup::list_backups,
+            backup::prune_backups,
+            overrides::find_likely_overrides,
+            dir_diff::compute_directory_diff,
+            dir_diff::sync_directory,
+            extract::check_files_present,
+            profiling::profile_operation,
+            merge::merge_packages,
+            filetype::suggest_extension_fix,
+            filetype::fix_extension,
+            validate::validate_package,
+            validate::validate_library,
+            conflicts::find_global_key_collisions,
+            conflicts::find_tuning_name_collisions,
+            install::install_from_url,
+            strip::strip_package_debug,
+            compatibility::compatibility_report,
+            backup::create_incremental_backup,
+            backup::restore_incremental,
+            dependencies::default_framework_markers,
+            dependencies::detect_framework_requirements,
+            symlinks::check_symlink_capability,
+            dedup::estimate_dedup_savings,
+            game_version::read_game_version,
+            profiles::swap_active_profile,
+            text_encoding::normalize_text_file,
+            report::export_report
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::write_zip;
+    use tempfile::tempdir;
+
+    #[test]
+    fn readme_only_zip_is_not_functional() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("fake.zip"),
+            &[
+                ("README.txt", b"Download the real mod on my Patreon"),
+                ("preview.png", b"\x89PNG"),
+            ],
+        );
+
+        let analysis = analyze_zip_content(zip_path).unwrap();
+
+        assert!(!analysis.is_functional_mod);
+        assert_eq!(analysis.fake_signals[0].code, "no_mod_files");
+        assert_eq!(analysis.fake_signals[0].weight, NO_MOD_FILES_WEIGHT);
+    }
+
+    #[test]
+    fn zip_with_mod_files_is_functional() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("real.zip"),
+            &[
+                ("Mod/hair.package", b"DBPF"),
+                ("Mod/script.ts4script", b"PK"),
+            ],
+        );
+
+        let analysis = analyze_zip_content(zip_path).unwrap();
+
+        assert!(analysis.is_functional_mod);
+        assert!(analysis.fake_signals.is_empty());
+    }
+
+    #[test]
+    fn flags_epoch_and_future_entry_dates() {
+        let dir = tempdir().unwrap();
+        let zip_path = dir.path().join("dated.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        let dated = [
+            ("Mod/future.package", 2099, 6, 1),
+            ("Mod/epoch.package", 1980, 1, 1),
+            ("Mod/normal.package", 2023, 3, 14),
+        ];
+        for (name, year, month, day) in dated {
+            let modified = zip::DateTime::from_date_and_time(year, month, day, 0, 0, 0).unwrap();
+            let options = zip::write::FileOptions::default().last_modified_time(modified);
+            writer.start_file(name, options).unwrap();
+            writer.write_all(b"DBPF").unwrap();
+        }
+        writer.finish().unwrap();
+
+        let analysis = analyze_zip_content(zip_path.to_string_lossy().to_string()).unwrap();
+
+        assert_eq!(
+            analysis.suspicious_timestamps,
+            vec!["Mod/future.package", "Mod/epoch.package"]
+        );
+        assert_eq!(analysis.fake_signals.len(), 1);
+        assert_eq!(analysis.fake_signals[0].code, "suspicious_timestamps");
+        assert_eq!(analysis.fake_signals[0].weight, SUSPICIOUS_TIMESTAMP_WEIGHT);
+    }
+
+    #[test]
+    fn reports_nested_manager_metadata() {
+        let dir = tempdir().unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("bundle.zip"),
+            &[
+                ("Bundle/Hair/hair.package", b"DBPF"),
+                ("Bundle/Hair/meta.ini", b"[General]"),
+                ("Bundle/.vortex/state.json", b"{}"),
+                ("Bundle/.vortex/profiles/default.json", b"{}"),
+                ("Bundle/vortex.deployment.sims4.json", b"{}"),
+                ("__MACOSX/Bundle/._hair.package", b""),
+                ("Bundle/metadata.txt", b"not a marker"),
+            ],
+        );
+
+        let analysis = analyze_zip_content(zip_path).unwrap();
+
+        let markers: Vec<(&str, &str)> = analysis
+            .manager_markers
+            .iter()
+            .map(|m| (m.manager.as_str(), m.path.as_str()))
+            .collect();
+        assert_eq!(
+            markers,
+            vec![
+                ("Mod Organizer 2", "Bundle/Hair/meta.ini"),
+                ("Vortex", "Bundle/.vortex/"),
+                ("Vortex", "Bundle/vortex.deployment.sims4.json"),
+                ("macOS Archive Utility", "__MACOSX/"),
+            ]
+        );
+        assert!(analysis.is_functional_mod);
+    }
+
+    #[test]
+    fn flags_archives_renamed_to_package() {
+        let dir = tempdir().unwrap();
+        let inner = write_zip(&dir.path().join("inner.zip"), &[("real.package", b"DBPF")]);
+        let inner_bytes = std::fs::read(inner).unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("outer.zip"),
+            &[
+                ("Mod/hair.package", &inner_bytes),
+                ("Mod/rar.package", b"Rar!\x1a\x07\x01\x00"),
+            ],
+        );
+
+        let analysis = analyze_zip_content(zip_path).unwrap();
+
+        assert_eq!(
+            analysis.disguised_archives,
+            vec!["Mod/hair.package", "Mod/rar.package"]
+        );
+        assert!(!analysis.has_package_files);
+        assert!(!analysis.is_functional_mod);
+        let codes: Vec<&str> = analysis
+            .fake_signals
+            .iter()
+            .map(|s| s.code.as_str())
+            .collect();
+        assert_eq!(codes, vec!["no_mod_files", "disguised_archive"]);
+        assert_eq!(analysis.fake_signals[1].weight, DISGUISED_ARCHIVE_WEIGHT);
+        assert!(analysis.nested_archives.is_empty());
+    }
+
+    #[test]
+    fn flags_nested_archives() {
+        let dir = tempdir().unwrap();
+        let inner = write_zip(&dir.path().join("inner.zip"), &[("real.package", b"DBPF")]);
+        let inner_bytes = std::fs::read(inner).unwrap();
+        let zip_path = write_zip(
+            &dir.path().join("outer.zip"),
+            &[
+                ("Mod.zip", &inner_bytes),
+                ("Extras.7z", b"7z\xBC\xAF\x27\x1C\x00\x04"),
+                ("Mod/script.ts4script", &inner_bytes),
+            ],
+        );
+
+        let analysis = analyze_zip_content(zip_path).unwrap();
+
+        assert_eq!(analysis.nested_archives, vec!["Mod.zip", "Extras.7z"]);
+        assert!(analysis.disguised_archives.is_empty());
+        assert!(analysis.has_ts_script);
+    }
+
+    #[test]
+    fn quick_verdict_matches_full_analysis() {
+        let dir = tempdir().unwrap();
+        let real = write_zip(
+            &dir.path().join("real.zip"),
+            &[
+                ("Mod/", b""),
+                ("Mod/hair.package", b"DBPF"),
+                ("Mod/README.txt", b"Thanks for downloading"),
+            ],
+        );
+        let fake = write_zip(
+            &dir.path().join("fake.zip"),
+            &[
+                ("Download here.url", b"[InternetShortcut]"),
+                ("preview.png", b"\x89PNG"),
+            ],
+        );
+        let script = write_zip(
+            &dir.path().join("script.zip"),
+            &[("Mod/script.ts4script", b"PK")],
+        );
+
+        for (zip_path, expected) in [(real, false), (fake, true), (script, false)] {
+            let full = analyze_zip_content(zip_path.clone()).unwrap();
+            let full_score: u32 = full.fake_signals.iter().map(|s| s.weight).sum();
+            let quick = quick_analyze(zip_path.clone()).unwrap();
+
+            assert_eq!(quick.likely_fake, expected, "{}", zip_path);
+            assert_eq!(quick.likely_fake, full_score >= FAKE_SCORE_LIMIT);
+            assert_eq!(quick.fake_score, full_score);
+            assert_eq!(quick.total_files, full.total_files);
+        }
+
+        let fake_path = dir.path().join("fake.zip").to_string_lossy().to_string();
+        let quick = quick_analyze(fake_path).unwrap();
+        assert_eq!(
+            quick.reason.as_deref(),
+            Some("No mod files detected (.package or .ts4script)")
+        );
+    }
+
+    #[test]
+    fn empty_dos_date_is_suspicious() {
+        assert!(is_suspicious_timestamp(zip::DateTime::from_msdos(0, 0), 0));
+        assert!(!is_suspicious_timestamp(
+            zip::DateTime::from_date_and_time(2024, 1, 1, 0, 0, 0).unwrap(),
+            1_735_689_600
+        ));
+    }
+}
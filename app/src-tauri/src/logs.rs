@@ -0,0 +1,129 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// Chunk size used when scanning the log file backwards
+const TAIL_CHUNK_SIZE: u64 = 8 * 1024;
+
+/// Resolve the active log file written by tauri_plugin_log
+/// The plugin names it after the app and rotates older content into timestamped siblings,
+/// so the active file is always `<app_log_dir>/<app name>.log`
+fn active_log_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let log_dir = app_handle
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to get app log directory: {}", e))?;
+
+    Ok(log_dir.join(format!("{}.log", app_handle.package_info().name)))
+}
+
+/// Get the path of the active application log file
+#[tauri::command]
+pub fn get_log_path(app_handle: tauri::AppHandle) -> Result<String, String> {
+    let path = active_log_path(&app_handle)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Read the last `max_lines` lines of the active application log file
+/// Returns an empty list if nothing has been logged yet
+#[tauri::command]
+pub fn read_log_tail(
+    app_handle: tauri::AppHandle,
+    max_lines: usize,
+) -> Result<Vec<String>, String> {
+    let path = active_log_path(&app_handle)?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    read_last_lines(&path, max_lines)
+}
+
+/// Read the last `max_lines` lines of a file without loading all of it
+/// Chunks are read backwards from the end until enough line breaks have been seen
+fn read_last_lines(path: &Path, max_lines: usize) -> Result<Vec<String>, String> {
+    if max_lines == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut file = File::open(path)
+        .map_err(|e| format!("Failed to open log file {}: {}", path.display(), e))?;
+    let file_len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read log file metadata {}: {}", path.display(), e))?
+        .len();
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut position = file_len;
+
+    // A trailing newline terminates the last line, it doesn't start a new one
+    while position > 0 && buffer.iter().filter(|&&b| b == b'\n').count() <= max_lines {
+        let chunk_len = TAIL_CHUNK_SIZE.min(position);
+        position -= chunk_len;
+
+        let mut chunk = vec![0u8; chunk_len as usize];
+        file.seek(SeekFrom::Start(position))
+            .and_then(|_| file.read_exact(&mut chunk))
+            .map_err(|e| format!("Failed to read log file {}: {}", path.display(), e))?;
+
+        chunk.extend_from_slice(&buffer);
+        buffer = chunk;
+    }
+
+    let content = String::from_utf8_lossy(&buffer);
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.len().saturating_sub(max_lines);
+
+    Ok(lines[start..].iter().map(|line| line.to_string()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn write_log(dir: &Path, line_count: usize) -> PathBuf {
+        let path = dir.join("SimsForge.log");
+        let content: String = (1..=line_count)
+            .map(|i| format!("[2026-01-01][12:00:00][simsforge][INFO] line {}\n", i))
+            .collect();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn returns_last_lines_in_order() {
+        let dir = tempdir().unwrap();
+        let path = write_log(dir.path(), 5000);
+
+        let lines = read_last_lines(&path, 3).unwrap();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with("line 4998"));
+        assert!(lines[2].ends_with("line 5000"));
+    }
+
+    #[test]
+    fn returns_whole_file_when_shorter_than_requested() {
+        let dir = tempdir().unwrap();
+        let path = write_log(dir.path(), 4);
+
+        let lines = read_last_lines(&path, 100).unwrap();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].ends_with("line 1"));
+    }
+
+    #[test]
+    fn handles_missing_trailing_newline_and_zero_lines() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("SimsForge.log");
+        fs::write(&path, "first\nsecond\nthird").unwrap();
+
+        assert_eq!(read_last_lines(&path, 2).unwrap(), vec!["second", "third"]);
+        assert!(read_last_lines(&path, 0).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,736 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::Manager;
+
+/// File name of the install manifest database in the app data directory
+const MANIFEST_FILE_NAME: &str = "manifest.db";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS installs (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        version TEXT,
+        installed_at INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS install_files (
+        install_id TEXT NOT NULL REFERENCES installs(id) ON DELETE CASCADE,
+        path TEXT NOT NULL,
+        hash TEXT,
+        PRIMARY KEY (install_id, path)
+    );
+    CREATE TABLE IF NOT EXISTS install_tags (
+        install_id TEXT NOT NULL REFERENCES installs(id) ON DELETE CASCADE,
+        tag TEXT NOT NULL COLLATE NOCASE,
+        PRIMARY KEY (install_id, tag)
+    );
+    CREATE INDEX IF NOT EXISTS install_tags_by_tag ON install_tags(tag);
+    CREATE TABLE IF NOT EXISTS install_notes (
+        install_id TEXT PRIMARY KEY REFERENCES installs(id) ON DELETE CASCADE,
+        note TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS seen_archives (
+        fingerprint TEXT PRIMARY KEY,
+        path TEXT NOT NULL,
+        first_seen INTEGER NOT NULL
+    );
+";
+
+/// File (or folder/link) written to disk by an install
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InstalledFile {
+    /// Absolute path of the installed file, folder or link
+    pub path: String,
+    /// SHA-256 of the file content when known
+    pub hash: Option<String>,
+}
+
+/// Manifest record of one installed mod
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InstallRecord {
+    pub id: String,
+    pub name: String,
+    pub version: Option<String>,
+    /// Unix timestamp (seconds) of the install
+    pub installed_at: i64,
+    pub files: Vec<InstalledFile>,
+    /// User-defined labels, managed with `set_mod_tags` (ignored by `upsert_install`)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Free-form user note, managed with `set_mod_note` (ignored by `upsert_install`)
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// Archive recorded the first time its fingerprint was seen
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SeenArchive {
+    pub fingerprint: String,
+    /// Where the archive was when it was first seen
+    pub path: String,
+    /// Unix timestamp (seconds)
+    pub first_seen: i64,
+}
+
+/// Outcome of uninstalling a single mod
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UninstallResult {
+    pub id: String,
+    pub success: bool,
+    /// Paths that were removed from disk
+    pub removed_paths: Vec<String>,
+    /// Paths that were already gone
+    pub missing_paths: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// SQLite-backed record of what each install wrote to disk
+pub struct Manifest {
+    conn: Connection,
+}
+
+impl Manifest {
+    /// Open (or create) a manifest database at `path`
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let conn = Connection::open(path)
+            .map_err(|e| format!("Failed to open manifest {}: {}", path.display(), e))?;
+        Self::from_connection(conn)
+    }
+
+    /// Open a throwaway in-memory manifest
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, String> {
+        Self::from_connection(Connection::open_in_memory().map_err(|e| e.to_string())?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self, String> {
+        conn.execute_batch("PRAGMA foreign_keys = ON;")
+            .and_then(|_| conn.execute_batch(SCHEMA))
+            .map_err(|e| format!("Failed to initialize manifest: {}", e))?;
+        Ok(Self { conn })
+    }
+
+    /// Insert or replace an install record and its files
+    pub fn upsert_install(&mut self, record: &InstallRecord) -> Result<(), String> {
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("Failed to start manifest transaction: {}", e))?;
+
+        tx.execute(
+            "DELETE FROM install_files WHERE install_id = ?1",
+            [&record.id],
+        )
+        .and_then(|_| {
+            tx.execute(
+                "INSERT INTO installs (id, name, version, installed_at) VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT(id) DO UPDATE SET
+                        name = excluded.name,
+                        version = excluded.version,
+                        installed_at = excluded.installed_at",
+                params![record.id, record.name, record.version, record.installed_at],
+            )
+        })
+        .map_err(|e| format!("Failed to save install {}: {}", record.id, e))?;
+
+        for file in &record.files {
+            tx.execute(
+                "INSERT INTO install_files (install_id, path, hash) VALUES (?1, ?2, ?3)",
+                params![record.id, file.path, file.hash],
+            )
+            .map_err(|e| format!("Failed to save install file {}: {}", file.path, e))?;
+        }
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit manifest transaction: {}", e))
+    }
+
+    /// Get a single install record
+    pub fn get_install(&self, id: &str) -> Result<Option<InstallRecord>, String> {
+        let record = self
+            .conn
+            .query_row(
+                "SELECT id, name, version, installed_at FROM installs WHERE id = ?1",
+                [id],
+                |row| {
+                    Ok(InstallRecord {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        version: row.get(2)?,
+                        installed_at: row.get(3)?,
+                        files: Vec::new(),
+                        tags: Vec::new(),
+                        note: None,
+                    })
+                },
+            )
+            .optional()
+            .map_err(|e| format!("Failed to read install {}: {}", id, e))?;
+
+        match record {
+            Some(mut record) => {
+                record.files = self.install_files(id)?;
+                record.tags = self.install_tags(id)?;
+                record.note = self.install_note(id)?;
+                Ok(Some(record))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// List all install records, sorted by name
+    pub fn list_installs(&self) -> Result<Vec<InstallRecord>, String> {
+        let ids: Vec<String> = self
+            .conn
+            .prepare("SELECT id FROM installs ORDER BY name COLLATE NOCASE, id")
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| row.get(0))?
+                    .collect::<Result<Vec<String>, _>>()
+            })
+            .map_err(|e| format!("Failed to list installs: {}", e))?;
+
+        ids.iter()
+            .filter_map(|id| self.get_install(id).transpose())
+            .collect()
+    }
+
+    /// List the installs carrying `tag` (case-insensitive), sorted by name
+    pub fn list_installs_by_tag(&self, tag: &str) -> Result<Vec<InstallRecord>, String> {
+        let ids: Vec<String> = self
+            .conn
+            .prepare(
+                "SELECT installs.id FROM installs
+                     JOIN install_tags ON install_tags.install_id = installs.id
+                     WHERE install_tags.tag = ?1
+                     ORDER BY installs.name COLLATE NOCASE, installs.id",
+            )
+            .and_then(|mut stmt| {
+                stmt.query_map([tag.trim()], |row| row.get(0))?
+                    .collect::<Result<Vec<String>, _>>()
+            })
+            .map_err(|e| format!("Failed to list installs tagged {}: {}", tag, e))?;
+
+        ids.iter()
+            .filter_map(|id| self.get_install(id).transpose())
+            .collect()
+    }
+
+    /// Replace the tags of an install
+    /// Tags are trimmed, empty ones dropped and duplicates (ignoring case) collapsed
+    pub fn set_tags(&mut self, id: &str, tags: &[String]) -> Result<(), String> {
+        self.ensure_install_exists(id)?;
+
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("Failed to start manifest transaction: {}", e))?;
+
+        tx.execute("DELETE FROM install_tags WHERE install_id = ?1", [id])
+            .map_err(|e| format!("Failed to clear tags of install {}: {}", id, e))?;
+
+        for tag in tags
+            .iter()
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty())
+        {
+            tx.execute(
+                "INSERT OR IGNORE INTO install_tags (install_id, tag) VALUES (?1, ?2)",
+                params![id, tag],
+            )
+            .map_err(|e| format!("Failed to save tag {}: {}", tag, e))?;
+        }
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit manifest transaction: {}", e))
+    }
+
+    /// Set or clear (None / blank) the note of an install
+    pub fn set_note(&self, id: &str, note: Option<&str>) -> Result<(), String> {
+        self.ensure_install_exists(id)?;
+
+        let result = match note.map(str::trim).filter(|note| !note.is_empty()) {
+            Some(note) => self.conn.execute(
+                "INSERT INTO install_notes (install_id, note) VALUES (?1, ?2)
+                     ON CONFLICT(install_id) DO UPDATE SET note = excluded.note",
+                params![id, note],
+            ),
+            None => self
+                .conn
+                .execute("DELETE FROM install_notes WHERE install_id = ?1", [id]),
+        };
+
+        result
+            .map(|_| ())
+            .map_err(|e| format!("Failed to save note of install {}: {}", id, e))
+    }
+
+    /// Hash recorded for an installed file, if any install tracked it with one
+    pub fn recorded_hash(&self, path: &str) -> Result<Option<String>, String> {
+        self.conn
+            .query_row(
+                "SELECT hash FROM install_files WHERE path = ?1 AND hash IS NOT NULL LIMIT 1",
+                [path],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to read hash of {}: {}", path, e))
+    }
+
+    /// Record a new hash for a file tracked by one or more installs
+    pub fn update_file_hash(&self, path: &str, hash: &str) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE install_files SET hash = ?2 WHERE path = ?1",
+                params![path, hash],
+            )
+            .map(|_| ())
+            .map_err(|e| format!("Failed to update hash of {}: {}", path, e))
+    }
+
+    /// Point manifest rows at new locations after files were moved, all or nothing
+    pub fn rename_paths(&mut self, renames: &[(String, String)]) -> Result<(), String> {
+        let tx = self
+            .conn
+            .transaction()
+            .map_err(|e| format!("Failed to start manifest transaction: {}", e))?;
+
+        for (from, to) in renames {
+            tx.execute(
+                "UPDATE install_files SET path = ?2 WHERE path = ?1",
+                params![from, to],
+            )
+            .map_err(|e| format!("Failed to update path {}: {}", from, e))?;
+        }
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit manifest transaction: {}", e))
+    }
+
+    /// Delete an install record (its files rows are removed by cascade)
+    pub fn remove_install(&self, id: &str) -> Result<(), String> {
+        self.conn
+            .execute("DELETE FROM installs WHERE id = ?1", [id])
+            .map(|_| ())
+            .map_err(|e| format!("Failed to remove install {}: {}", id, e))
+    }
+
+    /// Remember an archive fingerprint, returning the earlier sighting if it was already known
+    pub fn record_archive(
+        &self,
+        fingerprint: &str,
+        path: &str,
+    ) -> Result<Option<SeenArchive>, String> {
+        let previous = self
+            .conn
+            .query_row(
+                "SELECT fingerprint, path, first_seen FROM seen_archives WHERE fingerprint = ?1",
+                [fingerprint],
+                |row| {
+                    Ok(SeenArchive {
+                        fingerprint: row.get(0)?,
+                        path: row.get(1)?,
+                        first_seen: row.get(2)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(|e| format!("Failed to read archive {}: {}", fingerprint, e))?;
+
+        if previous.is_none() {
+            self.conn
+                .execute(
+                    "INSERT INTO seen_archives (fingerprint, path, first_seen) VALUES (?1, ?2, ?3)",
+                    params![fingerprint, path, unix_now()],
+                )
+                .map_err(|e| format!("Failed to record archive {}: {}", fingerprint, e))?;
+        }
+
+        Ok(previous)
+    }
+
+    fn install_files(&self, id: &str) -> Result<Vec<InstalledFile>, String> {
+        self.conn
+            .prepare("SELECT path, hash FROM install_files WHERE install_id = ?1 ORDER BY path")
+            .and_then(|mut stmt| {
+                stmt.query_map([id], |row| {
+                    Ok(InstalledFile {
+                        path: row.get(0)?,
+                        hash: row.get(1)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|e| format!("Failed to read files of install {}: {}", id, e))
+    }
+
+    fn install_tags(&self, id: &str) -> Result<Vec<String>, String> {
+        self.conn
+            .prepare("SELECT tag FROM install_tags WHERE install_id = ?1 ORDER BY tag")
+            .and_then(|mut stmt| {
+                stmt.query_map([id], |row| row.get(0))?
+                    .collect::<Result<Vec<String>, _>>()
+            })
+            .map_err(|e| format!("Failed to read tags of install {}: {}", id, e))
+    }
+
+    fn install_note(&self, id: &str) -> Result<Option<String>, String> {
+        self.conn
+            .query_row(
+                "SELECT note FROM install_notes WHERE install_id = ?1",
+                [id],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to read note of install {}: {}", id, e))
+    }
+
+    fn ensure_install_exists(&self, id: &str) -> Result<(), String> {
+        let exists: bool = self
+            .conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM installs WHERE id = ?1)",
+                [id],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to read install {}: {}", id, e))?;
+
+        if exists {
+            Ok(())
+        } else {
+            Err(format!("Install not found: {}", id))
+        }
+    }
+}
+
+/// Open the app's manifest database
+pub fn open_app_manifest(app_handle: &tauri::AppHandle) -> Result<Manifest, String> {
+    let app_data_dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    std::fs::create_dir_all(&app_data_dir)
+        .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+    Manifest::open(&app_data_dir.join(MANIFEST_FILE_NAME))
+}
+
+/// Current time as a Unix timestamp in seconds
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Record an install in the manifest
+/// `installed_at` is filled in when left at 0
+#[tauri::command]
+pub fn register_install(
+    app_handle: tauri::AppHandle,
+    mut record: InstallRecord,
+) -> Result<(), String> {
+    if record.installed_at == 0 {
+        record.installed_at = unix_now();
+    }
+    open_app_manifest(&app_handle)?.upsert_install(&record)
+}
+
+/// List all installs recorded in the manifest
+#[tauri::command]
+pub fn list_installs(app_handle: tauri::AppHandle) -> Result<Vec<InstallRecord>, String> {
+    open_app_manifest(&app_handle)?.list_installs()
+}
+
+/// List the installs carrying a tag (case-insensitive)
+#[tauri::command]
+pub fn list_installs_by_tag(
+    app_handle: tauri::AppHandle,
+    tag: String,
+) -> Result<Vec<InstallRecord>, String> {
+    open_app_manifest(&app_handle)?.list_installs_by_tag(&tag)
+}
+
+/// Replace the tags of an installed mod
+#[tauri::command]
+pub fn set_mod_tags(
+    app_handle: tauri::AppHandle,
+    id: String,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    open_app_manifest(&app_handle)?.set_tags(&id, &tags)
+}
+
+/// Set the note of an installed mod, an empty note removes it
+#[tauri::command]
+pub fn set_mod_note(
+    app_handle: tauri::AppHandle,
+    id: String,
+    note: Option<String>,
+) -> Result<(), String> {
+    open_app_manifest(&app_handle)?.set_note(&id, note.as_deref())
+}
+
+/// Uninstall several mods from their manifest records
+/// Each mod's files are moved to the trash (unless `use_trash` is false) and its record removed
+/// Failures are reported per mod and never abort the rest of the batch
+#[tauri::command(async)]
+pub fn uninstall_mods(
+    app_handle: tauri::AppHandle,
+    ids: Vec<String>,
+    use_trash: Option<bool>,
+) -> Result<Vec<UninstallResult>, String> {
+    let manifest = open_app_manifest(&app_handle)?;
+    Ok(uninstall_from_manifest(
+        &manifest,
+        &ids,
+        use_trash.unwrap_or(true),
+    ))
+}
+
+fn uninstall_from_manifest(
+    manifest: &Manifest,
+    ids: &[String],
+    use_trash: bool,
+) -> Vec<UninstallResult> {
+    ids.iter()
+        .map(|id| {
+            let mut result = UninstallResult {
+                id: id.clone(),
+                success: false,
+                removed_paths: Vec::new(),
+                missing_paths: Vec::new(),
+                error: None,
+            };
+
+            let outcome = manifest.get_install(id).and_then(|record| {
+                let record = record.ok_or_else(|| "Not found in manifest".to_string())?;
+
+                for file in &record.files {
+                    let path = Path::new(&file.path);
+                    // is_symlink catches dangling links whose target is already gone
+                    if !path.exists() && !path.is_symlink() {
+                        result.missing_paths.push(file.path.clone());
+                        continue;
+                    }
+                    remove_path(path, use_trash)?;
+                    result.removed_paths.push(file.path.clone());
+                }
+
+                // Only forget the install once everything it wrote is gone
+                manifest.remove_install(id)
+            });
+
+            match outcome {
+                Ok(()) => result.success = true,
+                Err(e) => result.error = Some(e),
+            }
+            result
+        })
+        .collect()
+}
+
+/// Remove a file, folder or link, via the trash when requested
+/// Links are always removed directly so the trash never follows them into their target
+pub fn remove_path(path: &Path, use_trash: bool) -> Result<(), String> {
+    let outcome = if path.is_symlink() {
+        #[cfg(target_os = "windows")]
+        let removed = std::fs::remove_dir(path).or_else(|_| std::fs::remove_file(path));
+        #[cfg(not(target_os = "windows"))]
+        let removed = std::fs::remove_file(path);
+        removed.map_err(|e| e.to_string())
+    } else if use_trash {
+        trash::delete(path).map_err(|e| e.to_string())
+    } else if path.is_dir() {
+        std::fs::remove_dir_all(path).map_err(|e| e.to_string())
+    } else {
+        std::fs::remove_file(path).map_err(|e| e.to_string())
+    };
+
+    outcome.map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn record_archive_reports_earlier_sighting() {
+        let manifest = Manifest::open_in_memory().unwrap();
+
+        assert_eq!(manifest.record_archive("abc", "first.zip").unwrap(), None);
+        let seen = manifest
+            .record_archive("abc", "second.zip")
+            .unwrap()
+            .unwrap();
+        assert_eq!(seen.path, "first.zip");
+        assert!(seen.first_seen > 0);
+        assert_eq!(manifest.record_archive("def", "other.zip").unwrap(), None);
+    }
+
+    fn record(id: &str, files: Vec<String>) -> InstallRecord {
+        InstallRecord {
+            id: id.to_string(),
+            name: format!("Mod {}", id),
+            version: Some("1.0".to_string()),
+            installed_at: 1_700_000_000,
+            files: files
+                .into_iter()
+                .map(|path| InstalledFile { path, hash: None })
+                .collect(),
+            tags: Vec::new(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn stores_and_lists_installs() {
+        let dir = tempdir().unwrap();
+        let mut manifest = Manifest::open(&dir.path().join(MANIFEST_FILE_NAME)).unwrap();
+        let first = record("b", vec!["/mods/b.package".to_string()]);
+        manifest.upsert_install(&first).unwrap();
+        manifest.upsert_install(&record("a", Vec::new())).unwrap();
+
+        // Re-registering replaces the file list instead of appending to it
+        let mut updated = first.clone();
+        updated.files[0].path = "/mods/b2.package".to_string();
+        manifest.upsert_install(&updated).unwrap();
+
+        let reopened = Manifest::open(&dir.path().join(MANIFEST_FILE_NAME)).unwrap();
+        let installs = reopened.list_installs().unwrap();
+        assert_eq!(installs.len(), 2);
+        assert_eq!(installs[0].id, "a");
+        assert_eq!(installs[1], updated);
+    }
+
+    #[test]
+    fn filters_installs_by_tag() {
+        let mut manifest = Manifest::open_in_memory().unwrap();
+        for id in ["a", "b", "c"] {
+            manifest.upsert_install(&record(id, Vec::new())).unwrap();
+        }
+        manifest
+            .set_tags("a", &["NSFW".to_string(), " needs MCCC ".to_string()])
+            .unwrap();
+        manifest
+            .set_tags(
+                "c",
+                &["nsfw".to_string(), "NSFW".to_string(), "".to_string()],
+            )
+            .unwrap();
+
+        let tagged: Vec<String> = manifest
+            .list_installs_by_tag("Nsfw")
+            .unwrap()
+            .into_iter()
+            .map(|install| install.id)
+            .collect();
+        assert_eq!(tagged, vec!["a", "c"]);
+        assert_eq!(
+            manifest.get_install("a").unwrap().unwrap().tags,
+            vec!["needs MCCC", "NSFW"]
+        );
+        assert_eq!(
+            manifest.get_install("c").unwrap().unwrap().tags,
+            vec!["nsfw"]
+        );
+
+        // Replacing the tag set drops the old tags
+        manifest.set_tags("a", &["needs MCCC".to_string()]).unwrap();
+        assert_eq!(manifest.list_installs_by_tag("nsfw").unwrap().len(), 1);
+        assert_eq!(
+            manifest.list_installs_by_tag("needs mccc").unwrap()[0].id,
+            "a"
+        );
+
+        assert!(manifest.set_tags("missing", &[]).is_err());
+    }
+
+    #[test]
+    fn persists_notes_across_reinstalls() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(MANIFEST_FILE_NAME);
+        let mut manifest = Manifest::open(&path).unwrap();
+        manifest.upsert_install(&record("a", Vec::new())).unwrap();
+        manifest.set_note("a", Some("conflicts with X")).unwrap();
+        manifest.set_tags("a", &["tuning".to_string()]).unwrap();
+
+        // Updating the mod keeps the user's annotations
+        let mut updated = record("a", vec!["/mods/a.package".to_string()]);
+        updated.version = Some("2.0".to_string());
+        manifest.upsert_install(&updated).unwrap();
+
+        let reopened = Manifest::open(&path).unwrap();
+        let install = reopened.get_install("a").unwrap().unwrap();
+        assert_eq!(install.note.as_deref(), Some("conflicts with X"));
+        assert_eq!(install.tags, vec!["tuning"]);
+        assert_eq!(install.version.as_deref(), Some("2.0"));
+
+        reopened.set_note("a", Some("   ")).unwrap();
+        assert_eq!(reopened.get_install("a").unwrap().unwrap().note, None);
+
+        // Uninstalling removes the annotations with the record
+        reopened.set_note("a", Some("temp")).unwrap();
+        reopened.remove_install("a").unwrap();
+        assert!(reopened.set_note("a", Some("x")).is_err());
+        assert!(reopened.list_installs_by_tag("tuning").unwrap().is_empty());
+    }
+
+    #[test]
+    fn uninstalls_mixed_present_and_missing_files() {
+        let dir = tempdir().unwrap();
+        let mods = dir.path().join("Mods");
+        fs::create_dir_all(mods.join("Folder")).unwrap();
+        fs::write(mods.join("present.package"), b"DBPF").unwrap();
+        fs::write(mods.join("Folder/inner.package"), b"DBPF").unwrap();
+        let path = |name: &str| mods.join(name).to_string_lossy().to_string();
+
+        let mut manifest = Manifest::open_in_memory().unwrap();
+        manifest
+            .upsert_install(&record(
+                "mixed",
+                vec![path("present.package"), path("gone.package")],
+            ))
+            .unwrap();
+        manifest
+            .upsert_install(&record("folder", vec![path("Folder")]))
+            .unwrap();
+
+        let ids = vec![
+            "mixed".to_string(),
+            "unknown".to_string(),
+            "folder".to_string(),
+        ];
+        let results = uninstall_from_manifest(&manifest, &ids, false);
+
+        assert!(results[0].success);
+        assert_eq!(results[0].removed_paths, vec![path("present.package")]);
+        assert_eq!(results[0].missing_paths, vec![path("gone.package")]);
+        assert!(!results[1].success);
+        assert!(results[1].error.as_deref().unwrap().contains("Not found"));
+        assert!(results[2].success);
+
+        assert!(!mods.join("present.package").exists());
+        assert!(!mods.join("Folder").exists());
+        assert!(manifest.list_installs().unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn removes_links_without_touching_their_target() {
+        let dir = tempdir().unwrap();
+        let target = dir.path().join("cache");
+        fs::create_dir(&target).unwrap();
+        fs::write(target.join("mod.package"), b"DBPF").unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        remove_path(&link, true).unwrap();
+
+        assert!(!link.is_symlink());
+        assert!(target.join("mod.package").exists());
+    }
+}
@@ -0,0 +1,195 @@
+use crate::dbpf::{self, IndexEntry, PackageWriter, ResourceKey};
+use crate::replace::{same_file, temp_path_for};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+/// Summary of a merge
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MergeReport {
+    pub packages_merged: usize,
+    pub resources_written: usize,
+    /// Resources dropped because their type was excluded
+    pub excluded: usize,
+    /// Resources dropped because a later package had the same key
+    pub duplicates_replaced: usize,
+    pub output_bytes: u64,
+}
+
+/// Merge packages into one to cut the game's load time
+/// On duplicate keys the package listed last wins, like the game's own load order;
+/// resources whose type is in `exclude_types` (e.g. thumbnails) are left out
+/// Resources are copied as stored, compressed ones are not recompressed
+#[tauri::command(async)]
+pub fn merge_packages(
+    packages: Vec<String>,
+    output: String,
+    exclude_types: Option<Vec<u32>>,
+) -> Result<MergeReport, String> {
+    let packages: Vec<PathBuf> = packages.iter().map(PathBuf::from).collect();
+    merge(
+        &packages,
+        Path::new(&output),
+        &exclude_types.unwrap_or_default(),
+    )
+}
+
+pub fn merge(
+    packages: &[PathBuf],
+    output: &Path,
+    exclude_types: &[u32],
+) -> Result<MergeReport, String> {
+    if packages.iter().any(|package| same_file(package, output)) {
+        return Err("Output must be a different file than the merged packages".to_string());
+    }
+
+    // Key -> (package index, entry), keys kept in the order they first appear
+    let mut order: Vec<ResourceKey> = Vec::new();
+    let mut chosen: HashMap<ResourceKey, (usize, IndexEntry)> = HashMap::new();
+    let mut excluded = 0;
+    let mut duplicates_replaced = 0;
+
+    for (package_index, package) in packages.iter().enumerate() {
+        for entry in dbpf::read_index(package)? {
+            if exclude_types.contains(&entry.key.type_id) {
+                excluded += 1;
+                continue;
+            }
+            match chosen.insert(entry.key, (package_index, entry.clone())) {
+                Some(_) => duplicates_replaced += 1,
+                None => order.push(entry.key),
+            }
+        }
+    }
+
+    let temp = temp_path_for(output)?;
+    let written = write_merged(packages, &order, &chosen, &temp).and_then(|_| {
+        fs::rename(&temp, output)
+            .map_err(|e| format!("Failed to write {}: {}", output.display(), e))
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    Ok(MergeReport {
+        packages_merged: packages.len(),
+        resources_written: order.len(),
+        excluded,
+        duplicates_replaced,
+        output_bytes: fs::metadata(output).map(|m| m.len()).unwrap_or(0),
+    })
+}
+
+fn write_merged(
+    packages: &[PathBuf],
+    order: &[ResourceKey],
+    chosen: &HashMap<ResourceKey, (usize, IndexEntry)>,
+    dest: &Path,
+) -> Result<(), String> {
+    let mut readers = packages
+        .iter()
+        .map(|path| {
+            File::open(path)
+                .map(BufReader::new)
+                .map_err(|e| format!("Failed to open package {}: {}", path.display(), e))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let file =
+        File::create(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut writer = PackageWriter::new(BufWriter::new(file))?;
+
+    for key in order {
+        let (package_index, entry) = &chosen[key];
+        let raw = dbpf::read_raw_resource(&mut readers[*package_index], entry)?;
+        writer.add_raw(entry, &raw)?;
+    }
+
+    writer
+        .finish()?
+        .into_inner()
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e.error()))?
+        .sync_all()
+        .map_err(|e| format!("Failed to sync {}: {}", dest.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbpf::test_support::{build_package, build_zlib_package, key};
+    use tempfile::tempdir;
+
+    const THUMBNAIL_TYPE: u32 = 0x3C1AF1F2;
+
+    fn resources(path: &Path) -> HashMap<ResourceKey, Vec<u8>> {
+        let mut reader = BufReader::new(File::open(path).unwrap());
+        dbpf::read_index(path)
+            .unwrap()
+            .iter()
+            .map(|entry| (entry.key, dbpf::read_resource(&mut reader, entry).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn merges_while_excluding_types() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("hair.package");
+        let second = dir.path().join("top.package");
+        fs::write(
+            &first,
+            build_zlib_package(&[
+                (key(0x034AEECB, 0, 1), b"hair caspart".to_vec()),
+                (key(THUMBNAIL_TYPE, 0, 1), b"hair thumbnail".to_vec()),
+                (key(0x0333406C, 0, 7), b"old tuning".to_vec()),
+            ]),
+        )
+        .unwrap();
+        fs::write(
+            &second,
+            build_package(&[
+                (key(0x034AEECB, 0, 2), b"top caspart".to_vec()),
+                (key(THUMBNAIL_TYPE, 0, 2), b"top thumbnail".to_vec()),
+                (key(0x0333406C, 0, 7), b"new tuning".to_vec()),
+            ]),
+        )
+        .unwrap();
+        let output = dir.path().join("merged.package");
+
+        let report = merge_packages(
+            vec![
+                first.to_string_lossy().to_string(),
+                second.to_string_lossy().to_string(),
+            ],
+            output.to_string_lossy().to_string(),
+            Some(vec![THUMBNAIL_TYPE]),
+        )
+        .unwrap();
+
+        assert_eq!(report.packages_merged, 2);
+        assert_eq!(report.resources_written, 3);
+        assert_eq!(report.excluded, 2);
+        assert_eq!(report.duplicates_replaced, 1);
+        assert_eq!(report.output_bytes, fs::metadata(&output).unwrap().len());
+
+        let merged = resources(&output);
+        assert_eq!(merged.len(), 3);
+        assert!(merged.keys().all(|key| key.type_id != THUMBNAIL_TYPE));
+        // Compressed resources survive the copy, the later package wins on duplicates
+        assert_eq!(merged[&key(0x034AEECB, 0, 1)], b"hair caspart");
+        assert_eq!(merged[&key(0x034AEECB, 0, 2)], b"top caspart");
+        assert_eq!(merged[&key(0x0333406C, 0, 7)], b"new tuning");
+    }
+
+    #[test]
+    fn refuses_to_overwrite_an_input() {
+        let dir = tempdir().unwrap();
+        let package = dir.path().join("a.package");
+        fs::write(&package, build_package(&[(key(1, 0, 1), b"x".to_vec())])).unwrap();
+
+        assert!(merge(std::slice::from_ref(&package), &package, &[]).is_err());
+        assert_eq!(resources(&package).len(), 1);
+    }
+}
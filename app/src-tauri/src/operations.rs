@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Error message returned by long-running commands that were cancelled
+pub const CANCELLED_ERROR: &str = "Operation cancelled";
+
+/// Shared flag checked by long-running operations between units of work
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Request cancellation of the operation holding this token
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether cancellation has been requested
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Registry of running cancellable operations, keyed by a frontend-provided operation ID
+/// Managed as Tauri state so any command can register itself and `cancel_operation` can reach it
+#[derive(Default)]
+pub struct OperationRegistry {
+    operations: Mutex<HashMap<String, CancellationToken>>,
+}
+
+impl OperationRegistry {
+    /// Register an operation and get a guard that unregisters it when dropped
+    pub fn start(&self, operation_id: &str) -> OperationGuard<'_> {
+        let token = CancellationToken::default();
+        self.operations
+            .lock()
+            .unwrap()
+            .insert(operation_id.to_string(), token.clone());
+
+        OperationGuard {
+            registry: self,
+            operation_id: operation_id.to_string(),
+            token,
+        }
+    }
+
+    /// Flag an operation as cancelled
+    /// Returns false if no operation with this ID is running
+    pub fn cancel(&self, operation_id: &str) -> bool {
+        match self.operations.lock().unwrap().get(operation_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Keeps an operation registered for as long as it is alive
+pub struct OperationGuard<'a> {
+    registry: &'a OperationRegistry,
+    operation_id: String,
+    token: CancellationToken,
+}
+
+impl OperationGuard<'_> {
+    /// Token to pass down to the worker functions
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+}
+
+impl Drop for OperationGuard<'_> {
+    fn drop(&mut self) {
+        self.registry
+            .operations
+            .lock()
+            .unwrap()
+            .remove(&self.operation_id);
+    }
+}
+
+/// Cancel a running operation by its ID
+/// Returns whether a matching operation was found
+#[tauri::command]
+pub fn cancel_operation(
+    registry: tauri::State<'_, OperationRegistry>,
+    operation_id: String,
+) -> bool {
+    registry.cancel(&operation_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_reaches_registered_operation_only_while_running() {
+        let registry = OperationRegistry::default();
+
+        {
+            let guard = registry.start("scan-1");
+            assert!(!guard.token().is_cancelled());
+            assert!(registry.cancel("scan-1"));
+            assert!(guard.token().is_cancelled());
+        }
+
+        assert!(!registry.cancel("scan-1"));
+        assert!(!registry.cancel("unknown"));
+    }
+}
@@ -0,0 +1,459 @@
+use crate::classify::{self, ModCategory};
+use crate::manifest::{self, Manifest};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// The game ignores packages nested deeper than this many folders inside Mods
+const MAX_PACKAGE_DEPTH: usize = 5;
+
+/// Folder levels kept free below a recommended depth, for mods that ship their own subfolder
+const INSTALL_DEPTH_HEADROOM: usize = 1;
+
+/// Which files get moved into category folders
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrganizeStrategy {
+    /// Only files lying directly in the Mods folder
+    #[default]
+    LooseFiles,
+    /// Every file, keeping its current folder name inside the category folder
+    Everything,
+}
+
+/// One file move of an organize plan
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PlannedMove {
+    pub from: String,
+    pub to: String,
+    pub category: ModCategory,
+}
+
+/// Moves planned (dry run) or performed by `organize_library`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OrganizeResult {
+    pub moves: Vec<PlannedMove>,
+    /// Whether the moves were carried out
+    pub applied: bool,
+}
+
+/// How deep new mods should be installed, derived from the existing Mods tree
+/// Depths count the folders between Mods and a package (`Mods/CAS/hair.package` is 1)
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct InstallDepthRecommendation {
+    /// Deepest package currently in the tree
+    pub current_max_depth: usize,
+    /// Depth at which new mod files should be placed
+    pub recommended_depth: u8,
+    /// Deepest level the game still loads packages from
+    pub max_loadable_depth: usize,
+    /// Packages already nested too deep to load, `organize_library` can move them up
+    pub unloadable_files: usize,
+}
+
+/// Suggest an install depth that follows the library's existing organization
+/// while leaving room below it before the game's nesting limit
+#[tauri::command(async)]
+pub fn recommend_install_depth(mods_dir: String) -> Result<InstallDepthRecommendation, String> {
+    recommend_depth(Path::new(&mods_dir))
+}
+
+pub fn recommend_depth(mods_dir: &Path) -> Result<InstallDepthRecommendation, String> {
+    if !mods_dir.is_dir() {
+        return Err(format!("Directory not found: {}", mods_dir.display()));
+    }
+
+    // Depth -> number of packages, links to installed mods count at the depth of the link
+    let mut depths: BTreeMap<usize, usize> = BTreeMap::new();
+    for entry in WalkDir::new(mods_dir)
+        .min_depth(1)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+    {
+        let is_package = entry
+            .path()
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("package"))
+            .unwrap_or(false);
+        if is_package && !entry.file_type().is_dir() {
+            *depths.entry(entry.depth() - 1).or_default() += 1;
+        }
+    }
+
+    let current_max_depth = depths.keys().next_back().copied().unwrap_or(0);
+    let unloadable_files = depths.range(MAX_PACKAGE_DEPTH + 1..).map(|(_, n)| n).sum();
+    // Most common loadable depth, the shallower one on ties
+    let typical_depth = depths
+        .range(..=MAX_PACKAGE_DEPTH)
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+        .map(|(&depth, _)| depth)
+        .unwrap_or(0);
+
+    // Loose files in Mods get a folder of their own, deep trees are pulled back under the limit
+    let recommended = typical_depth.clamp(1, MAX_PACKAGE_DEPTH - INSTALL_DEPTH_HEADROOM);
+
+    Ok(InstallDepthRecommendation {
+        current_max_depth,
+        recommended_depth: recommended as u8,
+        max_loadable_depth: MAX_PACKAGE_DEPTH,
+        unloadable_files,
+    })
+}
+
+/// Sort mod files into CAS / BuildBuy / Scripts / Tuning folders
+/// With `dry_run` only the planned moves are returned, otherwise the files are moved and the
+/// manifest updated; a failure part-way moves everything back
+#[tauri::command(async)]
+pub fn organize_library(
+    app_handle: tauri::AppHandle,
+    mods_dir: String,
+    strategy: Option<OrganizeStrategy>,
+    dry_run: bool,
+) -> Result<OrganizeResult, String> {
+    let mods_dir = Path::new(&mods_dir);
+    let moves = plan_moves(mods_dir, strategy.unwrap_or_default())?;
+
+    if dry_run {
+        return Ok(OrganizeResult {
+            moves,
+            applied: false,
+        });
+    }
+
+    let mut manifest = manifest::open_app_manifest(&app_handle)?;
+    apply_moves(&moves, &mut manifest)?;
+    Ok(OrganizeResult {
+        moves,
+        applied: true,
+    })
+}
+
+pub fn plan_moves(mods_dir: &Path, strategy: OrganizeStrategy) -> Result<Vec<PlannedMove>, String> {
+    if !mods_dir.is_dir() {
+        return Err(format!("Directory not found: {}", mods_dir.display()));
+    }
+
+    let max_depth = match strategy {
+        OrganizeStrategy::LooseFiles => 1,
+        OrganizeStrategy::Everything => usize::MAX,
+    };
+    let category_folders: Vec<&str> = [
+        ModCategory::Cas,
+        ModCategory::BuildBuy,
+        ModCategory::Scripts,
+        ModCategory::Tuning,
+    ]
+    .iter()
+    .map(|category| category.folder_name())
+    .collect();
+
+    // Links (installed mods) are never followed nor moved
+    let files: Vec<PathBuf> = WalkDir::new(mods_dir)
+        .max_depth(max_depth)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() != 1
+                || !entry.file_type().is_dir()
+                || !category_folders
+                    .iter()
+                    .any(|folder| entry.file_name().eq_ignore_ascii_case(folder))
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+
+    let mut taken: HashSet<PathBuf> = HashSet::new();
+    let mut moves = Vec::new();
+
+    for file in files {
+        let category = classify::classify_file(&file);
+        if category == ModCategory::Other {
+            continue;
+        }
+
+        let relative = file.strip_prefix(mods_dir).unwrap_or(&file);
+        let file_name = file.file_name().unwrap_or_default();
+        let mut target_dir = mods_dir.join(category.folder_name());
+        // Scripts only load one folder deep, packages keep their folder for context
+        if category != ModCategory::Scripts {
+            if let Some(parent) = relative.parent().and_then(|p| p.file_name()) {
+                target_dir.push(parent);
+            }
+        }
+
+        let target = unique_target(&target_dir, Path::new(file_name), &taken);
+        let depth = target
+            .strip_prefix(mods_dir)
+            .map(|p| p.components().count() - 1);
+        if depth.unwrap_or(usize::MAX) > MAX_PACKAGE_DEPTH {
+            continue;
+        }
+
+        taken.insert(target.clone());
+        moves.push(PlannedMove {
+            from: file.to_string_lossy().to_string(),
+            to: target.to_string_lossy().to_string(),
+            category,
+        });
+    }
+
+    Ok(moves)
+}
+
+/// Destination for `file_name` in `dir` that neither exists nor is planned, adding " (n)" if needed
+fn unique_target(dir: &Path, file_name: &Path, taken: &HashSet<PathBuf>) -> PathBuf {
+    let candidate = dir.join(file_name);
+    if !candidate.exists() && !taken.contains(&candidate) {
+        return candidate;
+    }
+
+    let stem = file_name
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = file_name
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    (2..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, extension)))
+        .find(|candidate| !candidate.exists() && !taken.contains(candidate))
+        .unwrap()
+}
+
+/// Move the files, then update the manifest in one transaction
+/// Any failure moves the already-moved files back before returning the error
+pub fn apply_moves(moves: &[PlannedMove], manifest: &mut Manifest) -> Result<(), String> {
+    let mut done: Vec<&PlannedMove> = Vec::new();
+
+    let outcome = moves
+        .iter()
+        .try_for_each(|planned| {
+            let to = Path::new(&planned.to);
+            if let Some(parent) = to.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            fs::rename(&planned.from, to)
+                .map_err(|e| format!("Failed to move {}: {}", planned.from, e))?;
+            done.push(planned);
+            Ok(())
+        })
+        .and_then(|_| {
+            let renames: Vec<(String, String)> = moves
+                .iter()
+                .map(|planned| (planned.from.clone(), planned.to.clone()))
+                .collect();
+            manifest.rename_paths(&renames)
+        });
+
+    if outcome.is_err() {
+        for planned in done.iter().rev() {
+            let _ = fs::rename(&planned.to, &planned.from);
+        }
+    }
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbpf::test_support::{build_package, key};
+    use crate::manifest::{InstallRecord, InstalledFile};
+    use crate::test_support::write_zip;
+    use tempfile::tempdir;
+
+    fn write_package(path: &Path, type_id: u32) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, build_package(&[(key(type_id, 0, 1), b"x".to_vec())])).unwrap();
+    }
+
+    fn messy_mods_folder(mods: &Path) {
+        write_package(&mods.join("hair.package"), 0x034AEECB);
+        write_package(&mods.join("sofa.package"), 0xC0DB5AE7);
+        write_package(&mods.join("career.package"), 0x0333406C);
+        write_package(&mods.join("Creator/dress.package"), 0x034AEECB);
+        write_package(&mods.join("CAS/already.package"), 0x034AEECB);
+        write_zip(&mods.join("mccc.ts4script"), &[("mc.pyc", b"")]);
+        // An existing CAS/hair.package forces a renamed destination
+        write_package(&mods.join("CAS/hair.package"), 0x034AEECB);
+        fs::write(mods.join("readme.txt"), b"notes").unwrap();
+    }
+
+    fn relative_moves(mods: &Path, moves: &[PlannedMove]) -> Vec<(String, String)> {
+        let relative = |path: &str| {
+            Path::new(path)
+                .strip_prefix(mods)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/")
+        };
+        moves
+            .iter()
+            .map(|m| (relative(&m.from), relative(&m.to)))
+            .collect()
+    }
+
+    #[test]
+    fn places_files_by_category() {
+        let dir = tempdir().unwrap();
+        let mods = dir.path().join("Mods");
+        messy_mods_folder(&mods);
+
+        let moves = plan_moves(&mods, OrganizeStrategy::LooseFiles).unwrap();
+        assert_eq!(
+            relative_moves(&mods, &moves),
+            vec![
+                ("career.package".into(), "Tuning/career.package".into()),
+                ("hair.package".into(), "CAS/hair (2).package".into()),
+                ("mccc.ts4script".into(), "Scripts/mccc.ts4script".into()),
+                ("sofa.package".into(), "BuildBuy/sofa.package".into()),
+            ]
+        );
+        // Dry run: nothing moved
+        assert!(mods.join("hair.package").exists());
+
+        let moves = plan_moves(&mods, OrganizeStrategy::Everything).unwrap();
+        let relative = relative_moves(&mods, &moves);
+        assert!(relative.contains(&(
+            "Creator/dress.package".into(),
+            "CAS/Creator/dress.package".into()
+        )));
+        assert!(!relative.iter().any(|(from, _)| from.starts_with("CAS/")));
+    }
+
+    #[test]
+    fn moves_files_and_updates_manifest() {
+        let dir = tempdir().unwrap();
+        let mods = dir.path().join("Mods");
+        messy_mods_folder(&mods);
+        let hair = mods.join("hair.package").to_string_lossy().to_string();
+        let readme = mods.join("readme.txt").to_string_lossy().to_string();
+
+        let mut manifest = Manifest::open_in_memory().unwrap();
+        manifest
+            .upsert_install(&InstallRecord {
+                id: "hair".to_string(),
+                name: "Hair".to_string(),
+                version: None,
+                installed_at: 1,
+                files: vec![
+                    InstalledFile {
+                        path: hair.clone(),
+                        hash: Some("abc".to_string()),
+                    },
+                    InstalledFile {
+                        path: readme.clone(),
+                        hash: None,
+                    },
+                ],
+                tags: Vec::new(),
+                note: None,
+            })
+            .unwrap();
+
+        let moves = plan_moves(&mods, OrganizeStrategy::LooseFiles).unwrap();
+        apply_moves(&moves, &mut manifest).unwrap();
+
+        let moved_hair = mods.join("CAS/hair (2).package");
+        assert!(moved_hair.exists());
+        assert!(!mods.join("hair.package").exists());
+        assert!(mods.join("Scripts/mccc.ts4script").exists());
+
+        let files = manifest.get_install("hair").unwrap().unwrap().files;
+        let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+        assert!(paths.contains(&moved_hair.to_string_lossy().as_ref()));
+        assert!(paths.contains(&readme.as_str()));
+        assert_eq!(
+            manifest
+                .recorded_hash(&moved_hair.to_string_lossy())
+                .unwrap(),
+            Some("abc".to_string())
+        );
+    }
+
+    #[test]
+    fn recommends_depth_for_shallow_tree() {
+        let dir = tempdir().unwrap();
+        let mods = dir.path().join("Mods");
+        fs::create_dir_all(&mods).unwrap();
+        assert_eq!(recommend_depth(&mods).unwrap().recommended_depth, 1);
+
+        write_package(&mods.join("hair.package"), 0x034AEECB);
+        write_package(&mods.join("sofa.package"), 0xC0DB5AE7);
+        write_package(&mods.join("Creator/dress.package"), 0x034AEECB);
+
+        assert_eq!(
+            recommend_depth(&mods).unwrap(),
+            InstallDepthRecommendation {
+                current_max_depth: 1,
+                recommended_depth: 1,
+                max_loadable_depth: MAX_PACKAGE_DEPTH,
+                unloadable_files: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn recommends_depth_for_deep_tree() {
+        let dir = tempdir().unwrap();
+        let mods = dir.path().join("Mods");
+        for name in ["a", "b", "c"] {
+            write_package(
+                &mods.join(format!("CAS/Hair/{}/{}.package", name, name)),
+                0x034AEECB,
+            );
+        }
+        write_package(&mods.join("BuildBuy/Sofas/sofa.package"), 0xC0DB5AE7);
+        write_package(&mods.join("1/2/3/4/5/6/lost.package"), 0x034AEECB);
+
+        let recommendation = recommend_depth(&mods).unwrap();
+        assert_eq!(recommendation.current_max_depth, 6);
+        assert_eq!(recommendation.unloadable_files, 1);
+        assert_eq!(recommendation.recommended_depth, 3);
+
+        // Organized right at the limit: keep a level free for the mod's own folder
+        for name in ["d", "e", "f", "g"] {
+            write_package(
+                &mods.join(format!("A/B/C/D/{}/{}.package", name, name)),
+                0x034AEECB,
+            );
+        }
+        assert_eq!(
+            recommend_depth(&mods).unwrap().recommended_depth as usize,
+            MAX_PACKAGE_DEPTH - INSTALL_DEPTH_HEADROOM
+        );
+    }
+
+    #[test]
+    fn failed_move_rolls_back() {
+        let dir = tempdir().unwrap();
+        let mods = dir.path().join("Mods");
+        write_package(&mods.join("a.package"), 0x034AEECB);
+        let moves = vec![
+            PlannedMove {
+                from: mods.join("a.package").to_string_lossy().to_string(),
+                to: mods.join("CAS/a.package").to_string_lossy().to_string(),
+                category: ModCategory::Cas,
+            },
+            PlannedMove {
+                from: mods.join("missing.package").to_string_lossy().to_string(),
+                to: mods
+                    .join("CAS/missing.package")
+                    .to_string_lossy()
+                    .to_string(),
+                category: ModCategory::Cas,
+            },
+        ];
+
+        let mut manifest = Manifest::open_in_memory().unwrap();
+        assert!(apply_moves(&moves, &mut manifest).is_err());
+        assert!(mods.join("a.package").exists());
+        assert!(!mods.join("CAS/a.package").exists());
+    }
+}
@@ -0,0 +1,233 @@
+use crate::conflicts::xml_tuning_name;
+use crate::dbpf::{self, IndexEntry, SIMDATA_TYPE};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+
+/// Tuning resource types checked for likely overrides, with a readable label
+/// No list of base-game instances is bundled, so overrides are guessed rather than confirmed:
+/// EA tuning ids fit in 32 bits while creator tools hash `creator:Name` to 64 bits, so a
+/// 32-bit instance in group 0 is flagged unless its tuning carries a `creator:` name; creator
+/// tuning from older 32-bit hashing tools without that prefix is flagged too
+const TUNING_TYPE_LABELS: [(u32, &str); 11] = [
+    (0x0333406C, "XML tuning"),
+    (SIMDATA_TYPE, "SimData"),
+    (0x03B33DDF, "Tuning"),
+    (0x6017E896, "Buff"),
+    (0xCB5FDDC7, "Trait"),
+    (0xE882D22F, "Interaction"),
+    (0x0C772E27, "Loot"),
+    (0x7DF2169C, "Snippet"),
+    (0xB61DE6B4, "Object tuning"),
+    (0x73996BEB, "Career"),
+    (0x28B64675, "Aspiration"),
+];
+
+/// Resource that probably replaces a base-game one, a guess from its instance id, not a match
+/// against the game's own resources
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LikelyOverride {
+    /// Key formatted as TYPE:GROUP:INSTANCE
+    pub resource_key: String,
+    pub type_id: u32,
+    pub instance: u64,
+    /// Human-readable resource type, e.g. "Buff"
+    pub resource_type: String,
+}
+
+/// Package likely to override at least one base-game resource
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PackageOverrides {
+    pub path: String,
+    pub likely_overrides: Vec<LikelyOverride>,
+}
+
+/// Result of a likely-override scan over a mods directory
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OverrideScanResult {
+    /// Packages with likely overrides, sorted by path
+    pub packages: Vec<PackageOverrides>,
+    pub scanned_packages: usize,
+    /// Packages that could not be parsed, with the reason
+    pub unreadable_packages: Vec<String>,
+}
+
+/// Find packages that look like they replace base-game tuning instead of adding their own
+/// Those are the mods most likely to break after a game patch; see `TUNING_TYPE_LABELS` for
+/// how the guess is made
+#[tauri::command(async)]
+pub fn find_likely_overrides(mods_dir: String) -> Result<OverrideScanResult, String> {
+    let mods_dir = Path::new(&mods_dir);
+    if !mods_dir.is_dir() {
+        return Err(format!("Directory not found: {}", mods_dir.display()));
+    }
+
+    let packages = dbpf::find_packages(mods_dir);
+    let results: Vec<(String, Result<Vec<LikelyOverride>, String>)> = packages
+        .par_iter()
+        .map(|path| {
+            (
+                path.to_string_lossy().to_string(),
+                package_likely_overrides(path),
+            )
+        })
+        .collect();
+
+    let mut scan = OverrideScanResult {
+        packages: Vec::new(),
+        scanned_packages: 0,
+        unreadable_packages: Vec::new(),
+    };
+    for (path, result) in results {
+        match result {
+            Ok(overrides) => {
+                scan.scanned_packages += 1;
+                if !overrides.is_empty() {
+                    scan.packages.push(PackageOverrides {
+                        path,
+                        likely_overrides: overrides,
+                    });
+                }
+            }
+            Err(e) => scan.unreadable_packages.push(e),
+        }
+    }
+
+    Ok(scan)
+}
+
+/// Resources of the package at `path` that look like base-game overrides
+pub fn package_likely_overrides(path: &Path) -> Result<Vec<LikelyOverride>, String> {
+    let entries = dbpf::read_index(path)?;
+    let mut reader = BufReader::new(
+        File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?,
+    );
+    Ok(likely_overrides(&mut reader, &entries))
+}
+
+/// Tuning resources with a 32-bit instance in group 0 whose tuning isn't named `creator:Name`
+/// SimData follows the XML tuning of the same instance, unreadable tuning stays flagged
+pub fn likely_overrides<R: Read + Seek>(
+    reader: &mut R,
+    entries: &[IndexEntry],
+) -> Vec<LikelyOverride> {
+    let candidates: Vec<(&IndexEntry, &str)> = entries
+        .iter()
+        .filter(|entry| entry.key.group == 0 && entry.key.instance <= u32::MAX as u64)
+        .filter_map(|entry| {
+            let (_, label) = TUNING_TYPE_LABELS
+                .iter()
+                .find(|(type_id, _)| *type_id == entry.key.type_id)?;
+            Some((entry, *label))
+        })
+        .collect();
+
+    let creator_instances: HashSet<u64> = candidates
+        .iter()
+        .filter(|(entry, _)| entry.key.type_id != SIMDATA_TYPE)
+        .filter(|(entry, _)| {
+            dbpf::read_resource(reader, entry)
+                .ok()
+                .and_then(|data| xml_tuning_name(&data))
+                .is_some_and(|name| is_creator_name(&name))
+        })
+        .map(|(entry, _)| entry.key.instance)
+        .collect();
+
+    let mut overrides: Vec<LikelyOverride> = candidates
+        .into_iter()
+        .filter(|(entry, _)| !creator_instances.contains(&entry.key.instance))
+        .map(|(entry, label)| LikelyOverride {
+            resource_key: entry.key.to_string(),
+            type_id: entry.key.type_id,
+            instance: entry.key.instance,
+            resource_type: label.to_string(),
+        })
+        .collect();
+
+    overrides.sort_by(|a, b| a.resource_key.cmp(&b.resource_key));
+    overrides.dedup();
+    overrides
+}
+
+/// EA's tuning names have no prefix, creator tuning follows the `creator:Name` convention
+fn is_creator_name(name: &str) -> bool {
+    name.split_once(':')
+        .is_some_and(|(creator, rest)| !creator.trim().is_empty() && !rest.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbpf::test_support::{build_package, key};
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn flags_packages_overriding_base_game_instances() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("override.package"),
+            build_package(&[
+                // Base-game buff (32-bit id) and its SimData
+                (key(0x6017E896, 0, 12345), b"<I n=\"Buff_Happy\"/>".to_vec()),
+                (key(0x545AC67A, 0, 12345), b"DATA".to_vec()),
+                // Creator's own buff, hashed 64-bit id
+                (
+                    key(0x6017E896, 0, 0x9A3C_5E11_02B4_77D0),
+                    b"<I n=\"creator:Buff\"/>".to_vec(),
+                ),
+            ]),
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("custom.package"),
+            build_package(&[
+                (key(0x6017E896, 0, 0xE2F0_0000_0000_0001), b"<I/>".to_vec()),
+                // Same small instance but not a tuning type
+                (key(0x034AEECB, 0, 12345), b"CASP".to_vec()),
+            ]),
+        )
+        .unwrap();
+        fs::write(dir.path().join("broken.package"), b"not a package").unwrap();
+
+        let scan = find_likely_overrides(dir.path().to_string_lossy().to_string()).unwrap();
+
+        assert_eq!(scan.scanned_packages, 2);
+        assert_eq!(scan.unreadable_packages.len(), 1);
+        assert_eq!(scan.packages.len(), 1);
+        assert!(scan.packages[0].path.ends_with("override.package"));
+        let overrides = &scan.packages[0].likely_overrides;
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides[0].resource_type, "SimData");
+        assert_eq!(overrides[1].resource_type, "Buff");
+        assert_eq!(
+            overrides[1].resource_key,
+            key(0x6017E896, 0, 12345).to_string()
+        );
+    }
+
+    #[test]
+    fn creator_tuning_with_32_bit_instances_is_not_an_override() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("older_tool.package");
+        fs::write(
+            &path,
+            build_package(&[
+                // 32-bit instance, as older tools hashed creator tuning names
+                (
+                    key(0x6017E896, 0, 0x8C3A_51F2),
+                    b"<I c=\"Buff\" i=\"buff\" n=\"creator:Buff_Custom\" s=\"2352632306\"/>"
+                        .to_vec(),
+                ),
+                (key(0x545AC67A, 0, 0x8C3A_51F2), b"DATA".to_vec()),
+            ]),
+        )
+        .unwrap();
+
+        assert!(package_likely_overrides(&path).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+use crate::dbpf::{self, NAME_MAP_TYPE, XML_TUNING_TYPES};
+use crate::descriptor;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Plain text resource creators use to embed a readme or modinfo block
+const EMBEDDED_TEXT_TYPE: u32 = 0x03B2E3A6;
+
+/// Embedded readme/modinfo text larger than this is not treated as metadata
+const MAX_EMBEDDED_TEXT_SIZE: u32 = 64 * 1024;
+
+/// Creator-provided metadata found inside a package
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct PackageMetadata {
+    pub name: Option<String>,
+    pub creator: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Read name, creator and description from the metadata a package carries
+/// An embedded modinfo-style text resource wins; otherwise the creator is the
+/// `creator:` prefix most tuning and name map entries share
+#[tauri::command(async)]
+pub fn read_package_metadata(path: String) -> Result<PackageMetadata, String> {
+    let path = Path::new(&path);
+    let entries = dbpf::read_index(path)?;
+    let mut reader =
+        BufReader::new(File::open(path).map_err(|e| format!("Failed to open package: {}", e))?);
+
+    let mut metadata = PackageMetadata::default();
+    let mut prefixes: HashMap<String, usize> = HashMap::new();
+
+    // Only the resource types that carry metadata are read, unreadable ones are skipped
+    for entry in &entries {
+        let type_id = entry.key.type_id;
+        let is_tuning = XML_TUNING_TYPES.contains(&type_id);
+        let is_text = type_id == EMBEDDED_TEXT_TYPE && entry.mem_size <= MAX_EMBEDDED_TEXT_SIZE;
+        if !is_tuning && !is_text && type_id != NAME_MAP_TYPE {
+            continue;
+        }
+        let Ok(data) = dbpf::read_resource(&mut reader, entry) else {
+            continue;
+        };
+
+        if type_id == NAME_MAP_TYPE {
+            for (_, name) in dbpf::parse_name_map(&data) {
+                count_prefix(&mut prefixes, &name);
+            }
+        } else if let Ok(text) = std::str::from_utf8(&data) {
+            if is_tuning {
+                if let Some(name) = tuning_name(text) {
+                    count_prefix(&mut prefixes, name);
+                }
+            } else if metadata.name.is_none() && metadata.creator.is_none() {
+                apply_embedded_descriptor(&mut metadata, text);
+            }
+        }
+    }
+
+    if metadata.creator.is_none() {
+        // Most frequent prefix, alphabetical on ties so the result is stable
+        metadata.creator = prefixes
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(&a.0)))
+            .map(|(prefix, _)| prefix);
+    }
+
+    Ok(metadata)
+}
+
+fn apply_embedded_descriptor(metadata: &mut PackageMetadata, text: &str) {
+    if let Some(found) = descriptor::parse_descriptor_content(text) {
+        metadata.name = found.name;
+        metadata.creator = found.author;
+        metadata.description = found.description;
+    }
+}
+
+/// `n` attribute of the root tuning element, e.g. `creator:TuningName`
+fn tuning_name(xml: &str) -> Option<&str> {
+    let start = xml.find(" n=\"")? + 4;
+    let end = xml[start..].find('"')?;
+    Some(&xml[start..start + end])
+}
+
+/// Tuning names follow the `creator:Name` convention, EA's own names have no prefix
+fn count_prefix(prefixes: &mut HashMap<String, usize>, name: &str) {
+    if let Some((prefix, rest)) = name.split_once(':') {
+        let prefix = prefix.trim();
+        if !prefix.is_empty() && !rest.is_empty() && !prefix.contains(char::is_whitespace) {
+            *prefixes.entry(prefix.to_string()).or_default() += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbpf::test_support::{build_package, build_zlib_package, key, name_map};
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn read(dir: &Path, name: &str, package: Vec<u8>) -> PackageMetadata {
+        let path = dir.join(name);
+        fs::write(&path, package).unwrap();
+        read_package_metadata(path.to_string_lossy().to_string()).unwrap()
+    }
+
+    #[test]
+    fn reads_embedded_descriptor() {
+        let dir = tempdir().unwrap();
+        let metadata = read(
+            dir.path(),
+            "hair.package",
+            build_zlib_package(&[
+                (key(0x034AEECB, 0, 1), vec![0u8; 32]),
+                (
+                    key(EMBEDDED_TEXT_TYPE, 0, 2),
+                    b"Name: Cool Hair\nCreator: Ana\nDescription: Long wavy hair".to_vec(),
+                ),
+            ]),
+        );
+
+        assert_eq!(
+            metadata,
+            PackageMetadata {
+                name: Some("Cool Hair".to_string()),
+                creator: Some("Ana".to_string()),
+                description: Some("Long wavy hair".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn creator_comes_from_tuning_and_name_map_prefixes() {
+        let dir = tempdir().unwrap();
+        let metadata = read(
+            dir.path(),
+            "tuning.package",
+            build_package(&[
+                (
+                    key(0x0333406C, 0, 1),
+                    br#"<?xml version="1.0" encoding="utf-8"?><I c="Buff" i="buff" m="buffs.buff" n="zerbu:Buff_Happy" s="1"/>"#.to_vec(),
+                ),
+                (
+                    key(NAME_MAP_TYPE, 0, 0),
+                    name_map(&[(0, "zerbu:Loot_Happy"), (1, "other:Thing"), (2, "NoPrefix")]),
+                ),
+            ]),
+        );
+
+        assert_eq!(metadata.creator.as_deref(), Some("zerbu"));
+        assert_eq!(metadata.name, None);
+        assert_eq!(metadata.description, None);
+    }
+
+    #[test]
+    fn returns_empty_metadata_when_nothing_is_embedded() {
+        let dir = tempdir().unwrap();
+        let metadata = read(
+            dir.path(),
+            "plain.package",
+            build_package(&[(
+                key(0x0333406C, 0, 1),
+                br#"<I c="Buff" i="buff" m="buffs.buff" n="Buff_Happy" s="1"/>"#.to_vec(),
+            )]),
+        );
+
+        assert_eq!(metadata, PackageMetadata::default());
+        assert!(read_package_metadata("missing.package".to_string()).is_err());
+    }
+
+    #[test]
+    fn ignores_text_in_other_resource_types() {
+        let dir = tempdir().unwrap();
+        let metadata = read(
+            dir.path(),
+            "cas.package",
+            build_package(&[
+                (
+                    key(0x034AEECB, 0, 1),
+                    b"Name: Not Metadata\nCreator: Nobody".to_vec(),
+                ),
+                (key(0x220557DA, 0, 2), b"Creator: Also Nobody".to_vec()),
+            ]),
+        );
+
+        assert_eq!(metadata, PackageMetadata::default());
+    }
+}
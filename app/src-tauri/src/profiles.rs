@@ -0,0 +1,181 @@
+use crate::manifest::{self, Manifest};
+use crate::transaction::{apply_transaction, ModOp};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Link a profile places in the game folder
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProfileLink {
+    /// Library folder of the mod
+    pub source: String,
+    /// Link path inside the game's Mods folder
+    pub target: String,
+}
+
+/// Links changed by a profile swap
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct ProfileSwap {
+    /// Links of the old profile that were removed
+    pub removed: Vec<String>,
+    /// Links of the new profile that were created
+    pub created: Vec<String>,
+    /// Links both profiles share, left in place
+    pub unchanged: Vec<String>,
+}
+
+/// Deactivate profile `from_id` and activate `to_id` as a whole, `links` holds each profile's links
+/// Links of the old profile are moved aside until every new link exists, then deleted; any
+/// failure (e.g. a locked or occupied link path) restores the old profile exactly
+#[tauri::command(async)]
+pub fn swap_active_profile(
+    app_handle: tauri::AppHandle,
+    from_id: String,
+    to_id: String,
+    links: HashMap<String, Vec<ProfileLink>>,
+) -> Result<ProfileSwap, String> {
+    let profile_links = |id: &str| {
+        links
+            .get(id)
+            .ok_or_else(|| format!("Profile {} has no links", id))
+    };
+    let mut manifest = manifest::open_app_manifest(&app_handle)?;
+    swap_links(
+        profile_links(&from_id)?,
+        profile_links(&to_id)?,
+        &mut manifest,
+    )
+}
+
+/// Run the swap as one transaction: unlink what only the old profile has, then link what
+/// only the new one has
+pub fn swap_links(
+    from: &[ProfileLink],
+    to: &[ProfileLink],
+    manifest: &mut Manifest,
+) -> Result<ProfileSwap, String> {
+    let mut swap = ProfileSwap::default();
+    let mut ops = Vec::new();
+
+    for link in from {
+        if to.contains(link) {
+            swap.unchanged.push(link.target.clone());
+            continue;
+        }
+        // Already gone, or a real folder the profile doesn't own
+        if Path::new(&link.target).is_symlink() {
+            ops.push(ModOp::Unlink {
+                target: link.target.clone(),
+            });
+            swap.removed.push(link.target.clone());
+        }
+    }
+
+    for link in to {
+        if from.contains(link) {
+            continue;
+        }
+        if links_to(Path::new(&link.target), &link.source) {
+            swap.unchanged.push(link.target.clone());
+            continue;
+        }
+        ops.push(ModOp::Link {
+            source: link.source.clone(),
+            target: link.target.clone(),
+        });
+        swap.created.push(link.target.clone());
+    }
+
+    let result = apply_transaction(&ops, manifest, false);
+    if result.success {
+        return Ok(swap);
+    }
+    let error = result.error.unwrap_or_default();
+    if result.rollback_errors.is_empty() {
+        Err(format!("{}, previous profile restored", error))
+    } else {
+        Err(format!(
+            "{}, previous profile only partially restored: {}",
+            error,
+            result.rollback_errors.join("; ")
+        ))
+    }
+}
+
+/// Whether `target` is a link to `source`
+fn links_to(target: &Path, source: &str) -> bool {
+    fs::read_link(target)
+        .map(|destination| destination == Path::new(source))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::create_symlink;
+    use tempfile::tempdir;
+
+    fn link(dir: &Path, name: &str) -> ProfileLink {
+        let source = dir.join("library").join(name);
+        fs::create_dir_all(&source).unwrap();
+        ProfileLink {
+            source: source.to_string_lossy().to_string(),
+            target: dir.join("Mods").join(name).to_string_lossy().to_string(),
+        }
+    }
+
+    fn activate(links: &[ProfileLink]) {
+        for link in links {
+            create_symlink(link.source.clone(), link.target.clone()).unwrap();
+        }
+    }
+
+    fn mods_entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir.join("Mods"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn swaps_links_keeping_shared_ones() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Mods")).unwrap();
+        let shared = link(dir.path(), "shared");
+        let from = vec![shared.clone(), link(dir.path(), "hair")];
+        let to = vec![shared.clone(), link(dir.path(), "chair")];
+        activate(&from);
+
+        let swap = swap_links(&from, &to, &mut Manifest::open_in_memory().unwrap()).unwrap();
+
+        assert_eq!(swap.removed, vec![from[1].target.clone()]);
+        assert_eq!(swap.created, vec![to[1].target.clone()]);
+        assert_eq!(swap.unchanged, vec![shared.target.clone()]);
+        assert_eq!(mods_entries(dir.path()), vec!["chair", "shared"]);
+        assert!(links_to(Path::new(&to[1].target), &to[1].source));
+    }
+
+    #[test]
+    fn failed_activation_restores_previous_profile() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Mods")).unwrap();
+        let from = vec![link(dir.path(), "hair"), link(dir.path(), "lamp")];
+        let to = vec![link(dir.path(), "chair"), link(dir.path(), "sofa")];
+        activate(&from);
+        // A real folder where the second link of the new profile goes
+        fs::create_dir_all(&to[1].target).unwrap();
+
+        let error = swap_links(&from, &to, &mut Manifest::open_in_memory().unwrap()).unwrap_err();
+
+        assert!(error.contains("already exists"), "{}", error);
+        assert!(error.ends_with("previous profile restored"), "{}", error);
+        assert_eq!(mods_entries(dir.path()), vec!["hair", "lamp", "sofa"]);
+        for link in &from {
+            assert!(links_to(Path::new(&link.target), &link.source));
+        }
+        assert!(!Path::new(&to[1].target).is_symlink());
+    }
+}
@@ -0,0 +1,274 @@
+use crate::archive;
+use crate::calculate_file_hash;
+use crate::extract::{self, ExtractOptions};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+/// Heavy operation `profile_operation` can time
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProfiledOperation {
+    /// Extract the `source` archive
+    Extract,
+    /// Copy the `source` file or folder
+    Copy,
+    /// SHA-256 every file of the `source` file or folder
+    Hash,
+}
+
+/// Input of the profiled operation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProfileArgs {
+    pub source: String,
+    /// Folder receiving the output of extract/copy, on the disk to measure
+    /// Everything is written in a scratch sub-folder removed at the end
+    pub scratch_dir: Option<String>,
+}
+
+/// Duration of one phase
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PhaseTiming {
+    pub phase: String,
+    pub ms: f64,
+}
+
+/// Timing breakdown of a profiled operation
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OperationProfile {
+    pub kind: ProfiledOperation,
+    /// Phases in the order they ran
+    pub phases: Vec<PhaseTiming>,
+    pub total_ms: f64,
+    pub files: usize,
+    /// Bytes read (hash) or written (extract, copy)
+    pub bytes: u64,
+    /// `bytes` over the duration of the main phase
+    pub throughput_mbps: f64,
+}
+
+/// Run an extract, copy or hash entirely in the backend and report how long each phase took
+/// Meant for diagnostics and bug reports: the numbers carry no IPC or frontend overhead
+#[tauri::command(async)]
+pub fn profile_operation(
+    kind: ProfiledOperation,
+    args: ProfileArgs,
+) -> Result<OperationProfile, String> {
+    let source = Path::new(&args.source);
+    let mut timer = PhaseTimer::new();
+
+    let (files, bytes, main_phase) = match kind {
+        ProfiledOperation::Hash => {
+            let paths = timer.run("scan", || list_files(source))?;
+            let bytes = timer.run("hash", || hash_all(&paths))?;
+            (paths.len(), bytes, "hash")
+        }
+        ProfiledOperation::Copy | ProfiledOperation::Extract => {
+            let scratch = args
+                .scratch_dir
+                .as_deref()
+                .map(|dir| Path::new(dir).join(format!(".simsforge-profile-{}", Uuid::new_v4())))
+                .ok_or("A scratch folder is needed to profile extract and copy")?;
+            let outcome = if kind == ProfiledOperation::Copy {
+                profile_copy(source, &scratch, &mut timer)
+            } else {
+                profile_extract(source, &scratch, &mut timer)
+            };
+            timer.run("cleanup", || {
+                if !scratch.exists() {
+                    return Ok(());
+                }
+                fs::remove_dir_all(&scratch)
+                    .map_err(|e| format!("Failed to remove {}: {}", scratch.display(), e))
+            })?;
+            outcome?
+        }
+    };
+
+    Ok(timer.finish(kind, files, bytes, main_phase))
+}
+
+fn profile_extract(
+    zip_path: &Path,
+    scratch: &Path,
+    timer: &mut PhaseTimer,
+) -> Result<(usize, u64, &'static str), String> {
+    let inspection = timer.run("inspect", || archive::inspect_archive(zip_path))?;
+    let report = timer.run("extract", || {
+        extract::extract_archive(zip_path, scratch, &ExtractOptions::default(), |_, _| {})
+    })?;
+    Ok((
+        report.files_written,
+        inspection.uncompressed_bytes,
+        "extract",
+    ))
+}
+
+fn profile_copy(
+    source: &Path,
+    scratch: &Path,
+    timer: &mut PhaseTimer,
+) -> Result<(usize, u64, &'static str), String> {
+    let paths = timer.run("scan", || list_files(source))?;
+    let bytes = timer.run("copy", || -> Result<u64, String> {
+        let mut bytes = 0;
+        for path in &paths {
+            let relative = path.strip_prefix(source).unwrap_or(path);
+            let target = match relative.file_name() {
+                // A single file source strips to an empty path
+                None => scratch.join(path.file_name().unwrap_or_default()),
+                Some(_) => scratch.join(relative),
+            };
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+            }
+            bytes += fs::copy(path, &target)
+                .map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+        }
+        Ok(bytes)
+    })?;
+    Ok((paths.len(), bytes, "copy"))
+}
+
+/// Files of `source` (itself when it is a file), sorted
+fn list_files(source: &Path) -> Result<Vec<PathBuf>, String> {
+    if !source.exists() {
+        return Err(format!("Not found: {}", source.display()));
+    }
+    Ok(WalkDir::new(source)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect())
+}
+
+fn hash_all(paths: &[PathBuf]) -> Result<u64, String> {
+    let mut bytes = 0;
+    for path in paths {
+        calculate_file_hash(path.to_string_lossy().to_string())?;
+        bytes += fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    }
+    Ok(bytes)
+}
+
+/// Records how long each phase takes, in order
+struct PhaseTimer {
+    start: Instant,
+    phases: Vec<PhaseTiming>,
+}
+
+impl PhaseTimer {
+    fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            phases: Vec::new(),
+        }
+    }
+
+    fn run<T>(&mut self, phase: &str, work: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = work();
+        self.phases.push(PhaseTiming {
+            phase: phase.to_string(),
+            ms: start.elapsed().as_secs_f64() * 1000.0,
+        });
+        result
+    }
+
+    fn finish(
+        self,
+        kind: ProfiledOperation,
+        files: usize,
+        bytes: u64,
+        main_phase: &str,
+    ) -> OperationProfile {
+        let main_ms = self
+            .phases
+            .iter()
+            .find(|timing| timing.phase == main_phase)
+            .map(|timing| timing.ms)
+            .unwrap_or(0.0);
+        let throughput_mbps = if main_ms > 0.0 {
+            bytes as f64 / (1024.0 * 1024.0) / (main_ms / 1000.0)
+        } else {
+            0.0
+        };
+
+        OperationProfile {
+            kind,
+            phases: self.phases,
+            total_ms: self.start.elapsed().as_secs_f64() * 1000.0,
+            files,
+            bytes,
+            throughput_mbps,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::write_zip;
+    use tempfile::tempdir;
+
+    fn phase_names(profile: &OperationProfile) -> Vec<&str> {
+        profile.phases.iter().map(|t| t.phase.as_str()).collect()
+    }
+
+    fn assert_consistent(profile: &OperationProfile) {
+        let sum: f64 = profile.phases.iter().map(|t| t.ms).sum();
+        assert!(profile.phases.iter().all(|t| t.ms >= 0.0));
+        assert!(profile.total_ms >= sum);
+        assert!(profile.throughput_mbps > 0.0);
+    }
+
+    #[test]
+    fn times_every_phase_in_order() {
+        let dir = tempdir().unwrap();
+        let content = vec![7u8; 256 * 1024];
+        let zip_path = write_zip(
+            &dir.path().join("mod.zip"),
+            &[("Mod/a.package", &content), ("Mod/b.package", &content)],
+        );
+        let scratch = dir.path().join("scratch");
+        fs::create_dir_all(&scratch).unwrap();
+        let args = |source: &str| ProfileArgs {
+            source: source.to_string(),
+            scratch_dir: Some(scratch.to_string_lossy().to_string()),
+        };
+
+        let extract = profile_operation(ProfiledOperation::Extract, args(&zip_path)).unwrap();
+        assert_eq!(phase_names(&extract), vec!["inspect", "extract", "cleanup"]);
+        assert_eq!((extract.files, extract.bytes), (2, 512 * 1024));
+        assert_consistent(&extract);
+
+        let copy = profile_operation(ProfiledOperation::Copy, args(&zip_path)).unwrap();
+        assert_eq!(phase_names(&copy), vec!["scan", "copy", "cleanup"]);
+        assert_eq!(copy.files, 1);
+        assert_consistent(&copy);
+
+        let hash = profile_operation(ProfiledOperation::Hash, args(&dir.path().to_string_lossy()))
+            .unwrap();
+        assert_eq!(phase_names(&hash), vec!["scan", "hash"]);
+        assert_eq!(hash.kind, ProfiledOperation::Hash);
+        assert_consistent(&hash);
+
+        // Scratch output is always cleaned up
+        assert_eq!(fs::read_dir(&scratch).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn extract_and_copy_need_a_scratch_folder() {
+        let args = ProfileArgs {
+            source: "missing.zip".to_string(),
+            scratch_dir: None,
+        };
+        assert!(profile_operation(ProfiledOperation::Copy, args).is_err());
+    }
+}
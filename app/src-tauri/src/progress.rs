@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// Steps of an install, in the order they run
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallPhase {
+    /// Only when installing from a URL
+    Downloading,
+    /// Fake-mod check of the downloaded archive, only when installing from a URL
+    Analyzing,
+    Extracting,
+    Hashing,
+    Linking,
+}
+
+/// Share of the overall progress bar given to each phase, from its typical cost
+/// Extraction dominates (decompression + writes), hashing re-reads every file once,
+/// linking is a handful of metadata operations
+const PHASE_WEIGHTS: [(InstallPhase, f64); 3] = [
+    (InstallPhase::Extracting, 0.70),
+    (InstallPhase::Hashing, 0.25),
+    (InstallPhase::Linking, 0.05),
+];
+
+/// Phase shares when the archive is downloaded first, the download usually takes the longest
+pub const URL_INSTALL_PHASE_WEIGHTS: [(InstallPhase, f64); 5] = [
+    (InstallPhase::Downloading, 0.45),
+    (InstallPhase::Analyzing, 0.05),
+    (InstallPhase::Extracting, 0.35),
+    (InstallPhase::Hashing, 0.12),
+    (InstallPhase::Linking, 0.03),
+];
+
+/// Weight of a phase as exposed to the frontend
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PhaseWeight {
+    pub phase: InstallPhase,
+    /// Fraction of the overall progress (weights sum to 1)
+    pub weight: f64,
+}
+
+/// Progress payload emitted on `install://progress`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Progress {
+    pub operation_id: String,
+    pub phase: InstallPhase,
+    /// Completion of the current phase, 0 to 1
+    pub phase_progress: f64,
+    /// Completion of the whole install, 0 to 100, never decreases
+    pub overall_progress: f64,
+}
+
+/// Weights used to turn phase progress into overall progress
+/// `from_url` gives the weights of `install_from_url`, which downloads and analyzes first
+#[tauri::command]
+pub fn get_install_phase_weights(from_url: Option<bool>) -> Vec<PhaseWeight> {
+    let weights: &[(InstallPhase, f64)] = if from_url.unwrap_or(false) {
+        &URL_INSTALL_PHASE_WEIGHTS
+    } else {
+        &PHASE_WEIGHTS
+    };
+    weights
+        .iter()
+        .map(|&(phase, weight)| PhaseWeight { phase, weight })
+        .collect()
+}
+
+/// Converts per-phase progress into one overall percentage and forwards it
+/// Updates may arrive out of order from worker threads, the overall value is kept monotonic
+pub struct ProgressTracker<F: Fn(Progress) + Sync> {
+    operation_id: String,
+    weights: &'static [(InstallPhase, f64)],
+    last_overall: Mutex<f64>,
+    on_progress: F,
+}
+
+impl<F: Fn(Progress) + Sync> ProgressTracker<F> {
+    pub fn new(operation_id: &str, on_progress: F) -> Self {
+        Self::with_weights(operation_id, &PHASE_WEIGHTS, on_progress)
+    }
+
+    /// Tracker for a flow with other phases than a local install, `weights` must sum to 1
+    pub fn with_weights(
+        operation_id: &str,
+        weights: &'static [(InstallPhase, f64)],
+        on_progress: F,
+    ) -> Self {
+        Self {
+            operation_id: operation_id.to_string(),
+            weights,
+            last_overall: Mutex::new(0.0),
+            on_progress,
+        }
+    }
+
+    /// Report `done` out of `total` units of work for `phase` (an empty phase counts as done)
+    pub fn update(&self, phase: InstallPhase, done: usize, total: usize) {
+        let phase_progress = if total == 0 {
+            1.0
+        } else {
+            (done as f64 / total as f64).clamp(0.0, 1.0)
+        };
+
+        // Emit under the lock, otherwise two workers could still deliver 60 then 50
+        let mut last = self.last_overall.lock().unwrap();
+        *last = last.max(weighted_progress(self.weights, phase, phase_progress));
+
+        (self.on_progress)(Progress {
+            operation_id: self.operation_id.clone(),
+            phase,
+            phase_progress,
+            overall_progress: *last,
+        });
+    }
+}
+
+/// Overall percentage once `phase` is `phase_progress` done, phases before it count as complete
+fn weighted_progress(
+    weights: &[(InstallPhase, f64)],
+    phase: InstallPhase,
+    phase_progress: f64,
+) -> f64 {
+    let mut overall = 0.0;
+    for &(current, weight) in weights {
+        if current == phase {
+            overall += weight * phase_progress;
+            break;
+        }
+        overall += weight;
+    }
+    (overall * 100.0).min(100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weights_cover_the_whole_bar() {
+        for from_url in [None, Some(true)] {
+            let total: f64 = get_install_phase_weights(from_url)
+                .iter()
+                .map(|w| w.weight)
+                .sum();
+            assert!((total - 1.0).abs() < 1e-9);
+        }
+        assert_eq!(
+            get_install_phase_weights(Some(true))[0].phase,
+            InstallPhase::Downloading
+        );
+        assert_eq!(
+            weighted_progress(&PHASE_WEIGHTS, InstallPhase::Extracting, 0.0),
+            0.0
+        );
+        assert!(
+            (weighted_progress(&PHASE_WEIGHTS, InstallPhase::Hashing, 0.0) - 70.0).abs() < 1e-9
+        );
+        assert!(
+            (weighted_progress(&PHASE_WEIGHTS, InstallPhase::Linking, 1.0) - 100.0).abs() < 1e-9
+        );
+    }
+
+    #[test]
+    fn out_of_order_updates_never_go_backwards() {
+        let events = Mutex::new(Vec::new());
+        let tracker = ProgressTracker::new("op", |p| events.lock().unwrap().push(p));
+
+        tracker.update(InstallPhase::Extracting, 3, 4);
+        tracker.update(InstallPhase::Extracting, 2, 4);
+        tracker.update(InstallPhase::Hashing, 0, 0);
+
+        let events = events.into_inner().unwrap();
+        assert!((events[0].overall_progress - 52.5).abs() < 1e-9);
+        assert_eq!(events[1].overall_progress, events[0].overall_progress);
+        assert_eq!(events[1].phase_progress, 0.5);
+        assert!((events[2].overall_progress - 95.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn concurrent_updates_are_delivered_in_order() {
+        use rayon::prelude::*;
+
+        let events = Mutex::new(Vec::new());
+        let tracker = ProgressTracker::new("op", |p| events.lock().unwrap().push(p));
+
+        (0..=1000usize)
+            .into_par_iter()
+            .for_each(|done| tracker.update(InstallPhase::Extracting, done, 1000));
+
+        let events = events.into_inner().unwrap();
+        assert_eq!(events.len(), 1001);
+        assert!(events
+            .windows(2)
+            .all(|pair| pair[0].overall_progress <= pair[1].overall_progress));
+    }
+}
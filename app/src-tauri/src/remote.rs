@@ -0,0 +1,204 @@
+use crate::manifest;
+use reqwest::header::{HeaderMap, CONTENT_LENGTH, ETAG, LAST_MODIFIED};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// HEAD requests should answer quickly, a slow server is treated as a failure
+const HEAD_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Check whether the file at `url` differs from the local copy, without downloading it
+/// Compares Content-Length, then the ETag against the hash recorded in the manifest or
+/// Last-Modified against the local modification time
+/// Returns true ("download it") whenever the server gives nothing to compare against
+#[tauri::command]
+pub async fn remote_file_differs(
+    app_handle: tauri::AppHandle,
+    local_path: String,
+    url: String,
+) -> Result<bool, String> {
+    // The manifest is optional here, a missing hash only removes the ETag comparison
+    let recorded_hash = manifest::open_app_manifest(&app_handle)
+        .and_then(|manifest| manifest.recorded_hash(&local_path))
+        .ok()
+        .flatten();
+
+    check_remote(Path::new(&local_path), recorded_hash.as_deref(), &url).await
+}
+
+async fn check_remote(
+    local_path: &Path,
+    recorded_hash: Option<&str>,
+    url: &str,
+) -> Result<bool, String> {
+    let client = reqwest::Client::builder()
+        .timeout(HEAD_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client
+        .head(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to query {}: {}", url, e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Server returned {} for {}", response.status(), url));
+    }
+
+    Ok(differs_from_headers(
+        local_path,
+        recorded_hash,
+        response.headers(),
+    ))
+}
+
+/// Decide from the response headers whether the remote file differs from the local one
+fn differs_from_headers(
+    local_path: &Path,
+    recorded_hash: Option<&str>,
+    headers: &HeaderMap,
+) -> bool {
+    let Ok(metadata) = std::fs::metadata(local_path) else {
+        return true;
+    };
+
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+    // A different size settles it, a missing one means we can't rule anything out
+    match header(CONTENT_LENGTH).and_then(|value| value.trim().parse::<u64>().ok()) {
+        Some(length) if length != metadata.len() => return true,
+        Some(_) => {}
+        None => return true,
+    }
+
+    if let (Some(etag), Some(hash)) = (header(ETAG), recorded_hash) {
+        return !normalize_etag(etag).eq_ignore_ascii_case(hash.trim());
+    }
+
+    if let Some(last_modified) =
+        header(LAST_MODIFIED).and_then(|value| httpdate::parse_http_date(value).ok())
+    {
+        let local_modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+        return last_modified > local_modified;
+    }
+
+    // Same size alone is too weak to skip an update
+    true
+}
+
+/// Strip the weak marker and quotes from an ETag (`W/"abc"` -> `abc`)
+fn normalize_etag(etag: &str) -> &str {
+    etag.trim().trim_start_matches("W/").trim_matches('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use tempfile::tempdir;
+
+    /// Serve a single HTTP response with the given headers and return the URL to query
+    fn mock_server(status: &'static str, headers: Vec<String>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/mod.package", listener.local_addr().unwrap());
+
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 4096];
+            let _ = stream.read(&mut request);
+
+            let mut response = format!("HTTP/1.1 {}\r\nConnection: close\r\n", status);
+            for header in headers {
+                response.push_str(&header);
+                response.push_str("\r\n");
+            }
+            response.push_str("\r\n");
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        url
+    }
+
+    fn local_file(dir: &Path) -> std::path::PathBuf {
+        let path = dir.join("mod.package");
+        fs::write(&path, b"0123456789").unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn identical_size_and_etag_skips_download() {
+        let dir = tempdir().unwrap();
+        let path = local_file(dir.path());
+        let url = mock_server(
+            "200 OK",
+            vec![
+                "Content-Length: 10".to_string(),
+                "ETag: W/\"ABC123\"".to_string(),
+            ],
+        );
+
+        assert!(!check_remote(&path, Some("abc123"), &url).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn different_size_or_etag_is_reported() {
+        let dir = tempdir().unwrap();
+        let path = local_file(dir.path());
+
+        let url = mock_server("200 OK", vec!["Content-Length: 11".to_string()]);
+        assert!(check_remote(&path, Some("abc123"), &url).await.unwrap());
+
+        let url = mock_server(
+            "200 OK",
+            vec![
+                "Content-Length: 10".to_string(),
+                "ETag: \"def456\"".to_string(),
+            ],
+        );
+        assert!(check_remote(&path, Some("abc123"), &url).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn compares_last_modified_without_recorded_hash() {
+        let dir = tempdir().unwrap();
+        let path = local_file(dir.path());
+
+        let old = httpdate::fmt_http_date(SystemTime::now() - Duration::from_secs(3600));
+        let url = mock_server(
+            "200 OK",
+            vec![
+                "Content-Length: 10".to_string(),
+                format!("Last-Modified: {}", old),
+            ],
+        );
+        assert!(!check_remote(&path, None, &url).await.unwrap());
+
+        let newer = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(3600));
+        let url = mock_server(
+            "200 OK",
+            vec![
+                "Content-Length: 10".to_string(),
+                format!("Last-Modified: {}", newer),
+            ],
+        );
+        assert!(check_remote(&path, None, &url).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn missing_headers_assume_different() {
+        let dir = tempdir().unwrap();
+        let path = local_file(dir.path());
+
+        let url = mock_server("200 OK", Vec::new());
+        assert!(check_remote(&path, Some("abc123"), &url).await.unwrap());
+
+        // Same size but nothing to validate the content with
+        let url = mock_server("200 OK", vec!["Content-Length: 10".to_string()]);
+        assert!(check_remote(&path, Some("abc123"), &url).await.unwrap());
+
+        let url = mock_server("404 Not Found", vec!["Content-Length: 0".to_string()]);
+        assert!(check_remote(&path, None, &url).await.is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::calculate_file_hash;
+use crate::manifest::{self, Manifest};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Replace a mod file with a new version without ever leaving a half-written file behind
+/// The replacement only happens if the current file still hashes to `expected_old_hash`
+/// Returns the SHA-256 of the new content, which is also recorded in the manifest
+#[tauri::command(async)]
+pub fn replace_file_atomic(
+    app_handle: tauri::AppHandle,
+    target_path: String,
+    new_source_path: String,
+    expected_old_hash: String,
+) -> Result<String, String> {
+    let manifest = manifest::open_app_manifest(&app_handle)?;
+    replace_file(
+        Path::new(&target_path),
+        Path::new(&new_source_path),
+        &expected_old_hash,
+        Some(&manifest),
+    )
+}
+
+pub fn replace_file(
+    target: &Path,
+    source: &Path,
+    expected_old_hash: &str,
+    manifest: Option<&Manifest>,
+) -> Result<String, String> {
+    ensure_unchanged(target, expected_old_hash)?;
+
+    // Same directory as the target so the final rename never crosses volumes
+    let temp_path = temp_path_for(target)?;
+    let outcome = write_synced_copy(source, &temp_path).and_then(|new_hash| {
+        // Narrow the window between the first check and the swap as much as possible
+        ensure_unchanged(target, expected_old_hash)?;
+        fs::rename(&temp_path, target)
+            .map_err(|e| format!("Failed to replace {}: {}", target.display(), e))?;
+        Ok(new_hash)
+    });
+
+    let new_hash = match outcome {
+        Ok(new_hash) => new_hash,
+        Err(e) => {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
+        }
+    };
+
+    if let Some(manifest) = manifest {
+        manifest.update_file_hash(&target.to_string_lossy(), &new_hash)?;
+    }
+
+    Ok(new_hash)
+}
+
+/// Fail unless `target` still has the content the caller based its decision on
+fn ensure_unchanged(target: &Path, expected_hash: &str) -> Result<(), String> {
+    let current = calculate_file_hash(target.to_string_lossy().to_string())?;
+    if current.eq_ignore_ascii_case(expected_hash.trim()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} was modified since it was last read, replacement aborted",
+            target.display()
+        ))
+    }
+}
+
+/// Hidden temporary file next to `target`, so renaming it over the target never crosses volumes
+pub(crate) fn temp_path_for(target: &Path) -> Result<PathBuf, String> {
+    let parent = target
+        .parent()
+        .ok_or_else(|| format!("Invalid target path: {}", target.display()))?;
+    let name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    Ok(parent.join(format!(".{}.{}.tmp", name, Uuid::new_v4())))
+}
+
+/// Whether `a` and `b` name the same file, through `./`, links or a case variant
+/// Paths that don't exist yet only match when they are written the same way
+pub(crate) fn same_file(a: &Path, b: &Path) -> bool {
+    if a == b {
+        return true;
+    }
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b || same_identity(&a, &b),
+        _ => false,
+    }
+}
+
+/// Hard links and case-insensitive filesystems (macOS) keep distinct canonical paths
+#[cfg(unix)]
+fn same_identity(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+/// Canonical paths on Windows already carry the on-disk case
+#[cfg(not(unix))]
+fn same_identity(_a: &Path, _b: &Path) -> bool {
+    false
+}
+
+/// Copy `source` to `dest`, flush it to disk and return the SHA-256 of what was written
+fn write_synced_copy(source: &Path, dest: &Path) -> Result<String, String> {
+    fs::copy(source, dest).map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+    File::open(dest)
+        .and_then(|file| file.sync_all())
+        .map_err(|e| format!("Failed to sync {}: {}", dest.display(), e))?;
+
+    calculate_file_hash(dest.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::{InstallRecord, InstalledFile};
+    use tempfile::tempdir;
+
+    fn hash_of(path: &Path) -> String {
+        calculate_file_hash(path.to_string_lossy().to_string()).unwrap()
+    }
+
+    #[test]
+    fn recognizes_the_same_file_under_another_path() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("pack.zip");
+        fs::write(&file, b"zip").unwrap();
+        fs::create_dir_all(dir.path().join("sub")).unwrap();
+
+        assert!(same_file(&file, &dir.path().join("sub/../pack.zip")));
+        assert!(same_file(&file, &dir.path().join("./pack.zip")));
+        #[cfg(unix)]
+        {
+            let link = dir.path().join("link.zip");
+            std::os::unix::fs::symlink(&file, &link).unwrap();
+            assert!(same_file(&file, &link));
+        }
+        assert!(!same_file(&file, &dir.path().join("other.zip")));
+    }
+
+    #[test]
+    fn replaces_file_and_updates_manifest_hash() {
+        let dir = tempdir().unwrap();
+        let target = dir.path().join("hair.package");
+        let source = dir.path().join("hair_v2.package");
+        fs::write(&target, b"version 1").unwrap();
+        fs::write(&source, b"version 2").unwrap();
+        let old_hash = hash_of(&target);
+
+        let mut manifest = Manifest::open_in_memory().unwrap();
+        manifest
+            .upsert_install(&InstallRecord {
+                id: "hair".to_string(),
+                name: "Hair".to_string(),
+                version: None,
+                installed_at: 1,
+                files: vec![InstalledFile {
+                    path: target.to_string_lossy().to_string(),
+                    hash: Some(old_hash.clone()),
+                }],
+                tags: Vec::new(),
+                note: None,
+            })
+            .unwrap();
+
+        let new_hash =
+            replace_file(&target, &source, &old_hash.to_uppercase(), Some(&manifest)).unwrap();
+
+        assert_eq!(fs::read(&target).unwrap(), b"version 2");
+        assert_eq!(new_hash, hash_of(&source));
+        assert_eq!(
+            manifest.recorded_hash(&target.to_string_lossy()).unwrap(),
+            Some(new_hash)
+        );
+        // Only the target and the source remain, no temp file left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn aborts_when_target_changed() {
+        let dir = tempdir().unwrap();
+        let target = dir.path().join("hair.package");
+        let source = dir.path().join("hair_v2.package");
+        fs::write(&target, b"version 1").unwrap();
+        fs::write(&source, b"version 2").unwrap();
+        let stale_hash = hash_of(&target);
+
+        // Someone else edits the file after we read it
+        fs::write(&target, b"edited by the user").unwrap();
+
+        let error = replace_file(&target, &source, &stale_hash, None).unwrap_err();
+
+        assert!(error.contains("replacement aborted"));
+        assert_eq!(fs::read(&target).unwrap(), b"edited by the user");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+}
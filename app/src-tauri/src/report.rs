@@ -0,0 +1,378 @@
+use crate::compatibility::{self, CompatibilityStatus, ModCompatibility};
+use crate::conflicts::{self, PackageConflict, ScriptModuleConflict};
+use crate::manifest::unix_now;
+use crate::operations::{CancellationToken, OperationRegistry, CANCELLED_ERROR};
+use crate::replace::temp_path_for;
+use crate::validate::{self, PackageValidation};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use tauri::Emitter;
+
+/// Scans run by `export_report`, in order
+const REPORT_STEPS: [&str; 3] = ["conflicts", "validation", "compatibility"];
+
+/// File format of an exported report
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Markdown,
+    Json,
+}
+
+/// Headline numbers of a library report
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct ReportCounts {
+    pub packages: usize,
+    pub scripts: usize,
+    pub conflicts: usize,
+    pub script_conflicts: usize,
+    pub broken_packages: usize,
+    /// Mods with a compatibility status other than ok
+    pub compatibility_issues: usize,
+}
+
+/// Everything `export_report` writes, paths are relative to the mods folder
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LibraryReport {
+    pub generated_at: i64,
+    pub game_version: Option<String>,
+    pub counts: ReportCounts,
+    pub conflicts: Vec<PackageConflict>,
+    pub script_conflicts: Vec<ScriptModuleConflict>,
+    /// Packages and scripts the conflict scan could not read
+    pub unreadable: Vec<String>,
+    pub broken_packages: Vec<PackageValidation>,
+    /// Mods at risk on `game_version`, None when no game version was given
+    pub compatibility: Option<Vec<ModCompatibility>>,
+}
+
+/// Progress payload emitted on `report://progress`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReportProgress {
+    pub operation_id: String,
+    /// Scan running: "conflicts", "validation" or "compatibility"
+    pub step: String,
+    /// Position of the step, 1-based, out of `steps`
+    pub step_index: usize,
+    pub steps: usize,
+    /// Progress within the step
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Run the conflict scan, package validation and (with `game_version`) the compatibility check,
+/// then write them as a single Markdown or JSON report to share in a support thread
+/// Paths are written relative to `mods_dir` so the report doesn't reveal the user's folders
+/// Emits `report://progress` for each scan, stop it with `cancel_operation(operation_id)`
+#[tauri::command(async)]
+pub fn export_report(
+    app_handle: tauri::AppHandle,
+    registry: tauri::State<'_, OperationRegistry>,
+    mods_dir: String,
+    output_path: String,
+    format: ReportFormat,
+    game_version: Option<String>,
+    operation_id: String,
+) -> Result<ReportCounts, String> {
+    let operation = registry.start(&operation_id);
+
+    let report = build_report(
+        Path::new(&mods_dir),
+        game_version.as_deref(),
+        operation.token(),
+        |step_index, done, total| {
+            let _ = app_handle.emit(
+                "report://progress",
+                ReportProgress {
+                    operation_id: operation_id.clone(),
+                    step: REPORT_STEPS[step_index].to_string(),
+                    step_index: step_index + 1,
+                    steps: REPORT_STEPS.len(),
+                    done,
+                    total,
+                },
+            );
+        },
+    )?;
+    write_report(&report, Path::new(&output_path), format)?;
+    Ok(report.counts)
+}
+
+pub fn build_report(
+    mods_dir: &Path,
+    game_version: Option<&str>,
+    token: &CancellationToken,
+    on_progress: impl Fn(usize, usize, usize) + Sync,
+) -> Result<LibraryReport, String> {
+    if !mods_dir.is_dir() {
+        return Err(format!("Directory not found: {}", mods_dir.display()));
+    }
+
+    let scan =
+        conflicts::scan_conflicts(mods_dir, token, |done, total| on_progress(0, done, total))?;
+    let broken_packages = validate::validate_all(mods_dir, false, token, |done, total| {
+        on_progress(1, done, total)
+    })?;
+    if token.is_cancelled() {
+        return Err(CANCELLED_ERROR.to_string());
+    }
+    let compatibility = match game_version {
+        Some(version) => Some(
+            compatibility::check_library(mods_dir, version, |done, total| {
+                on_progress(2, done, total)
+            })?
+            .into_iter()
+            .filter(|entry| entry.status != CompatibilityStatus::Ok)
+            .collect::<Vec<_>>(),
+        ),
+        None => None,
+    };
+
+    let root = format!(
+        "{}{}",
+        mods_dir.to_string_lossy().trim_end_matches(['/', '\\']),
+        std::path::MAIN_SEPARATOR
+    );
+    let relative = |text: &str| text.replace(&root, "");
+
+    let mut report = LibraryReport {
+        generated_at: unix_now(),
+        game_version: game_version.map(str::to_string),
+        counts: ReportCounts {
+            packages: scan.scanned_packages,
+            scripts: scan.scanned_scripts,
+            conflicts: scan.conflicts.len(),
+            script_conflicts: scan.script_conflicts.len(),
+            broken_packages: broken_packages.len(),
+            compatibility_issues: compatibility.as_ref().map(Vec::len).unwrap_or(0),
+        },
+        conflicts: scan.conflicts,
+        script_conflicts: scan.script_conflicts,
+        unreadable: scan.unreadable_packages,
+        broken_packages,
+        compatibility,
+    };
+
+    for conflict in &mut report.conflicts {
+        conflict.packages = conflict.packages.iter().map(|p| relative(p)).collect();
+    }
+    for conflict in &mut report.script_conflicts {
+        conflict.scripts = conflict.scripts.iter().map(|p| relative(p)).collect();
+    }
+    report.unreadable = report.unreadable.iter().map(|e| relative(e)).collect();
+    for package in &mut report.broken_packages {
+        package.path = relative(&package.path);
+        package.errors = package.errors.iter().map(|e| relative(e)).collect();
+    }
+    for entry in report.compatibility.iter_mut().flatten() {
+        entry.mod_path = relative(&entry.mod_path);
+    }
+
+    Ok(report)
+}
+
+/// Write the report next to `output` first, then move it into place
+fn write_report(report: &LibraryReport, output: &Path, format: ReportFormat) -> Result<(), String> {
+    let content = match format {
+        ReportFormat::Json => serde_json::to_string_pretty(report)
+            .map_err(|e| format!("Failed to serialize report: {}", e))?,
+        ReportFormat::Markdown => render_markdown(report),
+    };
+
+    let temp = temp_path_for(output)?;
+    let result = fs::write(&temp, content).and_then(|_| fs::rename(&temp, output));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp);
+        return Err(format!("Failed to write {}: {}", output.display(), e));
+    }
+    Ok(())
+}
+
+fn render_markdown(report: &LibraryReport) -> String {
+    let counts = &report.counts;
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# SimsForge library report\n");
+    let _ = writeln!(out, "Generated at {} (Unix time)", report.generated_at);
+    let _ = writeln!(
+        out,
+        "Game version: {}\n",
+        report.game_version.as_deref().unwrap_or("not given")
+    );
+
+    let _ = writeln!(out, "## Summary\n");
+    let _ = writeln!(out, "| | Count |\n|---|---|");
+    let _ = writeln!(out, "| Packages | {} |", counts.packages);
+    let _ = writeln!(out, "| Scripts | {} |", counts.scripts);
+    let _ = writeln!(out, "| Resource conflicts | {} |", counts.conflicts);
+    let _ = writeln!(out, "| Script conflicts | {} |", counts.script_conflicts);
+    let _ = writeln!(out, "| Broken packages | {} |", counts.broken_packages);
+    let _ = writeln!(
+        out,
+        "| Compatibility issues | {} |\n",
+        counts.compatibility_issues
+    );
+
+    let _ = writeln!(out, "## Resource conflicts\n");
+    if report.conflicts.is_empty() {
+        let _ = writeln!(out, "None");
+    }
+    for conflict in &report.conflicts {
+        let _ = writeln!(
+            out,
+            "- `{}` in {}",
+            conflict.resource_key,
+            code_list(&conflict.packages)
+        );
+    }
+
+    let _ = writeln!(out, "\n## Script conflicts\n");
+    if report.script_conflicts.is_empty() {
+        let _ = writeln!(out, "None");
+    }
+    for conflict in &report.script_conflicts {
+        let _ = writeln!(
+            out,
+            "- `{}` in {}",
+            conflict.module,
+            code_list(&conflict.scripts)
+        );
+    }
+
+    let _ = writeln!(out, "\n## Broken packages\n");
+    if report.broken_packages.is_empty() && report.unreadable.is_empty() {
+        let _ = writeln!(out, "None");
+    }
+    for package in &report.broken_packages {
+        let _ = writeln!(out, "- `{}`: {}", package.path, package.errors.join("; "));
+    }
+    for error in &report.unreadable {
+        let _ = writeln!(out, "- {}", error);
+    }
+
+    let _ = writeln!(out, "\n## Compatibility\n");
+    match &report.compatibility {
+        None => {
+            let _ = writeln!(out, "Skipped, no game version given");
+        }
+        Some(entries) if entries.is_empty() => {
+            let _ = writeln!(out, "None");
+        }
+        Some(entries) => {
+            for entry in entries {
+                let status = serde_json::to_value(entry.status)
+                    .ok()
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    out,
+                    "- `{}` ({}): {}",
+                    entry.mod_path,
+                    status,
+                    entry.reasons.join("; ")
+                );
+            }
+        }
+    }
+
+    out
+}
+
+fn code_list(items: &[String]) -> String {
+    items
+        .iter()
+        .map(|item| format!("`{}`", item))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbpf::test_support::{build_package, key};
+    use tempfile::tempdir;
+
+    /// Two packages sharing a key, one truncated package and a script compiled for Python 3.3
+    fn fixture_library(mods: &Path) {
+        let shared = key(0x034AEECB, 0, 7);
+        for name in ["hair_a.package", "hair_b.package"] {
+            fs::write(
+                mods.join(name),
+                build_package(&[(shared, b"part".to_vec())]),
+            )
+            .unwrap();
+        }
+        let mut broken = build_package(&[(key(0x0333406C, 0, 1), b"<I/>".to_vec())]);
+        broken.truncate(broken.len() - 8);
+        fs::write(mods.join("broken.package"), broken).unwrap();
+        crate::test_support::write_zip(
+            &mods.join("old.ts4script"),
+            &[("old/main.pyc", b"\x9e\x0c\x0d\x0a\0\0\0\0")],
+        );
+    }
+
+    #[test]
+    fn markdown_report_has_every_section() {
+        let dir = tempdir().unwrap();
+        let mods = dir.path().join("Mods");
+        fs::create_dir_all(&mods).unwrap();
+        fixture_library(&mods);
+
+        let report = build_report(
+            &mods,
+            Some("1.105.332.1020"),
+            &CancellationToken::default(),
+            |_, _, _| {},
+        )
+        .unwrap();
+        let output = dir.path().join("report.md");
+        write_report(&report, &output, ReportFormat::Markdown).unwrap();
+        let markdown = fs::read_to_string(&output).unwrap();
+
+        for section in [
+            "# SimsForge library report",
+            "## Summary",
+            "## Resource conflicts",
+            "## Script conflicts",
+            "## Broken packages",
+            "## Compatibility",
+        ] {
+            assert!(markdown.contains(section), "missing {}", section);
+        }
+        assert!(markdown.contains("| Resource conflicts | 1 |"));
+        assert!(markdown.contains("`hair_a.package`, `hair_b.package`"));
+        assert!(markdown.contains("- `broken.package`"));
+        assert!(markdown.contains("- `old.ts4script` (script-version-mismatch)"));
+        // Paths never reveal where the library lives
+        assert!(!markdown.contains(&mods.to_string_lossy().to_string()));
+    }
+
+    #[test]
+    fn json_report_round_trips() {
+        let dir = tempdir().unwrap();
+        fixture_library(dir.path());
+        let steps = std::sync::Mutex::new(Vec::new());
+
+        let report = build_report(
+            dir.path(),
+            None,
+            &CancellationToken::default(),
+            |step, _, _| steps.lock().unwrap().push(step),
+        )
+        .unwrap();
+        let output = dir.path().join("report.json");
+        write_report(&report, &output, ReportFormat::Json).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+
+        assert_eq!(json["counts"]["conflicts"], 1);
+        assert_eq!(json["counts"]["broken_packages"], 1);
+        assert_eq!(json["compatibility"], serde_json::Value::Null);
+        assert_eq!(json["broken_packages"][0]["path"], "broken.package");
+        let mut steps = steps.into_inner().unwrap();
+        steps.dedup();
+        assert_eq!(steps, vec![0, 1]);
+    }
+}
@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+/// Python module layout of a .ts4script archive
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ScriptInspection {
+    /// Fully-qualified module names (`package.module`), sorted
+    pub modules: Vec<String>,
+    /// Whether the archive ships .py sources (not only compiled .pyc)
+    pub has_source: bool,
+}
+
+/// Python version of the compiled modules, from the magic number starting every .pyc
+const PYC_MAGIC_RANGES: [(u16, u16, &str); 10] = [
+    (3190, 3230, "3.3"),
+    (3250, 3310, "3.4"),
+    (3320, 3351, "3.5"),
+    (3360, 3379, "3.6"),
+    (3390, 3399, "3.7"),
+    (3400, 3419, "3.8"),
+    (3420, 3429, "3.9"),
+    (3430, 3449, "3.10"),
+    (3450, 3499, "3.11"),
+    (3500, 3549, "3.12"),
+];
+
+/// List the Python modules a .ts4script archive provides
+#[tauri::command]
+pub fn inspect_ts4script(path: String) -> Result<ScriptInspection, String> {
+    inspect_script(Path::new(&path))
+}
+
+pub fn inspect_script(path: &Path) -> Result<ScriptInspection, String> {
+    let file =
+        File::open(path).map_err(|e| format!("Failed to open script {}: {}", path.display(), e))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| format!("Invalid script archive {}: {}", path.display(), e))?;
+
+    let mut modules = Vec::new();
+    let mut has_source = false;
+
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index_raw(i)
+            .map_err(|e| format!("Failed to read script entry: {}", e))?;
+        if entry.is_dir() {
+            continue;
+        }
+
+        let name = entry.name();
+        has_source |= name.to_lowercase().ends_with(".py");
+        if let Some(module) = module_name(name) {
+            modules.push(module);
+        }
+    }
+
+    // .py and .pyc of the same module count once
+    modules.sort();
+    modules.dedup();
+
+    Ok(ScriptInspection {
+        modules,
+        has_source,
+    })
+}
+
+/// Python versions the archive's .pyc files were compiled with, sorted
+/// Files with an unknown magic number are reported as "unknown"
+pub fn compiled_python_versions(path: &Path) -> Result<Vec<String>, String> {
+    let file =
+        File::open(path).map_err(|e| format!("Failed to open script {}: {}", path.display(), e))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| format!("Invalid script archive {}: {}", path.display(), e))?;
+
+    let mut versions = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read script entry: {}", e))?;
+        if entry.is_dir() || !entry.name().to_lowercase().ends_with(".pyc") {
+            continue;
+        }
+
+        let mut magic = [0u8; 2];
+        let version = match entry.read_exact(&mut magic) {
+            Ok(()) => python_version(u16::from_le_bytes(magic)).unwrap_or("unknown"),
+            Err(_) => "unknown",
+        };
+        versions.push(version.to_string());
+    }
+
+    versions.sort();
+    versions.dedup();
+    Ok(versions)
+}
+
+/// Python version a .pyc magic number belongs to
+fn python_version(magic: u16) -> Option<&'static str> {
+    PYC_MAGIC_RANGES
+        .iter()
+        .find(|(first, last, _)| (*first..=*last).contains(&magic))
+        .map(|(_, _, version)| *version)
+}
+
+/// Map an archive path to the module name Python imports it as
+/// `pkg/sub/mod.pyc` -> `pkg.sub.mod`, `pkg/__init__.py` -> `pkg`,
+/// `pkg/__pycache__/mod.cpython-37.pyc` -> `pkg.mod`
+pub(crate) fn module_name(entry_name: &str) -> Option<String> {
+    let lower = entry_name.to_lowercase();
+    let stem_len = if lower.ends_with(".pyc") {
+        entry_name.len() - 4
+    } else if lower.ends_with(".py") {
+        entry_name.len() - 3
+    } else {
+        return None;
+    };
+
+    let mut parts: Vec<&str> = entry_name[..stem_len]
+        .split(['/', '\\'])
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect();
+
+    if parts.len() >= 2 && parts[parts.len() - 2] == "__pycache__" {
+        let file = parts.pop()?;
+        parts.pop();
+        parts.push(file.split('.').next().unwrap_or(file));
+    }
+    if parts.last() == Some(&"__init__") {
+        parts.pop();
+    }
+
+    (!parts.is_empty()).then(|| parts.join("."))
+}
+
+/// Every .ts4script file under `root`, sorted by path
+pub fn find_scripts(root: &Path) -> Vec<PathBuf> {
+    let mut scripts: Vec<PathBuf> = WalkDir::new(root)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            path.extension()
+                .map(|ext| ext.eq_ignore_ascii_case("ts4script"))
+                .unwrap_or(false)
+        })
+        .collect();
+
+    scripts.sort();
+    scripts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::write_zip;
+    use tempfile::tempdir;
+
+    #[test]
+    fn maps_archive_paths_to_module_names() {
+        assert_eq!(module_name("mod.pyc").as_deref(), Some("mod"));
+        assert_eq!(
+            module_name("pkg/sub/utils.PYC").as_deref(),
+            Some("pkg.sub.utils")
+        );
+        assert_eq!(module_name("pkg/__init__.py").as_deref(), Some("pkg"));
+        assert_eq!(
+            module_name("pkg/__pycache__/core.cpython-37.pyc").as_deref(),
+            Some("pkg.core")
+        );
+        assert_eq!(module_name("__init__.pyc"), None);
+        assert_eq!(module_name("pkg/readme.txt"), None);
+    }
+
+    #[test]
+    fn lists_modules_once() {
+        let dir = tempdir().unwrap();
+        let path = write_zip(
+            &dir.path().join("mod.ts4script"),
+            &[
+                ("mymod/", b""),
+                ("mymod/__init__.pyc", b""),
+                ("mymod/main.pyc", b""),
+                ("mymod/main.py", b""),
+            ],
+        );
+
+        let inspection = inspect_ts4script(path).unwrap();
+
+        assert_eq!(inspection.modules, vec!["mymod", "mymod.main"]);
+        assert!(inspection.has_source);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::dbpf::{self, PackageWriter, NAME_MAP_TYPE, XML_TUNING_TYPES};
+use crate::replace::{same_file, temp_path_for};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+/// Resources only modding tools read, the game ignores them
+const DEBUG_TYPES: [u32; 1] = [
+    // Name map, instance id -> resource name lookup for editors
+    NAME_MAP_TYPE,
+];
+
+/// Savings of a stripped package
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StripReport {
+    /// Input size minus output size
+    pub removed_bytes: u64,
+    /// Debug resources dropped
+    pub removed_count: usize,
+    /// Tuning resources rewritten without their XML comments
+    pub comments_stripped: usize,
+}
+
+/// Write a smaller copy of a package without the resources the game never reads
+/// Drops name maps and strips `<!-- -->` comments from XML tuning; everything else is copied as stored
+#[tauri::command(async)]
+pub fn strip_package_debug(path: String, output: String) -> Result<StripReport, String> {
+    strip_package(Path::new(&path), Path::new(&output))
+}
+
+pub fn strip_package(path: &Path, output: &Path) -> Result<StripReport, String> {
+    if same_file(path, output) {
+        return Err("Output must be a different file than the package".to_string());
+    }
+
+    let temp = temp_path_for(output)?;
+    let stripped = write_stripped(path, &temp).and_then(|report| {
+        fs::rename(&temp, output)
+            .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+        Ok(report)
+    });
+    let (removed_count, comments_stripped) = match stripped {
+        Ok(report) => report,
+        Err(e) => {
+            let _ = fs::remove_file(&temp);
+            return Err(e);
+        }
+    };
+
+    let size = |p: &Path| fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+    Ok(StripReport {
+        removed_bytes: size(path).saturating_sub(size(output)),
+        removed_count,
+        comments_stripped,
+    })
+}
+
+/// Rewrite `path` into `dest`, returns (removed resources, tuning resources stripped of comments)
+fn write_stripped(path: &Path, dest: &Path) -> Result<(usize, usize), String> {
+    let entries = dbpf::read_index(path)?;
+    let mut reader = BufReader::new(
+        File::open(path)
+            .map_err(|e| format!("Failed to open package {}: {}", path.display(), e))?,
+    );
+    let file =
+        File::create(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut writer = PackageWriter::new(BufWriter::new(file))?;
+
+    let mut removed = 0;
+    let mut comments_stripped = 0;
+    for entry in &entries {
+        if DEBUG_TYPES.contains(&entry.key.type_id) {
+            removed += 1;
+            continue;
+        }
+
+        if XML_TUNING_TYPES.contains(&entry.key.type_id) {
+            let data = dbpf::read_resource(&mut reader, entry)?;
+            if let Some(stripped) = strip_xml_comments(&data) {
+                writer.add(entry.key, stripped.as_bytes())?;
+                comments_stripped += 1;
+                continue;
+            }
+        }
+
+        let raw = dbpf::read_raw_resource(&mut reader, entry)?;
+        writer.add_raw(entry, &raw)?;
+    }
+
+    writer
+        .finish()?
+        .into_inner()
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e.error()))?
+        .sync_all()
+        .map_err(|e| format!("Failed to sync {}: {}", dest.display(), e))?;
+    Ok((removed, comments_stripped))
+}
+
+/// XML text without its comments, None when it is not XML or has no comment
+fn strip_xml_comments(data: &[u8]) -> Option<String> {
+    let text = std::str::from_utf8(data).ok()?;
+    if !text.trim_start().starts_with('<') || !text.contains("<!--") {
+        return None;
+    }
+
+    let mut stripped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("<!--") {
+        // An unterminated comment means the XML is not what we think, leave it alone
+        let end = rest[start..].find("-->")? + start + 3;
+        stripped.push_str(&rest[..start]);
+        rest = &rest[end..];
+    }
+    stripped.push_str(rest);
+    Some(stripped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbpf::test_support::{build_package, key};
+    use tempfile::tempdir;
+
+    #[test]
+    fn keeps_functional_resources_and_drops_debug_ones() {
+        let dir = tempdir().unwrap();
+        let package = dir.path().join("trait.package");
+        let output = dir.path().join("trait_stripped.package");
+        let tuning = key(0x0333406C, 0, 0x80000000_00000001);
+        let simdata = key(0x545AC67A, 0, 0x80000000_00000001);
+        let name_map = key(NAME_MAP_TYPE, 0, 0x80000000_00000001);
+        let interaction = key(0xE882D22F, 0, 0x80000000_00000002);
+        let commented = format!(
+            "<?xml version=\"1.0\"?>\n<!-- {} -->\n<I n=\"creator:Trait\"><!-- note --><T n=\"x\">1</T></I>",
+            "exported with debug notes ".repeat(40)
+        );
+        fs::write(
+            &package,
+            build_package(&[
+                (tuning, commented.into_bytes()),
+                (simdata, b"simdata bytes".to_vec()),
+                (name_map, vec![0u8; 2048]),
+                (
+                    interaction,
+                    b"<I n=\"creator:Hug\"><!-- todo --></I>".to_vec(),
+                ),
+            ]),
+        )
+        .unwrap();
+
+        let report = strip_package_debug(
+            package.to_string_lossy().to_string(),
+            output.to_string_lossy().to_string(),
+        )
+        .unwrap();
+
+        assert_eq!(report.removed_count, 1);
+        assert_eq!(report.comments_stripped, 2);
+        assert_eq!(
+            report.removed_bytes,
+            fs::metadata(&package).unwrap().len() - fs::metadata(&output).unwrap().len()
+        );
+        assert!(report.removed_bytes > 2048);
+
+        let entries = dbpf::read_index(&output).unwrap();
+        let mut reader = BufReader::new(File::open(&output).unwrap());
+        assert_eq!(entries.len(), 3);
+        assert!(entries.iter().all(|entry| entry.key != name_map));
+        for entry in &entries {
+            let data = dbpf::read_resource(&mut reader, entry).unwrap();
+            if entry.key == tuning {
+                assert_eq!(
+                    String::from_utf8(data).unwrap(),
+                    "<?xml version=\"1.0\"?>\n\n<I n=\"creator:Trait\"><T n=\"x\">1</T></I>"
+                );
+            } else if entry.key == interaction {
+                assert_eq!(data, b"<I n=\"creator:Hug\"></I>");
+            } else {
+                assert_eq!(data, b"simdata bytes");
+            }
+        }
+    }
+}
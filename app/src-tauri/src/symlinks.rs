@@ -0,0 +1,285 @@
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tauri::Emitter;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+/// Progress is emitted every this many entries scanned (per root)
+const PROGRESS_INTERVAL: usize = 1024;
+
+/// Windows error when creating a symlink without the privilege (no admin, no developer mode)
+#[cfg(target_os = "windows")]
+const ERROR_PRIVILEGE_NOT_HELD: i32 = 1314;
+
+/// How `create_symlink` links a mod folder on this platform
+#[cfg(target_os = "windows")]
+const LINK_METHOD: &str = "directory_symlink";
+#[cfg(not(target_os = "windows"))]
+const LINK_METHOD: &str = "symlink";
+
+/// Link (symlink or junction) found by an audit
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SymlinkAuditEntry {
+    pub path: String,
+    /// Target as stored in the link
+    pub target: String,
+    /// The target doesn't exist or the link chain loops
+    pub is_broken: bool,
+    /// Root the link was found under, as passed in `roots`
+    pub profile: String,
+}
+
+/// Whether mod folders can be linked into a location
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SymlinkCapability {
+    pub supported: bool,
+    /// Kind of link that was created, None when none could be
+    pub method: Option<String>,
+    /// Linking failed for lack of privilege and would work elevated (or in developer mode)
+    pub needs_admin: bool,
+    /// Why the test link could not be created
+    pub error: Option<String>,
+}
+
+/// Payload of `symlink-audit://progress`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SymlinkAuditProgress {
+    pub roots_done: usize,
+    pub roots_total: usize,
+    pub entries_scanned: usize,
+}
+
+/// Find every link under several profile/mods roots and flag the dangling ones
+/// Roots are scanned in parallel, missing roots are skipped
+/// Links are not followed during the scan, so link cycles can't trap it
+/// Emits `symlink-audit://progress` while scanning
+#[tauri::command(async)]
+pub fn audit_all_symlinks(
+    app_handle: tauri::AppHandle,
+    roots: Vec<String>,
+) -> Vec<SymlinkAuditEntry> {
+    audit_roots(&roots, |progress| {
+        let _ = app_handle.emit("symlink-audit://progress", progress);
+    })
+}
+
+/// Try linking a throwaway folder inside `test_dir` the way installs do
+/// Lets the app fall back to copying mods up front where links can't be created
+#[tauri::command(async)]
+pub fn check_symlink_capability(test_dir: String) -> SymlinkCapability {
+    probe_symlinks(Path::new(&test_dir))
+}
+
+pub fn probe_symlinks(test_dir: &Path) -> SymlinkCapability {
+    let probe = test_dir.join(format!(".simsforge-link-test-{}", Uuid::new_v4()));
+    let outcome = try_link(&probe);
+    let _ = fs::remove_dir_all(&probe);
+
+    match outcome {
+        Ok(()) => SymlinkCapability {
+            supported: true,
+            method: Some(LINK_METHOD.to_string()),
+            needs_admin: false,
+            error: None,
+        },
+        Err(e) => SymlinkCapability {
+            supported: false,
+            method: None,
+            needs_admin: lacks_link_privilege(&e),
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// Link a folder holding a marker file and read the marker back through the link
+fn try_link(probe: &Path) -> io::Result<()> {
+    let source = probe.join("source");
+    let link = probe.join("link");
+    fs::create_dir_all(&source)?;
+    fs::write(source.join("marker"), b"link test")?;
+
+    #[cfg(target_os = "windows")]
+    std::os::windows::fs::symlink_dir(&source, &link)?;
+    #[cfg(not(target_os = "windows"))]
+    std::os::unix::fs::symlink(&source, &link)?;
+
+    fs::read(link.join("marker"))?;
+    // Removing the link must leave the source alone
+    #[cfg(target_os = "windows")]
+    fs::remove_dir(&link)?;
+    #[cfg(not(target_os = "windows"))]
+    fs::remove_file(&link)?;
+    if source.join("marker").is_file() {
+        Ok(())
+    } else {
+        Err(io::Error::other("removing the link deleted its target"))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn lacks_link_privilege(error: &io::Error) -> bool {
+    error.raw_os_error() == Some(ERROR_PRIVILEGE_NOT_HELD)
+}
+
+/// Unix symlinks never need elevation, failures come from the location itself
+#[cfg(not(target_os = "windows"))]
+fn lacks_link_privilege(_error: &io::Error) -> bool {
+    false
+}
+
+/// Audit `roots`, results ordered by root (in the given order) then path
+pub fn audit_roots(
+    roots: &[String],
+    on_progress: impl Fn(SymlinkAuditProgress) + Sync,
+) -> Vec<SymlinkAuditEntry> {
+    let roots_done = AtomicUsize::new(0);
+    let entries_scanned = AtomicUsize::new(0);
+    let report = |roots_done: usize| {
+        on_progress(SymlinkAuditProgress {
+            roots_done,
+            roots_total: roots.len(),
+            entries_scanned: entries_scanned.load(Ordering::Relaxed),
+        })
+    };
+
+    roots
+        .par_iter()
+        .map(|root| {
+            let mut links = Vec::new();
+
+            // follow_links(false): a link to a directory is reported, never descended into
+            for entry in WalkDir::new(root)
+                .min_depth(1)
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+            {
+                if entries_scanned
+                    .fetch_add(1, Ordering::Relaxed)
+                    .is_multiple_of(PROGRESS_INTERVAL)
+                {
+                    report(roots_done.load(Ordering::Relaxed));
+                }
+                if entry.path_is_symlink() {
+                    links.push(inspect_link(entry.path(), root));
+                }
+            }
+
+            links.sort_by(|a, b| a.path.cmp(&b.path));
+            report(roots_done.fetch_add(1, Ordering::Relaxed) + 1);
+            links
+        })
+        .collect::<Vec<_>>()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+fn inspect_link(path: &Path, root: &str) -> SymlinkAuditEntry {
+    let target = fs::read_link(path)
+        .map(|target| target.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    SymlinkAuditEntry {
+        path: path.to_string_lossy().to_string(),
+        target,
+        // Fails for missing targets and for chains that loop back on themselves
+        is_broken: fs::canonicalize(path).is_err(),
+        profile: root.to_string(),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use std::sync::Mutex;
+    use tempfile::tempdir;
+
+    #[test]
+    fn audits_links_across_roots() {
+        let dir = tempdir().unwrap();
+        let cache = dir.path().join("cache");
+        fs::create_dir_all(cache.join("hair")).unwrap();
+        fs::create_dir_all(cache.join("chair")).unwrap();
+
+        let first = dir.path().join("profile_a");
+        let second = dir.path().join("profile_b");
+        fs::create_dir_all(first.join("CAS")).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        symlink(cache.join("hair"), first.join("CAS/hair")).unwrap();
+        symlink(cache.join("removed"), first.join("gone")).unwrap();
+        symlink(cache.join("chair"), second.join("chair")).unwrap();
+        // A directory link back to its own root and a link chain that loops
+        symlink(&second, second.join("self")).unwrap();
+        symlink(second.join("loop_b"), second.join("loop_a")).unwrap();
+        symlink(second.join("loop_a"), second.join("loop_b")).unwrap();
+
+        let roots = vec![
+            first.to_string_lossy().to_string(),
+            second.to_string_lossy().to_string(),
+            dir.path().join("missing").to_string_lossy().to_string(),
+        ];
+        let progress = Mutex::new(Vec::new());
+        let links = audit_roots(&roots, |p| progress.lock().unwrap().push(p));
+
+        let summary: Vec<(String, bool, &str)> = links
+            .iter()
+            .map(|link| {
+                let name = Path::new(&link.path).strip_prefix(dir.path()).unwrap();
+                (
+                    name.to_string_lossy().to_string(),
+                    link.is_broken,
+                    link.profile.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("profile_a/CAS/hair".to_string(), false, roots[0].as_str()),
+                ("profile_a/gone".to_string(), true, roots[0].as_str()),
+                ("profile_b/chair".to_string(), false, roots[1].as_str()),
+                ("profile_b/loop_a".to_string(), true, roots[1].as_str()),
+                ("profile_b/loop_b".to_string(), true, roots[1].as_str()),
+                ("profile_b/self".to_string(), false, roots[1].as_str()),
+            ]
+        );
+        assert_eq!(links[1].target, cache.join("removed").to_string_lossy());
+
+        let progress = progress.into_inner().unwrap();
+        assert_eq!(progress.iter().map(|p| p.roots_done).max(), Some(3));
+        assert!(progress.iter().all(|p| p.roots_total == 3));
+    }
+
+    #[test]
+    fn links_work_in_a_writable_folder() {
+        let dir = tempdir().unwrap();
+
+        let capability = probe_symlinks(dir.path());
+
+        assert!(capability.supported);
+        assert_eq!(capability.method.as_deref(), Some("symlink"));
+        assert!(!capability.needs_admin);
+        // The probe cleans up after itself
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn reports_locations_where_links_fail() {
+        let dir = tempdir().unwrap();
+        // A file where a folder is expected, nothing can be created inside it
+        let not_a_folder = dir.path().join("Mods");
+        fs::write(&not_a_folder, b"").unwrap();
+
+        let capability = probe_symlinks(&not_a_folder);
+
+        assert!(!capability.supported);
+        assert_eq!(capability.method, None);
+        assert!(!capability.needs_admin);
+        assert!(capability.error.is_some());
+    }
+}
@@ -0,0 +1,74 @@
+//! Fixtures shared by the unit tests of several modules
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+/// Write a ZIP archive with the given (name, content) entries and return its path as a String
+pub fn write_zip(path: &Path, entries: &[(&str, &[u8])]) -> String {
+    let mut writer = ZipWriter::new(File::create(path).unwrap());
+
+    for (name, content) in entries {
+        if name.ends_with('/') {
+            writer.add_directory(*name, FileOptions::default()).unwrap();
+        } else {
+            writer.start_file(*name, FileOptions::default()).unwrap();
+            writer.write_all(content).unwrap();
+        }
+    }
+
+    writer.finish().unwrap();
+    path.to_string_lossy().to_string()
+}
+
+/// Serve `body` with range support, recording the Range header of every request
+pub fn range_server(body: Vec<u8>) -> (String, Arc<Mutex<Vec<Option<String>>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/pack.zip", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = requests.clone();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut range = None;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("range: bytes=") {
+                    range = Some(value.trim().to_string());
+                }
+            }
+            seen.lock().unwrap().push(range.clone());
+
+            let (status, slice) = match range.as_deref().and_then(|r| r.split_once('-')) {
+                Some((start, end)) => {
+                    let start: usize = start.parse().unwrap();
+                    let end = end.parse::<usize>().map(|e| e + 1).unwrap_or(body.len());
+                    if start >= body.len() {
+                        ("416 Range Not Satisfiable", &body[..0])
+                    } else {
+                        ("206 Partial Content", &body[start..end])
+                    }
+                }
+                None => ("200 OK", &body[..]),
+            };
+            let head = format!(
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nAccept-Ranges: bytes\r\nConnection: close\r\n\r\n",
+                status,
+                slice.len()
+            );
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(slice).unwrap();
+        }
+    });
+
+    (url, requests)
+}
@@ -0,0 +1,138 @@
+use crate::replace::temp_path_for;
+use encoding_rs::Encoding;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Outcome of `normalize_text_file`
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct TextNormalization {
+    /// Encoding the file was in, e.g. "UTF-8", "windows-1252", "Shift_JIS"
+    pub detected_encoding: String,
+    /// Whether the file was rewritten as UTF-8
+    pub converted: bool,
+    /// Copy of the original bytes, when converted
+    pub backup: Option<String>,
+}
+
+/// Re-save a text sidecar (readme, descriptor) in a legacy encoding as UTF-8
+/// The encoding is detected from the content; the original is kept next to it as `<name>.bak`
+/// and files already in UTF-8 are left untouched
+#[tauri::command(async)]
+pub fn normalize_text_file(path: String) -> Result<TextNormalization, String> {
+    normalize_file(Path::new(&path))
+}
+
+pub fn normalize_file(path: &Path) -> Result<TextNormalization, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let encoding = detect_encoding(&bytes);
+    if encoding == encoding_rs::UTF_8 && std::str::from_utf8(&bytes).is_ok() {
+        return Ok(TextNormalization {
+            detected_encoding: encoding.name().to_string(),
+            converted: false,
+            backup: None,
+        });
+    }
+
+    let (text, _, had_errors) = encoding.decode(&bytes);
+    if had_errors {
+        return Err(format!(
+            "{} is not valid {} text",
+            path.display(),
+            encoding.name()
+        ));
+    }
+
+    let backup = backup_path(path);
+    fs::copy(path, &backup).map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+
+    let temp = temp_path_for(path)?;
+    let result = fs::write(&temp, text.as_bytes()).and_then(|_| fs::rename(&temp, path));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp);
+        return Err(format!("Failed to rewrite {}: {}", path.display(), e));
+    }
+
+    Ok(TextNormalization {
+        detected_encoding: encoding.name().to_string(),
+        converted: true,
+        backup: Some(backup.to_string_lossy().to_string()),
+    })
+}
+
+/// Encoding announced by a byte order mark, UTF-8 when the bytes are valid UTF-8,
+/// otherwise guessed from the content (the detector falls back to windows-1252 for plain ASCII)
+fn detect_encoding(bytes: &[u8]) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return encoding;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return encoding_rs::UTF_8;
+    }
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(bytes, true);
+    detector.guess(None, true)
+}
+
+/// `<name>.bak`, or `<name>.bak.2`, `.bak.3`... when earlier backups exist
+fn backup_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    std::iter::once(path.with_file_name(format!("{}.bak", name)))
+        .chain((2..).map(|n| path.with_file_name(format!("{}.bak.{}", name, n))))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| path.with_file_name(format!("{}.bak", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// "Créée par Renée – merci à tous, voilà les modèles de café élégants" in windows-1252
+    const CP1252_NOTE: &[u8] = b"Cr\xe9\xe9e par Ren\xe9e \x96 merci \xe0 tous, voil\xe0 les mod\xe8les de caf\xe9 \xe9l\xe9gants";
+
+    #[test]
+    fn converts_cp1252_notes_to_utf8() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("readme.txt");
+        fs::write(&path, CP1252_NOTE).unwrap();
+
+        let result = normalize_file(&path).unwrap();
+
+        assert_eq!(result.detected_encoding, "windows-1252");
+        assert!(result.converted);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "Créée par Renée – merci à tous, voilà les modèles de café élégants"
+        );
+        let backup = result.backup.unwrap();
+        assert!(backup.ends_with("readme.txt.bak"));
+        assert_eq!(fs::read(&backup).unwrap(), CP1252_NOTE);
+
+        // Already UTF-8 now, left alone
+        let again = normalize_file(&path).unwrap();
+        assert_eq!(again.detected_encoding, "UTF-8");
+        assert!(!again.converted);
+        assert_eq!(again.backup, None);
+        assert!(!dir.path().join("readme.txt.bak.2").exists());
+    }
+
+    #[test]
+    fn leaves_ascii_files_alone() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("readme.txt");
+        fs::write(&path, b"Install in Mods, max one folder deep.\r\n").unwrap();
+
+        let result = normalize_file(&path).unwrap();
+
+        assert_eq!(result.detected_encoding, "UTF-8");
+        assert!(!result.converted);
+        assert_eq!(result.backup, None);
+        assert!(!dir.path().join("readme.txt.bak").exists());
+    }
+}
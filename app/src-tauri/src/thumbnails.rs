@@ -0,0 +1,200 @@
+use crate::dbpf::{self, IndexEntry};
+use base64::Engine;
+use image::{ImageFormat, RgbaImage};
+use image_dds::ddsfile::Dds;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, Cursor};
+use std::path::Path;
+
+/// Thumbnail resource types, in order of preference
+/// Dedicated thumbnails come first, plain DDS textures are only a fallback
+const THUMBNAIL_TYPES: [u32; 8] = [
+    0x3C1AF1F2, // CAS part thumbnail
+    0x5B282D45, // Body part thumbnail
+    0x3C2A8647, // Build/Buy thumbnail
+    0x0580A2B6, // Build/Buy thumbnail (large)
+    0x0580A2B5, // Build/Buy thumbnail (medium)
+    0x0580A2B4, // Build/Buy thumbnail (small)
+    0xCD9DE247, // Sim/household thumbnail
+    0x00B2D882, // DDS image (texture)
+];
+
+const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+const JPEG_MAGIC: &[u8] = b"\xFF\xD8\xFF";
+const DDS_MAGIC: &[u8] = b"DDS ";
+
+/// Preview image extracted from a package
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PackageThumbnail {
+    /// PNG-encoded image, base64 for direct use in an <img> data URL
+    pub png_bytes_base64: String,
+    pub width: u32,
+    pub height: u32,
+    /// Resource the image was read from
+    pub resource_key: String,
+}
+
+/// Extract a preview image from a package, converted to PNG
+/// Returns None when the package has no thumbnail or texture that can be decoded
+#[tauri::command(async)]
+pub fn extract_package_thumbnail(path: String) -> Option<PackageThumbnail> {
+    let path = Path::new(&path);
+    let entries = dbpf::read_index(path).ok()?;
+    let mut reader = BufReader::new(File::open(path).ok()?);
+
+    let mut candidates: Vec<&IndexEntry> = entries
+        .iter()
+        .filter(|entry| THUMBNAIL_TYPES.contains(&entry.key.type_id))
+        .collect();
+    // Preferred type first, then the largest image of that type
+    candidates.sort_by_key(|entry| {
+        let priority = THUMBNAIL_TYPES
+            .iter()
+            .position(|&t| t == entry.key.type_id)
+            .unwrap_or(THUMBNAIL_TYPES.len());
+        (priority, std::cmp::Reverse(entry.mem_size))
+    });
+
+    // Unsupported variants (e.g. shuffled DST textures) are skipped in favour of the next candidate
+    candidates.into_iter().find_map(|entry| {
+        let data = dbpf::read_resource(&mut reader, entry).ok()?;
+        let image = decode_image(&data)?;
+        let png = encode_png(&image)?;
+
+        Some(PackageThumbnail {
+            png_bytes_base64: base64::engine::general_purpose::STANDARD.encode(png),
+            width: image.width(),
+            height: image.height(),
+            resource_key: entry.key.to_string(),
+        })
+    })
+}
+
+/// Decode a thumbnail resource, sniffing PNG, JPEG (JFIF thumbnails) or DDS (BC1-BC7) data
+fn decode_image(data: &[u8]) -> Option<RgbaImage> {
+    if data.starts_with(DDS_MAGIC) {
+        let dds = Dds::read(Cursor::new(data)).ok()?;
+        return image_dds::image_from_dds(&dds, 0).ok();
+    }
+
+    let format = if data.starts_with(PNG_MAGIC) {
+        ImageFormat::Png
+    } else if data.starts_with(JPEG_MAGIC) {
+        ImageFormat::Jpeg
+    } else {
+        return None;
+    };
+
+    image::load_from_memory_with_format(data, format)
+        .ok()
+        .map(|image| image.to_rgba8())
+}
+
+fn encode_png(image: &RgbaImage) -> Option<Vec<u8>> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .ok()?;
+    Some(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbpf::test_support::{build_package, build_zlib_package, key};
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// 4x4 BC1 (DXT1) DDS made of a single solid red block
+    fn red_bc1_dds() -> Vec<u8> {
+        let mut dds = DDS_MAGIC.to_vec();
+        let mut header = [0u32; 31];
+        header[0] = 124; // header size
+        header[1] = 0x1 | 0x2 | 0x4 | 0x1000 | 0x80000; // caps, height, width, pixel format, linear size
+        header[2] = 4; // height
+        header[3] = 4; // width
+        header[4] = 8; // linear size
+        header[18] = 32; // pixel format size
+        header[19] = 0x4; // DDPF_FOURCC
+        header[20] = u32::from_le_bytes(*b"DXT1");
+        header[26] = 0x1000; // DDSCAPS_TEXTURE
+        for value in header {
+            dds.extend_from_slice(&value.to_le_bytes());
+        }
+        // color0 = color1 = pure red (RGB565), all indices 0
+        dds.extend_from_slice(&[0x00, 0xF8, 0x00, 0xF8, 0, 0, 0, 0]);
+        dds
+    }
+
+    fn decode_thumbnail(thumbnail: &PackageThumbnail) -> RgbaImage {
+        let png = base64::engine::general_purpose::STANDARD
+            .decode(&thumbnail.png_bytes_base64)
+            .unwrap();
+        image::load_from_memory_with_format(&png, ImageFormat::Png)
+            .unwrap()
+            .to_rgba8()
+    }
+
+    #[test]
+    fn decodes_bc1_thumbnail_to_png() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("hair.package");
+        fs::write(
+            &path,
+            build_zlib_package(&[
+                (key(0x034AEECB, 0, 1), b"caspart".to_vec()),
+                (key(0x3C1AF1F2, 0, 1), red_bc1_dds()),
+            ]),
+        )
+        .unwrap();
+
+        let thumbnail = extract_package_thumbnail(path.to_string_lossy().to_string()).unwrap();
+
+        assert_eq!((thumbnail.width, thumbnail.height), (4, 4));
+        assert_eq!(thumbnail.resource_key, key(0x3C1AF1F2, 0, 1).to_string());
+        let image = decode_thumbnail(&thumbnail);
+        assert_eq!(image.get_pixel(2, 2).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn prefers_dedicated_thumbnail_over_texture() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("chair.package");
+
+        let mut png = Vec::new();
+        RgbaImage::from_pixel(2, 3, image::Rgba([0, 0, 255, 255]))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        fs::write(
+            &path,
+            build_package(&[
+                (key(0x00B2D882, 0, 1), red_bc1_dds()),
+                (key(0x3C2A8647, 0, 2), png),
+            ]),
+        )
+        .unwrap();
+
+        let thumbnail = extract_package_thumbnail(path.to_string_lossy().to_string()).unwrap();
+
+        assert_eq!((thumbnail.width, thumbnail.height), (2, 3));
+        assert_eq!(
+            decode_thumbnail(&thumbnail).get_pixel(0, 0).0,
+            [0, 0, 255, 255]
+        );
+    }
+
+    #[test]
+    fn returns_none_without_thumbnail() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("tuning.package");
+        fs::write(
+            &path,
+            build_package(&[(key(0x0333406C, 0, 1), b"<I/>".to_vec())]),
+        )
+        .unwrap();
+
+        assert!(extract_package_thumbnail(path.to_string_lossy().to_string()).is_none());
+        assert!(extract_package_thumbnail("missing.package".to_string()).is_none());
+    }
+}
@@ -0,0 +1,335 @@
+use crate::create_symlink;
+use crate::install::{self, InstallRequest};
+use crate::manifest::{self, remove_path, InstallRecord, Manifest};
+use crate::progress::ProgressTracker;
+use crate::replace::temp_path_for;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One step of a mod transaction
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ModOp {
+    Install(InstallRequest),
+    /// Remove an installed mod's files and manifest record
+    Uninstall {
+        id: String,
+    },
+    /// Link `source` to `target` (`target` must not exist yet)
+    Link {
+        source: String,
+        target: String,
+    },
+    /// Remove the link at `target`, kept aside until the transaction commits
+    Unlink {
+        target: String,
+    },
+}
+
+/// Outcome of `apply_mod_transaction`
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct TransactionResult {
+    pub success: bool,
+    /// Operations applied before the failure (all of them on success)
+    pub completed: usize,
+    /// Index of the operation that failed
+    pub failed_op: Option<usize>,
+    pub error: Option<String>,
+    /// Steps that could not be undone, the library may need manual repair when not empty
+    pub rollback_errors: Vec<String>,
+}
+
+/// Reverse action journaled before each change
+#[derive(Debug)]
+enum Undo {
+    /// Something the transaction created
+    RemovePath(PathBuf),
+    /// A file moved aside, put back where it was
+    Restore { original: PathBuf, staged: PathBuf },
+    /// A manifest record removed or overwritten
+    RestoreRecord(InstallRecord),
+    /// A manifest record the transaction added
+    ForgetRecord(String),
+}
+
+/// Apply installs, uninstalls, links and unlinks as a whole
+/// Removed files are moved aside until every operation succeeded, then deleted (to the
+/// trash unless `use_trash` is false); any failure undoes the completed steps in reverse
+#[tauri::command(async)]
+pub fn apply_mod_transaction(
+    app_handle: tauri::AppHandle,
+    ops: Vec<ModOp>,
+    use_trash: Option<bool>,
+) -> Result<TransactionResult, String> {
+    let mut manifest = manifest::open_app_manifest(&app_handle)?;
+    Ok(apply_transaction(
+        &ops,
+        &mut manifest,
+        use_trash.unwrap_or(true),
+    ))
+}
+
+pub fn apply_transaction(
+    ops: &[ModOp],
+    manifest: &mut Manifest,
+    use_trash: bool,
+) -> TransactionResult {
+    let mut journal = Vec::new();
+    let mut result = TransactionResult::default();
+
+    for (index, op) in ops.iter().enumerate() {
+        if let Err(e) = apply_op(op, manifest, &mut journal) {
+            result.failed_op = Some(index);
+            result.error = Some(e);
+            result.rollback_errors = rollback(journal, manifest);
+            return result;
+        }
+        result.completed += 1;
+    }
+
+    // Committed: the staged files are no longer needed
+    for undo in journal {
+        if let Undo::Restore { staged, .. } = undo {
+            if let Err(e) = remove_path(&staged, use_trash) {
+                eprintln!("Warning: Failed to clean up staged file: {}", e);
+            }
+        }
+    }
+    result.success = true;
+    result
+}
+
+fn apply_op(op: &ModOp, manifest: &mut Manifest, journal: &mut Vec<Undo>) -> Result<(), String> {
+    match op {
+        ModOp::Install(request) => {
+            journal.push(match manifest.get_install(&request.id)? {
+                Some(previous) => Undo::RestoreRecord(previous),
+                None => Undo::ForgetRecord(request.id.clone()),
+            });
+
+            let dest_dir = PathBuf::from(&request.dest_dir);
+            if exists(&dest_dir) {
+                stage(&dest_dir, journal)?;
+            }
+            journal.push(Undo::RemovePath(dest_dir));
+
+            if let Some(link_path) = &request.link_path {
+                let link_path = PathBuf::from(link_path);
+                if exists(&link_path) {
+                    return Err(format!("{} already exists", link_path.display()));
+                }
+                journal.push(Undo::RemovePath(link_path));
+            }
+
+            install::install(
+                request,
+                manifest,
+                &ProgressTracker::new(&request.id, |_| {}),
+            )?;
+        }
+        ModOp::Uninstall { id } => {
+            let record = manifest
+                .get_install(id)?
+                .ok_or_else(|| format!("{} not found in manifest", id))?;
+
+            for file in &record.files {
+                let path = Path::new(&file.path);
+                if exists(path) {
+                    stage(path, journal)?;
+                }
+            }
+            manifest.remove_install(id)?;
+            journal.push(Undo::RestoreRecord(record));
+        }
+        ModOp::Link { source, target } => {
+            let target_path = PathBuf::from(target);
+            if exists(&target_path) {
+                return Err(format!("{} already exists", target));
+            }
+            create_symlink(source.clone(), target.clone())?;
+            journal.push(Undo::RemovePath(target_path));
+        }
+        ModOp::Unlink { target } => {
+            let target_path = Path::new(target);
+            if !target_path.is_symlink() {
+                return Err(format!("{} is not a link", target));
+            }
+            stage(target_path, journal)?;
+        }
+    }
+    Ok(())
+}
+
+/// Move `path` aside (same folder, so never across volumes) and journal how to put it back
+fn stage(path: &Path, journal: &mut Vec<Undo>) -> Result<(), String> {
+    let staged = temp_path_for(path)?;
+    fs::rename(path, &staged)
+        .map_err(|e| format!("Failed to move {} aside: {}", path.display(), e))?;
+    journal.push(Undo::Restore {
+        original: path.to_path_buf(),
+        staged,
+    });
+    Ok(())
+}
+
+/// Undo the journal in reverse, returning the steps that failed
+fn rollback(journal: Vec<Undo>, manifest: &mut Manifest) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    for undo in journal.into_iter().rev() {
+        let outcome = match undo {
+            Undo::RemovePath(path) if exists(&path) => remove_path(&path, false),
+            Undo::RemovePath(_) => Ok(()),
+            Undo::Restore { original, staged } => fs::rename(&staged, &original)
+                .map_err(|e| format!("Failed to restore {}: {}", original.display(), e)),
+            Undo::RestoreRecord(record) => manifest
+                .upsert_install(&record)
+                .and_then(|_| manifest.set_tags(&record.id, &record.tags))
+                .and_then(|_| manifest.set_note(&record.id, record.note.as_deref())),
+            Undo::ForgetRecord(id) => manifest.remove_install(&id),
+        };
+        if let Err(e) = outcome {
+            errors.push(e);
+        }
+    }
+
+    errors
+}
+
+/// Exists, or is a link (possibly dangling)
+fn exists(path: &Path) -> bool {
+    path.exists() || path.is_symlink()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::ExtractOptions;
+    use crate::manifest::InstalledFile;
+    use crate::test_support::write_zip;
+    use tempfile::tempdir;
+
+    fn request(dir: &Path, id: &str, zip_path: String) -> InstallRequest {
+        InstallRequest {
+            id: id.to_string(),
+            name: id.to_string(),
+            version: None,
+            zip_path,
+            dest_dir: dir.join("library").join(id).to_string_lossy().to_string(),
+            link_path: Some(dir.join("Mods").join(id).to_string_lossy().to_string()),
+            options: ExtractOptions::default(),
+        }
+    }
+
+    /// An installed mod "old" with one file, tagged and annotated
+    fn installed_mod(dir: &Path, manifest: &mut Manifest) -> PathBuf {
+        let file = dir.join("library/old/old.package");
+        fs::create_dir_all(file.parent().unwrap()).unwrap();
+        fs::write(&file, b"old mod").unwrap();
+        manifest
+            .upsert_install(&InstallRecord {
+                id: "old".to_string(),
+                name: "Old".to_string(),
+                version: Some("1.0".to_string()),
+                installed_at: 1,
+                files: vec![InstalledFile {
+                    path: file.to_string_lossy().to_string(),
+                    hash: None,
+                }],
+                tags: Vec::new(),
+                note: None,
+            })
+            .unwrap();
+        manifest.set_tags("old", &["keep".to_string()]).unwrap();
+        manifest.set_note("old", Some("favourite")).unwrap();
+        file
+    }
+
+    #[test]
+    fn failure_mid_transaction_rolls_everything_back() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Mods")).unwrap();
+        let mut manifest = Manifest::open_in_memory().unwrap();
+        let old_file = installed_mod(dir.path(), &mut manifest);
+        let zip_path = write_zip(&dir.path().join("new.zip"), &[("new.package", b"DBPF")]);
+        let shared = dir.path().join("shared");
+        fs::create_dir_all(&shared).unwrap();
+        let link = dir.path().join("Mods/shared");
+
+        let ops = vec![
+            ModOp::Install(request(dir.path(), "new", zip_path)),
+            ModOp::Uninstall {
+                id: "old".to_string(),
+            },
+            ModOp::Link {
+                source: shared.to_string_lossy().to_string(),
+                target: link.to_string_lossy().to_string(),
+            },
+            ModOp::Install(request(
+                dir.path(),
+                "broken",
+                dir.path().join("missing.zip").to_string_lossy().to_string(),
+            )),
+        ];
+
+        let result = apply_transaction(&ops, &mut manifest, false);
+
+        assert!(!result.success);
+        assert_eq!(result.completed, 3);
+        assert_eq!(result.failed_op, Some(3));
+        assert!(result.error.is_some());
+        assert!(result.rollback_errors.is_empty());
+
+        // Everything created is gone
+        assert!(!dir.path().join("library/new").exists());
+        assert!(!dir.path().join("library/broken").exists());
+        assert!(!exists(&dir.path().join("Mods/new")));
+        assert!(!exists(&link));
+        assert!(manifest.get_install("new").unwrap().is_none());
+
+        // Everything removed is back, manifest row included
+        assert_eq!(fs::read(&old_file).unwrap(), b"old mod");
+        let old = manifest.get_install("old").unwrap().unwrap();
+        assert_eq!(old.version.as_deref(), Some("1.0"));
+        assert_eq!(old.tags, vec!["keep"]);
+        assert_eq!(old.note.as_deref(), Some("favourite"));
+        assert_eq!(fs::read_dir(old_file.parent().unwrap()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn successful_transaction_commits_and_cleans_staging() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("Mods")).unwrap();
+        let mut manifest = Manifest::open_in_memory().unwrap();
+        let old_file = installed_mod(dir.path(), &mut manifest);
+        let zip_path = write_zip(&dir.path().join("new.zip"), &[("new.package", b"DBPF")]);
+
+        let ops = vec![
+            ModOp::Uninstall {
+                id: "old".to_string(),
+            },
+            ModOp::Install(request(dir.path(), "new", zip_path)),
+        ];
+        let result = apply_transaction(&ops, &mut manifest, false);
+
+        assert!(result.success);
+        assert_eq!(result.completed, 2);
+        assert!(dir.path().join("Mods/new/new.package").exists());
+        assert!(manifest.get_install("new").unwrap().is_some());
+        assert!(manifest.get_install("old").unwrap().is_none());
+        assert_eq!(fs::read_dir(old_file.parent().unwrap()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn ops_deserialize_from_tagged_json() {
+        let ops: Vec<ModOp> = serde_json::from_str(
+            r#"[{"op": "uninstall", "id": "a"}, {"op": "link", "source": "s", "target": "t"},
+                {"op": "unlink", "target": "t"}]"#,
+        )
+        .unwrap();
+        assert!(matches!(&ops[0], ModOp::Uninstall { id } if id == "a"));
+        assert!(matches!(&ops[1], ModOp::Link { .. }));
+        assert!(matches!(&ops[2], ModOp::Unlink { target } if target == "t"));
+    }
+}
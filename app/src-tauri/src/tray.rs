@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Extensions of the files the game writes to the Tray folder
+const TRAY_EXTENSIONS: [&str; 8] = [
+    "trayitem",
+    "householdbinary",
+    "hhi",
+    "sgi",
+    "blueprint",
+    "bpi",
+    "room",
+    "rmi",
+];
+
+/// What a tray group contains, from the data file present next to its `.trayitem`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TrayItemKind {
+    Household,
+    Lot,
+    Room,
+}
+
+/// Files sharing one tray id, e.g. `0x00000001!0x00e3f0a6a0b52ab2.trayitem`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TrayGroup {
+    /// Instance id after the `!`, lowercase
+    pub id: String,
+    pub kind: TrayItemKind,
+    /// File names, sorted
+    pub files: Vec<String>,
+    pub total_bytes: u64,
+}
+
+/// Complete groups of a Tray folder, partial ones are only reported
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct TrayListing {
+    pub groups: Vec<TrayGroup>,
+    /// One entry per skipped group
+    pub warnings: Vec<String>,
+}
+
+/// Summary of a tray backup
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct TrayBackupReport {
+    pub groups_backed_up: usize,
+    pub files_copied: usize,
+    /// Skipped groups, from the listing or because a copy failed
+    pub warnings: Vec<String>,
+}
+
+/// List the households, lots and rooms saved in the Tray folder
+#[tauri::command(async)]
+pub fn list_tray_items(tray_dir: String) -> Result<TrayListing, String> {
+    list_groups(Path::new(&tray_dir))
+}
+
+/// Copy every complete tray group into `backup_dir`
+/// A group is copied entirely or not at all
+#[tauri::command(async)]
+pub fn backup_tray(tray_dir: String, backup_dir: String) -> Result<TrayBackupReport, String> {
+    backup_groups(Path::new(&tray_dir), Path::new(&backup_dir))
+}
+
+pub fn list_groups(tray_dir: &Path) -> Result<TrayListing, String> {
+    let entries = fs::read_dir(tray_dir)
+        .map_err(|e| format!("Failed to read Tray folder {}: {}", tray_dir.display(), e))?;
+
+    // Tray id -> (file name, extension, size)
+    let mut by_id: BTreeMap<String, Vec<(String, String, u64)>> = BTreeMap::new();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let Some((id, extension)) = tray_file_id(&path) else {
+            continue;
+        };
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        by_id.entry(id).or_default().push((
+            entry.file_name().to_string_lossy().to_string(),
+            extension,
+            size,
+        ));
+    }
+
+    let mut listing = TrayListing::default();
+    for (id, mut files) in by_id {
+        files.sort();
+        let has = |ext: &str| files.iter().any(|(_, extension, _)| extension == ext);
+
+        if !has("trayitem") {
+            listing.warnings.push(format!(
+                "Skipped tray id {}: orphaned files without a .trayitem ({})",
+                id,
+                files
+                    .iter()
+                    .map(|(name, _, _)| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+            continue;
+        }
+
+        let kind = if has("householdbinary") {
+            TrayItemKind::Household
+        } else if has("blueprint") {
+            TrayItemKind::Lot
+        } else if has("room") {
+            TrayItemKind::Room
+        } else {
+            listing.warnings.push(format!(
+                "Skipped tray id {}: .trayitem without its household, lot or room data",
+                id
+            ));
+            continue;
+        };
+
+        listing.groups.push(TrayGroup {
+            id,
+            kind,
+            total_bytes: files.iter().map(|(_, _, size)| size).sum(),
+            files: files.into_iter().map(|(name, _, _)| name).collect(),
+        });
+    }
+
+    Ok(listing)
+}
+
+pub fn backup_groups(tray_dir: &Path, backup_dir: &Path) -> Result<TrayBackupReport, String> {
+    let listing = list_groups(tray_dir)?;
+    fs::create_dir_all(backup_dir).map_err(|e| {
+        format!(
+            "Failed to create backup folder {}: {}",
+            backup_dir.display(),
+            e
+        )
+    })?;
+
+    let mut report = TrayBackupReport {
+        warnings: listing.warnings,
+        ..Default::default()
+    };
+
+    for group in listing.groups {
+        let mut copied = Vec::new();
+        let outcome = group.files.iter().try_for_each(|name| {
+            let target = backup_dir.join(name);
+            fs::copy(tray_dir.join(name), &target)?;
+            copied.push(target);
+            Ok::<_, std::io::Error>(())
+        });
+
+        match outcome {
+            Ok(()) => {
+                report.groups_backed_up += 1;
+                report.files_copied += copied.len();
+            }
+            Err(e) => {
+                // Don't leave half a household in the backup
+                for path in copied {
+                    let _ = fs::remove_file(path);
+                }
+                report
+                    .warnings
+                    .push(format!("Failed to back up tray id {}: {}", group.id, e));
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Shared id and lowercase extension of a tray file, None for anything else
+fn tray_file_id(path: &Path) -> Option<(String, String)> {
+    let extension = path.extension()?.to_string_lossy().to_lowercase();
+    if !TRAY_EXTENSIONS.contains(&extension.as_str()) {
+        return None;
+    }
+
+    let stem = path.file_stem()?.to_string_lossy();
+    let (_, id) = stem.split_once('!')?;
+    (!id.is_empty()).then(|| (id.to_lowercase(), extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const HOUSEHOLD: &str = "0x00e3f0a6a0b52ab2";
+    const LOT: &str = "0x00f1000000000042";
+
+    fn tray_fixture(tray: &Path) {
+        for name in [
+            format!("0x00000001!{}.trayitem", HOUSEHOLD),
+            format!("0x00000000!{}.householdbinary", HOUSEHOLD),
+            format!("0x00000002!{}.hhi", HOUSEHOLD),
+            format!("0x00000006!{}.sgi", HOUSEHOLD),
+            format!("0x00000007!{}.sgi", HOUSEHOLD),
+            format!("0x00000001!{}.trayitem", LOT),
+            format!("0x00000000!{}.blueprint", LOT),
+            format!("0x00000004!{}.bpi", LOT),
+            // Thumbnail left behind after the household was deleted in game
+            "0x00000006!0x00aa000000000001.sgi".to_string(),
+            // Tray item whose data file is missing
+            "0x00000001!0x00bb000000000002.trayitem".to_string(),
+            "desktop.ini".to_string(),
+        ] {
+            fs::write(tray.join(name), b"tray data").unwrap();
+        }
+    }
+
+    #[test]
+    fn groups_mixed_tray_folder() {
+        let dir = tempdir().unwrap();
+        tray_fixture(dir.path());
+
+        let listing = list_groups(dir.path()).unwrap();
+
+        assert_eq!(listing.groups.len(), 2);
+        let household = &listing.groups[0];
+        assert_eq!(household.id, HOUSEHOLD);
+        assert_eq!(household.kind, TrayItemKind::Household);
+        assert_eq!(household.files.len(), 5);
+        assert_eq!(household.total_bytes, 45);
+        let lot = &listing.groups[1];
+        assert_eq!(lot.id, LOT);
+        assert_eq!(lot.kind, TrayItemKind::Lot);
+        assert_eq!(
+            lot.files,
+            vec![
+                format!("0x00000000!{}.blueprint", LOT),
+                format!("0x00000001!{}.trayitem", LOT),
+                format!("0x00000004!{}.bpi", LOT),
+            ]
+        );
+
+        assert_eq!(listing.warnings.len(), 2);
+        assert!(listing.warnings[0].contains("0x00aa000000000001: orphaned"));
+        assert!(listing.warnings[1].contains("0x00bb000000000002: .trayitem without"));
+    }
+
+    #[test]
+    fn backs_up_complete_groups_only() {
+        let dir = tempdir().unwrap();
+        let tray = dir.path().join("Tray");
+        let backup = dir.path().join("backup");
+        fs::create_dir_all(&tray).unwrap();
+        tray_fixture(&tray);
+
+        let report = backup_groups(&tray, &backup).unwrap();
+
+        assert_eq!(report.groups_backed_up, 2);
+        assert_eq!(report.files_copied, 8);
+        assert_eq!(report.warnings.len(), 2);
+        assert_eq!(fs::read_dir(&backup).unwrap().count(), 8);
+        assert!(backup
+            .join(format!("0x00000000!{}.householdbinary", HOUSEHOLD))
+            .exists());
+        assert!(!backup.join("0x00000006!0x00aa000000000001.sgi").exists());
+    }
+}
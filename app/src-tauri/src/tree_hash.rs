@@ -0,0 +1,252 @@
+use crate::calculate_file_hash;
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// File hashes from previous tree hashes, reused while size and modification time are unchanged
+/// Directory hashes are cheap to rebuild from these, so only file hashes are cached
+#[derive(Default)]
+pub struct TreeHashCache {
+    files: Mutex<HashMap<PathBuf, CachedFileHash>>,
+}
+
+struct CachedFileHash {
+    modified: SystemTime,
+    size: u64,
+    hash: String,
+}
+
+/// Hash a directory tree into a single Merkle root
+/// Each folder hashes the sorted names and hashes of its children, so two trees with the same
+/// content produce the same root wherever they live on disk
+#[tauri::command(async)]
+pub fn compute_tree_hash(
+    cache: tauri::State<'_, TreeHashCache>,
+    root: String,
+) -> Result<String, String> {
+    let root = Path::new(&root);
+    if !root.is_dir() {
+        return Err(format!("Directory not found: {}", root.display()));
+    }
+
+    tree_hash(root, &cache)
+}
+
+pub fn tree_hash(dir: &Path, cache: &TreeHashCache) -> Result<String, String> {
+    let mut entries: Vec<fs::DirEntry> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    let children: Vec<(String, String)> = entries
+        .par_iter()
+        .map(|entry| {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let file_type = entry
+                .file_type()
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+            // Kind prefixes keep a file and a folder with the same name and hash apart
+            let node = if file_type.is_symlink() {
+                let target = fs::read_link(&path)
+                    .map_err(|e| format!("Failed to read link {}: {}", path.display(), e))?;
+                format!("link:{}", target.to_string_lossy())
+            } else if file_type.is_dir() {
+                format!("dir:{}", tree_hash(&path, cache)?)
+            } else {
+                format!("file:{}", file_hash(&path, cache)?)
+            };
+            Ok((name, node))
+        })
+        .collect::<Result<_, String>>()?;
+
+    let mut hasher = Sha256::new();
+    for (name, node) in children {
+        hasher.update(name.as_bytes());
+        hasher.update([0]);
+        hasher.update(node.as_bytes());
+        hasher.update([0]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Root `tree_hash` gives a folder holding exactly these files (relative path -> SHA-256)
+/// and folders, without the folder having to exist
+pub fn tree_hash_of_listing(files: &BTreeMap<String, String>, dirs: &[String]) -> String {
+    let mut root = ListingNode::default();
+    for dir in dirs {
+        root.folder(dir);
+    }
+    for (path, hash) in files {
+        let (parent, name) = path.rsplit_once('/').unwrap_or(("", path));
+        root.folder(parent)
+            .children
+            .insert(name.to_string(), ListingEntry::File(hash.clone()));
+    }
+    root.hash()
+}
+
+#[derive(Default)]
+struct ListingNode {
+    children: BTreeMap<String, ListingEntry>,
+}
+
+enum ListingEntry {
+    File(String),
+    Dir(ListingNode),
+}
+
+impl ListingNode {
+    /// Folder at `path` below this one, created when missing
+    fn folder(&mut self, path: &str) -> &mut ListingNode {
+        let mut node = self;
+        for name in path.split('/').filter(|name| !name.is_empty()) {
+            let entry = node
+                .children
+                .entry(name.to_string())
+                .or_insert_with(|| ListingEntry::Dir(ListingNode::default()));
+            // A folder listed where a file was wins, the hashes will simply not match
+            if let ListingEntry::File(_) = entry {
+                *entry = ListingEntry::Dir(ListingNode::default());
+            }
+            node = match entry {
+                ListingEntry::Dir(dir) => dir,
+                ListingEntry::File(_) => unreachable!(),
+            };
+        }
+        node
+    }
+
+    /// Same formula as `tree_hash`
+    fn hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        for (name, entry) in &self.children {
+            let node = match entry {
+                ListingEntry::File(hash) => format!("file:{}", hash),
+                ListingEntry::Dir(dir) => format!("dir:{}", dir.hash()),
+            };
+            hasher.update(name.as_bytes());
+            hasher.update([0]);
+            hasher.update(node.as_bytes());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+fn file_hash(path: &Path, cache: &TreeHashCache) -> Result<String, String> {
+    let metadata =
+        fs::metadata(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+    let size = metadata.len();
+
+    if let Some(cached) = cache.files.lock().unwrap().get(path) {
+        if cached.modified == modified && cached.size == size {
+            return Ok(cached.hash.clone());
+        }
+    }
+
+    let hash = calculate_file_hash(path.to_string_lossy().to_string())?;
+    cache.files.lock().unwrap().insert(
+        path.to_path_buf(),
+        CachedFileHash {
+            modified,
+            size,
+            hash: hash.clone(),
+        },
+    );
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn build_profile(root: &Path) {
+        fs::create_dir_all(root.join("Mods/CAS")).unwrap();
+        fs::create_dir_all(root.join("Mods/Empty")).unwrap();
+        fs::write(root.join("Mods/CAS/hair.package"), b"hair").unwrap();
+        fs::write(root.join("Mods/script.ts4script"), b"script").unwrap();
+        fs::write(root.join("Options.ini"), b"[options]").unwrap();
+    }
+
+    #[test]
+    fn identical_trees_hash_equal_regardless_of_location() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("profiles/default");
+        let second = dir.path().join("elsewhere/nested/copy");
+        build_profile(&first);
+        build_profile(&second);
+        let cache = TreeHashCache::default();
+
+        let hash = tree_hash(&first, &cache).unwrap();
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, tree_hash(&second, &cache).unwrap());
+        assert_eq!(
+            tree_hash(&first.join("Mods"), &cache).unwrap(),
+            tree_hash(&second.join("Mods"), &cache).unwrap()
+        );
+    }
+
+    #[test]
+    fn listing_hash_matches_folder_hash() {
+        let dir = tempdir().unwrap();
+        build_profile(dir.path());
+        let hash_of = |content: &[u8]| format!("{:x}", Sha256::digest(content));
+        let files: BTreeMap<String, String> = [
+            ("Mods/CAS/hair.package", hash_of(b"hair")),
+            ("Mods/script.ts4script", hash_of(b"script")),
+            ("Options.ini", hash_of(b"[options]")),
+        ]
+        .into_iter()
+        .map(|(path, hash)| (path.to_string(), hash))
+        .collect();
+
+        assert_eq!(
+            tree_hash_of_listing(&files, &["Mods/Empty".to_string()]),
+            tree_hash(dir.path(), &TreeHashCache::default()).unwrap()
+        );
+        assert_ne!(
+            tree_hash_of_listing(&files, &[]),
+            tree_hash(dir.path(), &TreeHashCache::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn detects_single_file_changes() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("profile");
+        build_profile(&root);
+        let cache = TreeHashCache::default();
+        let original = tree_hash(&root, &cache).unwrap();
+
+        // Same size content change, deep in the tree
+        fs::write(root.join("Mods/CAS/hair.package"), b"HAIR").unwrap();
+        let filetime = SystemTime::now() + std::time::Duration::from_secs(10);
+        fs::File::options()
+            .write(true)
+            .open(root.join("Mods/CAS/hair.package"))
+            .unwrap()
+            .set_modified(filetime)
+            .unwrap();
+        let changed = tree_hash(&root, &cache).unwrap();
+        assert_ne!(original, changed);
+
+        // Renaming a file changes the hash too, even with identical content
+        fs::rename(root.join("Options.ini"), root.join("Options.bak")).unwrap();
+        assert_ne!(changed, tree_hash(&root, &cache).unwrap());
+
+        // A fresh cache agrees with the cached result
+        assert_eq!(
+            tree_hash(&root, &TreeHashCache::default()).unwrap(),
+            tree_hash(&root, &cache).unwrap()
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use crate::cpu;
+use crate::dbpf;
+use crate::operations::{CancellationToken, OperationRegistry, CANCELLED_ERROR};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use tauri::Emitter;
+
+/// Number of packages validated per batch, progress and cancellation are checked between batches
+const VALIDATE_BATCH_SIZE: usize = 32;
+
+/// Outcome of validating one package
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PackageValidation {
+    pub path: String,
+    pub ok: bool,
+    /// Everything wrong with the package, empty when `ok`
+    pub errors: Vec<String>,
+}
+
+/// Progress payload emitted on `validate-library://progress`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ValidateLibraryProgress {
+    pub operation_id: String,
+    pub validated: usize,
+    pub total: usize,
+}
+
+/// Check that a package can be fully read: header, index and every resource's data
+#[tauri::command(async)]
+pub fn validate_package(path: String) -> PackageValidation {
+    validate(Path::new(&path))
+}
+
+/// Validate every package under `mods_dir` to find the corrupt ones
+/// Only problematic packages are returned unless `include_valid` is set
+/// Emits `validate-library://progress` after each batch, stop it with `cancel_operation(operation_id)`
+#[tauri::command(async)]
+pub fn validate_library(
+    app_handle: tauri::AppHandle,
+    registry: tauri::State<'_, OperationRegistry>,
+    mods_dir: String,
+    operation_id: String,
+    include_valid: Option<bool>,
+) -> Result<Vec<PackageValidation>, String> {
+    let operation = registry.start(&operation_id);
+
+    validate_all(
+        Path::new(&mods_dir),
+        include_valid.unwrap_or(false),
+        operation.token(),
+        |validated, total| {
+            let _ = app_handle.emit(
+                "validate-library://progress",
+                ValidateLibraryProgress {
+                    operation_id: operation_id.clone(),
+                    validated,
+                    total,
+                },
+            );
+        },
+    )
+}
+
+pub fn validate_all(
+    mods_dir: &Path,
+    include_valid: bool,
+    token: &CancellationToken,
+    on_progress: impl Fn(usize, usize),
+) -> Result<Vec<PackageValidation>, String> {
+    if !mods_dir.is_dir() {
+        return Err(format!("Directory not found: {}", mods_dir.display()));
+    }
+
+    let packages = dbpf::find_packages(mods_dir);
+    let total = packages.len();
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(cpu::recommended_workers().scanning)
+        .build()
+        .map_err(|e| format!("Failed to create validation thread pool: {}", e))?;
+
+    let mut results = Vec::new();
+    let mut validated = 0;
+
+    on_progress(0, total);
+
+    for batch in packages.chunks(VALIDATE_BATCH_SIZE) {
+        if token.is_cancelled() {
+            return Err(CANCELLED_ERROR.to_string());
+        }
+
+        let batch_results: Vec<PackageValidation> =
+            pool.install(|| batch.par_iter().map(|path| validate(path)).collect());
+        results.extend(
+            batch_results
+                .into_iter()
+                .filter(|result| include_valid || !result.ok),
+        );
+
+        validated += batch.len();
+        on_progress(validated, total);
+    }
+
+    Ok(results)
+}
+
+fn validate(path: &Path) -> PackageValidation {
+    let errors = match package_errors(path) {
+        Ok(errors) => errors,
+        Err(e) => vec![e],
+    };
+
+    PackageValidation {
+        path: path.to_string_lossy().to_string(),
+        ok: errors.is_empty(),
+        errors,
+    }
+}
+
+/// Errors of the individual resources, or the error that made the package unreadable
+fn package_errors(path: &Path) -> Result<Vec<String>, String> {
+    let entries = dbpf::read_index(path)?;
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open package {}: {}", path.display(), e))?;
+    let mut reader = BufReader::new(file);
+
+    Ok(entries
+        .iter()
+        .filter_map(|entry| dbpf::read_resource(&mut reader, entry).err())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbpf::test_support::{build_package, build_zlib_package, key};
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::tempdir;
+
+    #[test]
+    fn reports_only_the_corrupt_package() {
+        let dir = tempdir().unwrap();
+        for i in 0..4 {
+            fs::write(
+                dir.path().join(format!("valid_{}.package", i)),
+                build_package(&[(key(1, 0, i), b"fine".to_vec())]),
+            )
+            .unwrap();
+        }
+        // Index intact but the compressed data garbled on disk
+        let mut garbled = build_zlib_package(&[(key(2, 0, 1), vec![7u8; 4096])]);
+        let index_position = u32::from_le_bytes(garbled[64..68].try_into().unwrap()) as usize;
+        garbled[98..index_position].fill(0xFF);
+        let corrupt = dir.path().join("corrupt.package");
+        fs::write(&corrupt, &garbled).unwrap();
+
+        let progress_calls = AtomicUsize::new(0);
+        let problems = validate_all(dir.path(), false, &CancellationToken::default(), |_, _| {
+            progress_calls.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, corrupt.to_string_lossy());
+        assert!(!problems[0].ok);
+        assert_eq!(problems[0].errors.len(), 1);
+        assert!(problems[0].errors[0].contains("failed to inflate"));
+        assert!(progress_calls.load(Ordering::SeqCst) >= 2);
+
+        let everything =
+            validate_all(dir.path(), true, &CancellationToken::default(), |_, _| {}).unwrap();
+        assert_eq!(everything.len(), 5);
+        assert_eq!(everything.iter().filter(|result| result.ok).count(), 4);
+    }
+
+    #[test]
+    fn stops_when_cancelled() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("a.package"),
+            build_package(&[(key(1, 0, 1), b"x".to_vec())]),
+        )
+        .unwrap();
+        let token = CancellationToken::default();
+        token.cancel();
+
+        let error = validate_all(dir.path(), true, &token, |_, _| {}).unwrap_err();
+        assert_eq!(error, CANCELLED_ERROR);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::operations::{CancellationToken, OperationRegistry};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::Emitter;
+use walkdir::WalkDir;
+
+/// Entries are emitted in batches of this size to keep the event count reasonable
+const WALK_BATCH_SIZE: usize = 256;
+
+/// Filters applied while walking
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct WalkFilter {
+    /// Only report files with one of these extensions (case-insensitive, with or without the dot)
+    /// An empty list matches every file
+    pub extensions: Vec<String>,
+    /// Maximum depth below the root (1 = direct children only)
+    pub max_depth: Option<usize>,
+    /// Also report directories
+    pub include_dirs: bool,
+}
+
+/// Single file or directory found by the walk
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WalkEntry {
+    pub path: String,
+    /// Path relative to the walk root, with forward slashes
+    pub relative_path: String,
+    pub is_dir: bool,
+    /// Size in bytes (0 for directories)
+    pub size: u64,
+    pub depth: usize,
+}
+
+/// Payload of `walk://entry`
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WalkEntriesEvent {
+    pub operation_id: String,
+    pub entries: Vec<WalkEntry>,
+}
+
+/// Payload of `walk://done`, also returned by the command
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WalkSummary {
+    pub operation_id: String,
+    pub total_entries: usize,
+    pub cancelled: bool,
+}
+
+/// Walk a directory tree, streaming matching entries to the frontend as they are found
+/// Entries arrive in batches on `walk://entry`, followed by a single `walk://done`
+/// The walk can be stopped with `cancel_operation(operation_id)`
+#[tauri::command(async)]
+pub fn walk_directory(
+    app_handle: tauri::AppHandle,
+    registry: tauri::State<'_, OperationRegistry>,
+    root: String,
+    filter: Option<WalkFilter>,
+    operation_id: String,
+) -> Result<WalkSummary, String> {
+    let operation = registry.start(&operation_id);
+
+    let (total_entries, cancelled) = walk(
+        Path::new(&root),
+        &filter.unwrap_or_default(),
+        operation.token(),
+        |entries| {
+            let _ = app_handle.emit(
+                "walk://entry",
+                WalkEntriesEvent {
+                    operation_id: operation_id.clone(),
+                    entries,
+                },
+            );
+        },
+    )?;
+
+    let summary = WalkSummary {
+        operation_id,
+        total_entries,
+        cancelled,
+    };
+    let _ = app_handle.emit("walk://done", summary.clone());
+    Ok(summary)
+}
+
+/// Walk `root` and hand matching entries to `on_batch`
+/// Returns the number of entries reported and whether the walk was cancelled
+fn walk(
+    root: &Path,
+    filter: &WalkFilter,
+    token: &CancellationToken,
+    mut on_batch: impl FnMut(Vec<WalkEntry>),
+) -> Result<(usize, bool), String> {
+    if !root.is_dir() {
+        return Err(format!("Directory not found: {}", root.display()));
+    }
+
+    let extensions: Vec<String> = filter
+        .extensions
+        .iter()
+        .map(|ext| ext.trim_start_matches('.').to_lowercase())
+        .collect();
+
+    let mut walker = WalkDir::new(root).min_depth(1);
+    if let Some(max_depth) = filter.max_depth {
+        walker = walker.max_depth(max_depth);
+    }
+
+    let mut batch = Vec::with_capacity(WALK_BATCH_SIZE);
+    let mut total = 0;
+    let mut cancelled = false;
+
+    // Unreadable entries (permissions, races with deletions) are skipped rather than failing the walk
+    for entry in walker.into_iter().filter_map(|entry| entry.ok()) {
+        if token.is_cancelled() {
+            cancelled = true;
+            break;
+        }
+
+        let is_dir = entry.file_type().is_dir();
+        let matches = if is_dir {
+            filter.include_dirs
+        } else {
+            extensions.is_empty()
+                || entry
+                    .path()
+                    .extension()
+                    .map(|ext| extensions.contains(&ext.to_string_lossy().to_lowercase()))
+                    .unwrap_or(false)
+        };
+        if !matches {
+            continue;
+        }
+
+        let relative_path = entry
+            .path()
+            .strip_prefix(root)
+            .unwrap_or(entry.path())
+            .to_string_lossy()
+            .replace('\\', "/");
+
+        batch.push(WalkEntry {
+            path: entry.path().to_string_lossy().to_string(),
+            relative_path,
+            is_dir,
+            size: if is_dir {
+                0
+            } else {
+                entry.metadata().map(|m| m.len()).unwrap_or(0)
+            },
+            depth: entry.depth(),
+        });
+        total += 1;
+
+        if batch.len() == WALK_BATCH_SIZE {
+            on_batch(std::mem::replace(
+                &mut batch,
+                Vec::with_capacity(WALK_BATCH_SIZE),
+            ));
+        }
+    }
+
+    if !batch.is_empty() {
+        on_batch(batch);
+    }
+
+    Ok((total, cancelled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn fixture() -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("CAS/Hair")).unwrap();
+        for i in 0..300 {
+            fs::write(root.join(format!("CAS/Hair/hair_{}.package", i)), b"DBPF").unwrap();
+        }
+        fs::write(root.join("script.TS4SCRIPT"), b"PK").unwrap();
+        fs::write(root.join("readme.txt"), b"hi").unwrap();
+        fs::write(root.join("CAS/top.package"), b"DBPF").unwrap();
+        dir
+    }
+
+    #[test]
+    fn emits_every_matching_entry_in_batches() {
+        let dir = fixture();
+        let filter = WalkFilter {
+            extensions: vec![".package".to_string(), "ts4script".to_string()],
+            ..Default::default()
+        };
+
+        let mut batches = Vec::new();
+        let (total, cancelled) = walk(dir.path(), &filter, &CancellationToken::default(), |b| {
+            batches.push(b)
+        })
+        .unwrap();
+
+        let emitted: usize = batches.iter().map(|b| b.len()).sum();
+        assert_eq!(total, 302);
+        assert_eq!(emitted, 302);
+        assert_eq!(batches.len(), 2);
+        assert!(!cancelled);
+        assert!(batches
+            .iter()
+            .flatten()
+            .any(|e| e.relative_path == "CAS/Hair/hair_7.package" && e.size == 4 && e.depth == 3));
+    }
+
+    #[test]
+    fn respects_max_depth_and_directory_flag() {
+        let dir = fixture();
+        let filter = WalkFilter {
+            max_depth: Some(2),
+            include_dirs: true,
+            ..Default::default()
+        };
+
+        let mut entries = Vec::new();
+        walk(dir.path(), &filter, &CancellationToken::default(), |b| {
+            entries.extend(b)
+        })
+        .unwrap();
+
+        let mut names: Vec<&str> = entries.iter().map(|e| e.relative_path.as_str()).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "CAS",
+                "CAS/Hair",
+                "CAS/top.package",
+                "readme.txt",
+                "script.TS4SCRIPT"
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_when_cancelled() {
+        let dir = fixture();
+        let token = CancellationToken::default();
+        token.cancel();
+
+        let (total, cancelled) = walk(dir.path(), &WalkFilter::default(), &token, |_| {}).unwrap();
+
+        assert_eq!(total, 0);
+        assert!(cancelled);
+    }
+}